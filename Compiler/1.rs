@@ -5,13 +5,31 @@ struct Substitution {
 }
 
 // Simplified definition of DiagnosticMessage and SuggestionStyle
+// (see `errors/src/message.rs` for the translatable version)
 #[derive(Debug)]
-struct DiagnosticMessage(String);
+enum DiagnosticMessage {
+    // Already formatted, never translated
+    Eager(String),
+    // Looked up in a Fluent bundle when the diagnostic is emitted
+    FluentIdentifier {
+        id: String,
+        attr: Option<String>,
+        args: Vec<(String, String)>,
+    },
+}
 
 #[derive(Debug)]
 enum SuggestionStyle {
-    Simple,
-    Fancy,
+    // "help: msg" only, inline when short enough
+    HideCodeInline,
+    // "help: msg" only, never inline
+    HideCodeAlways,
+    // Not shown at all, only exported to tools as JSON
+    CompletelyHidden,
+    // "help: msg: `code`" inline when short enough, a code block otherwise
+    ShowCode,
+    // Always a code block
+    ShowAlways,
 }
 
 #[derive(Debug)]
@@ -46,8 +64,12 @@ fn main() {
         },
     ];
 
-    let msg = DiagnosticMessage("Example diagnostic message".to_string());
-    let style = SuggestionStyle::Simple;
+    let msg = DiagnosticMessage::FluentIdentifier {
+        id: "errors_unused_variable".to_string(),
+        attr: Some("suggestion".to_string()),
+        args: vec![("name".to_string(), "x".to_string())],
+    };
+    let style = SuggestionStyle::ShowCode;
     let applicability = Applicability::Exact;
 
     // Create a CodeSuggestion instance
@@ -59,4 +81,8 @@ fn main() {
     };
 
     println!("{:#?}", code_suggestion);
+
+    // A message formatted right away, shown as is in every language
+    let eager = DiagnosticMessage::Eager(format!("remove the unused variable `{}`", "x"));
+    println!("{:#?}", eager);
 }
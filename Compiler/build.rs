@@ -0,0 +1,21 @@
+use std::env;
+use std::process::Command;
+
+fn main() {
+    // `cargo run` puts the toolchain's `lib` directory on the library path,
+    // but the driver is also run directly, e.g. as `RUSTC_WORKSPACE_WRAPPER`,
+    // so point it at `librustc_driver` itself.
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let output = Command::new(rustc)
+        .args(["--print", "sysroot"])
+        .output()
+        .expect("failed to run rustc");
+    let sysroot = String::from_utf8(output.stdout).expect("sysroot is not UTF-8");
+    let sysroot = sysroot.trim();
+    if cfg!(unix) {
+        println!("cargo:rustc-link-arg-bins=-Wl,-rpath,{sysroot}/lib");
+    }
+    // `cargo collect-lang-items` builds dependencies with the rustc of this
+    // sysroot, whose metadata is the only one the driver can read.
+    println!("cargo:rustc-env=COLLECT_LANG_ITEMS_SYSROOT={sysroot}");
+}
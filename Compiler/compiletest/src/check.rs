@@ -0,0 +1,280 @@
+//! A stand-in front end for the UI tests. The driver in `src/main.rs` needs
+//! `rustc_private`, so instead of rustc this parses the test file with `syn`
+//! and reports a few of rustc's diagnostics, which is enough to exercise the
+//! emitter on real source files.
+
+use std::collections::HashSet;
+
+use compiler_errors::{
+    Applicability, BytePos, Diagnostic, DiagnosticMessage, IntoDiagnostic, Level, SourceFile, Span,
+};
+use syn::visit::{self, Visit};
+
+/// Types a lowercase path segment may have meant, on top of the ones
+/// declared in the file.
+const PRELUDE_TYPES: &[&str] = &[
+    "Arc", "BTreeMap", "Box", "Cell", "HashMap", "HashSet", "Option", "Rc", "RefCell", "Result",
+    "String", "Vec",
+];
+
+#[derive(compiler_errors::Diagnostic)]
+#[diag(errors_undeclared_type, code = "E0433")]
+struct UndeclaredType {
+    #[primary_span]
+    #[suggestion(code = "{candidate}", applicability = "maybe-incorrect")]
+    span: Span,
+    name: String,
+    candidate: String,
+}
+
+/// Runs every check on `file`, or reports why it does not parse.
+pub fn check_file(file: &SourceFile) -> Vec<Diagnostic> {
+    let krate = match syn::parse_file(&file.src) {
+        Ok(krate) => krate,
+        Err(errors) => {
+            return errors
+                .into_iter()
+                .map(|error| {
+                    let mut diag = Diagnostic::new(Level::Error, error.to_string());
+                    diag.set_span(span(file, error.span()));
+                    diag
+                })
+                .collect();
+        }
+    };
+
+    let mut names = Names::default();
+    names.visit_file(&krate);
+    let mut checker = Checker {
+        file,
+        names,
+        diags: Vec::new(),
+    };
+    checker.visit_file(&krate);
+    checker.diags
+}
+
+/// Converts a `proc_macro2` span into a span of `file`.
+///
+/// syn reports running out of input at the call site, an empty span at 1:1
+/// that no token has, which is put at the end of the last line instead, as
+/// rustc does with `<eof>`.
+fn span(file: &SourceFile, span: proc_macro2::Span) -> Span {
+    let call_site = proc_macro2::LineColumn { line: 1, column: 0 };
+    if span.start() == call_site && span.end() == call_site {
+        let end = file.start_pos + BytePos::from_usize(file.src.trim_end().len());
+        return Span::new(end, end);
+    }
+    let pos = |loc: proc_macro2::LineColumn| {
+        let line = loc.line.saturating_sub(1);
+        let Some(line_begin) = file.line_begin(line) else {
+            return file.end_pos();
+        };
+        let line = file.get_line(line).unwrap_or_default();
+        let offset: usize = line.chars().take(loc.column).map(char::len_utf8).sum();
+        line_begin + BytePos::from_usize(offset)
+    };
+    Span::new(pos(span.start()), pos(span.end()))
+}
+
+/// The modules, imports and types declared in the file.
+#[derive(Default)]
+struct Names {
+    modules: HashSet<String>,
+    types: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for Names {
+    fn visit_item_mod(&mut self, i: &'ast syn::ItemMod) {
+        self.modules.insert(i.ident.to_string());
+        visit::visit_item_mod(self, i);
+    }
+
+    fn visit_use_name(&mut self, i: &'ast syn::UseName) {
+        self.modules.insert(i.ident.to_string());
+    }
+
+    fn visit_use_rename(&mut self, i: &'ast syn::UseRename) {
+        self.modules.insert(i.rename.to_string());
+    }
+
+    fn visit_item_struct(&mut self, i: &'ast syn::ItemStruct) {
+        self.types.insert(i.ident.to_string());
+    }
+
+    fn visit_item_enum(&mut self, i: &'ast syn::ItemEnum) {
+        self.types.insert(i.ident.to_string());
+    }
+}
+
+struct Checker<'a> {
+    file: &'a SourceFile,
+    names: Names,
+    diags: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    /// `unused_variables`: `let` bindings never mentioned in their function.
+    fn check_unused_variables(&mut self, body: &syn::Block) {
+        let mut locals = Locals::default();
+        locals.visit_block(body);
+        let mut uses = Uses::default();
+        uses.visit_block(body);
+
+        for ident in locals.bindings {
+            let name = ident.to_string();
+            if name.starts_with('_') || uses.names.contains(&name) {
+                continue;
+            }
+            let sp = span(self.file, ident.span());
+            let mut diag = Diagnostic::new(
+                Level::Warning,
+                DiagnosticMessage::fluent("errors_unused_variable").arg("name", name.clone()),
+            );
+            diag.set_span(sp)
+                .span_suggestion(
+                    sp,
+                    DiagnosticMessage::fluent("errors_unused_variable").with_attr("suggestion"),
+                    format!("_{name}"),
+                    Applicability::MachineApplicable,
+                )
+                .note("`#[warn(unused_variables)]` on by default");
+            self.diags.push(diag);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Checker<'_> {
+    fn visit_item_fn(&mut self, i: &'ast syn::ItemFn) {
+        self.check_unused_variables(&i.block);
+        visit::visit_item_fn(self, i);
+    }
+
+    fn visit_impl_item_fn(&mut self, i: &'ast syn::ImplItemFn) {
+        self.check_unused_variables(&i.block);
+        visit::visit_impl_item_fn(self, i);
+    }
+
+    /// E0433 for `string::new()`, when a type differing only in case exists.
+    fn visit_expr_path(&mut self, i: &'ast syn::ExprPath) {
+        let segments = &i.path.segments;
+        if i.qself.is_none() && segments.len() > 1 {
+            let first = &segments[0].ident;
+            let name = first.to_string();
+            let is_known = self.names.modules.contains(&name)
+                || self.names.types.contains(&name)
+                || ["std", "core", "alloc", "crate", "self", "super", "Self"]
+                    .contains(&name.as_str());
+            let candidate = PRELUDE_TYPES
+                .iter()
+                .map(|ty| ty.to_string())
+                .chain(self.names.types.iter().cloned())
+                .find(|ty| *ty != name && ty.eq_ignore_ascii_case(&name));
+            if let (false, Some(candidate)) = (is_known, candidate) {
+                let diag = UndeclaredType {
+                    span: span(self.file, first.span()),
+                    name,
+                    candidate,
+                };
+                self.diags.push(diag.into_diagnostic());
+            }
+        }
+        visit::visit_expr_path(self, i);
+    }
+}
+
+/// The identifiers bound by the `let`s of a function, not counting nested
+/// items.
+#[derive(Default)]
+struct Locals {
+    bindings: Vec<syn::Ident>,
+}
+
+impl<'ast> Visit<'ast> for Locals {
+    fn visit_local(&mut self, i: &'ast syn::Local) {
+        let pat = match &i.pat {
+            syn::Pat::Type(pat) => &*pat.pat,
+            pat => pat,
+        };
+        if let syn::Pat::Ident(pat) = pat {
+            if pat.subpat.is_none() {
+                self.bindings.push(pat.ident.clone());
+            }
+        }
+        visit::visit_local(self, i);
+    }
+
+    fn visit_item(&mut self, _: &'ast syn::Item) {}
+}
+
+/// Every name a function body mentions, including inside macro calls and
+/// their `{name}` format arguments.
+#[derive(Default)]
+struct Uses {
+    names: HashSet<String>,
+}
+
+impl Uses {
+    fn visit_tokens(&mut self, tokens: proc_macro2::TokenStream) {
+        for tt in tokens {
+            match tt {
+                proc_macro2::TokenTree::Ident(ident) => {
+                    self.names.insert(ident.to_string());
+                }
+                proc_macro2::TokenTree::Group(group) => self.visit_tokens(group.stream()),
+                proc_macro2::TokenTree::Literal(lit) => {
+                    let lit = lit.to_string();
+                    for arg in lit.split('{').skip(1) {
+                        let name = arg.split(['}', ':']).next().unwrap_or_default();
+                        self.names.insert(name.trim().to_string());
+                    }
+                }
+                proc_macro2::TokenTree::Punct(_) => {}
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Uses {
+    fn visit_expr_path(&mut self, i: &'ast syn::ExprPath) {
+        if let Some(ident) = i.path.get_ident() {
+            self.names.insert(ident.to_string());
+        }
+        visit::visit_expr_path(self, i);
+    }
+
+    fn visit_macro(&mut self, i: &'ast syn::Macro) {
+        self.visit_tokens(i.tokens.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use compiler_errors::SourceMap;
+
+    use super::*;
+
+    fn messages(src: &str) -> Vec<String> {
+        let mut sm = SourceMap::new();
+        let file = sm.new_source_file("test.rs", src);
+        check_file(&file)
+            .iter()
+            .map(|diag| format!("{:?}", diag.message))
+            .collect()
+    }
+
+    #[test]
+    fn uses_inside_macros_count() {
+        assert!(messages("fn main() { let x = 1; println!(\"{x}\"); }").is_empty());
+        assert!(messages("fn main() { let x = 1; println!(\"{}\", x); }").is_empty());
+        assert_eq!(messages("fn main() { let x = 1; let _y = 2; }").len(), 1);
+    }
+
+    #[test]
+    fn known_modules_are_not_types() {
+        assert!(
+            messages("mod string { pub fn new() {} }\nfn main() { string::new(); }").is_empty()
+        );
+        assert_eq!(messages("fn main() { vec::new(); }").len(), 1);
+    }
+}
@@ -0,0 +1,147 @@
+//! The `//~ ERROR` annotations of a UI test, with the syntax of rustc's
+//! compiletest:
+//!
+//! ```text
+//! let x = 42; //~ WARN unused variable
+//! //~^ HELP prefix it with an underscore
+//! //~| NOTE on by default
+//! ```
+//!
+//! `//~` annotates its own line, each `^` moves one line up, and `//~|`
+//! annotates the same line as the annotation above it.
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+impl FromStr for ErrorKind {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<ErrorKind, ()> {
+        match s {
+            "ERROR" => Ok(ErrorKind::Error),
+            "WARN" | "WARNING" => Ok(ErrorKind::Warning),
+            "NOTE" => Ok(ErrorKind::Note),
+            "HELP" => Ok(ErrorKind::Help),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ErrorKind::Error => "ERROR",
+            ErrorKind::Warning => "WARN",
+            ErrorKind::Note => "NOTE",
+            ErrorKind::Help => "HELP",
+        })
+    }
+}
+
+/// A diagnostic, either expected by an annotation or actually emitted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    /// One-based, `None` for diagnostics without a span.
+    pub line_num: Option<usize>,
+    pub kind: ErrorKind,
+    pub msg: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line_num {
+            Some(line) => write!(f, "{}:{}: {}", line, self.kind, self.msg),
+            None => write!(f, "?: {}: {}", self.kind, self.msg),
+        }
+    }
+}
+
+/// A malformed annotation.
+#[derive(Debug, PartialEq, Eq)]
+pub struct AnnotationError {
+    pub line_num: usize,
+    pub msg: String,
+}
+
+/// Parses every `//~` annotation of `src`.
+pub fn load_errors(src: &str) -> Result<Vec<Error>, AnnotationError> {
+    let mut errors: Vec<Error> = Vec::new();
+    for (i, line) in src.lines().enumerate() {
+        let line_num = i + 1;
+        let Some(start) = line.find("//~") else {
+            continue;
+        };
+        let rest = &line[start + 3..];
+        let fail = |msg: &str| AnnotationError {
+            line_num,
+            msg: msg.to_string(),
+        };
+
+        let (target, rest) = if let Some(rest) = rest.strip_prefix('|') {
+            let prev = errors
+                .last()
+                .ok_or_else(|| fail("`//~|` must follow another annotation"))?;
+            (prev.line_num, rest)
+        } else {
+            let carets = rest.chars().take_while(|&c| c == '^').count();
+            let target = line_num
+                .checked_sub(carets)
+                .filter(|&line| line > 0)
+                .ok_or_else(|| fail("`//~^` points above the first line"))?;
+            (Some(target), &rest[carets..])
+        };
+
+        let rest = rest.trim_start();
+        let (kind, msg) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        let kind = kind
+            .parse()
+            .map_err(|()| fail("expected one of `ERROR`, `WARN`, `NOTE` or `HELP`"))?;
+        errors.push(Error {
+            line_num: target,
+            kind,
+            msg: msg.trim().to_string(),
+        });
+    }
+    Ok(errors)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_annotation_targets() {
+        let src = "fn main() {\n    \
+                   let x = 42; //~ WARN unused variable\n    \
+                   //~^ HELP prefix it\n    \
+                   //~| NOTE on by default\n\
+                   }\n";
+        let errors = load_errors(src).unwrap();
+        let lines: Vec<_> = errors.iter().map(|e| (e.line_num, e.kind)).collect();
+        assert_eq!(
+            lines,
+            [
+                (Some(2), ErrorKind::Warning),
+                (Some(2), ErrorKind::Help),
+                (Some(2), ErrorKind::Note),
+            ]
+        );
+        assert_eq!(errors[0].msg, "unused variable");
+    }
+
+    #[test]
+    fn rejects_unknown_kinds() {
+        let err = load_errors("//~ OOPS\n").unwrap_err();
+        assert_eq!(err.line_num, 1);
+        assert!(load_errors("//~^ ERROR up\n").is_err());
+        assert!(load_errors("//~| ERROR nothing above\n").is_err());
+    }
+}
@@ -0,0 +1,97 @@
+//! A small compiletest for the diagnostics of `compiler_errors`: every `.rs`
+//! file under `tests/ui` is checked by [`check`], its diagnostics are matched
+//! against its `//~ ERROR` annotations, and their rendered output against
+//! the `.stderr` file next to it.
+//!
+//! ```text
+//! cargo run -p compiletest -- [--bless] [FILTER]...
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub mod check;
+pub mod errors;
+mod runtest;
+
+use runtest::TestOutcome;
+
+pub struct Config {
+    /// The directory the tests are collected from.
+    pub src_base: PathBuf,
+    /// Overwrite the `.stderr` files that do not match instead of failing.
+    pub bless: bool,
+    /// Only run the tests whose path contains one of these.
+    pub filters: Vec<String>,
+}
+
+impl Config {
+    /// The configuration running every test of `Compiler/tests/ui`.
+    pub fn ui() -> Config {
+        Config {
+            src_base: Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests/ui"),
+            bless: false,
+            filters: Vec::new(),
+        }
+    }
+}
+
+/// Runs every test selected by `config`, printing a report in the format of
+/// libtest. Returns whether all of them passed.
+pub fn run_tests(config: &Config) -> io::Result<bool> {
+    let mut tests = Vec::new();
+    collect_tests(&config.src_base, &mut tests)?;
+    tests.retain(|path| {
+        let path = path.to_string_lossy();
+        config.filters.is_empty() || config.filters.iter().any(|f| path.contains(f.as_str()))
+    });
+    tests.sort();
+
+    println!("\nrunning {} tests", tests.len());
+    let mut failures = Vec::new();
+    for path in &tests {
+        let name = path
+            .strip_prefix(&config.src_base)
+            .unwrap_or(path)
+            .display();
+        match runtest::run_test(config, path)? {
+            TestOutcome::Passed => println!("test [ui] {name} ... ok"),
+            TestOutcome::Blessed => println!("test [ui] {name} ... ok (blessed)"),
+            TestOutcome::Failed(problems) => {
+                println!("test [ui] {name} ... FAILED");
+                failures.push((name.to_string(), problems));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:\n");
+        for (name, problems) in &failures {
+            println!("---- [ui] {name} ----");
+            for problem in problems {
+                println!("{problem}");
+            }
+            println!();
+        }
+    }
+    println!(
+        "\ntest result: {}. {} passed; {} failed\n",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        tests.len() - failures.len(),
+        failures.len()
+    );
+    Ok(failures.is_empty())
+}
+
+fn collect_tests(dir: &Path, tests: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_tests(&path, tests)?;
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            tests.push(path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use std::process::ExitCode;
+
+use compiletest::Config;
+
+fn main() -> ExitCode {
+    let mut config = Config::ui();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bless" => config.bless = true,
+            "--src-base" => match args.next() {
+                Some(dir) => config.src_base = dir.into(),
+                None => {
+                    eprintln!("error: `--src-base` needs a directory");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "-h" | "--help" => {
+                println!("usage: compiletest [--bless] [--src-base DIR] [FILTER]...");
+                return ExitCode::SUCCESS;
+            }
+            _ => config.filters.push(arg),
+        }
+    }
+
+    match compiletest::run_tests(&config) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use compiler_errors::{
+    Diagnostic, Handler, HumanEmitter, Level, MultiSpan, SourceMap, TranslateError, Translator,
+};
+
+use crate::errors::{self, Error, ErrorKind};
+use crate::{check, Config};
+
+pub enum TestOutcome {
+    Passed,
+    /// The `.stderr` file was out of date and has been rewritten.
+    Blessed,
+    /// Every reason the test failed.
+    Failed(Vec<String>),
+}
+
+/// Checks one UI test: its `//~` annotations against the diagnostics it
+/// produces, and its rendered output against the `.stderr` file next to it.
+pub fn run_test(config: &Config, path: &Path) -> io::Result<TestOutcome> {
+    let src = fs::read_to_string(path)?;
+    let expected_errors = match errors::load_errors(&src) {
+        Ok(errors) => errors,
+        Err(err) => {
+            return Ok(TestOutcome::Failed(vec![format!(
+                "{}:{}: malformed annotation: {}",
+                path.display(),
+                err.line_num,
+                err.msg
+            )]));
+        }
+    };
+
+    let mut sm = SourceMap::new();
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    // Like compiletest, so that `.stderr` files do not depend on where the
+    // repository is checked out.
+    let file = sm.new_source_file(format!("$DIR/{file_name}"), src);
+    let diags = check::check_file(&file);
+
+    let translator = Translator::fallback();
+    let rendered = actual_errors(&sm, &translator, &diags).and_then(|actual_errors| {
+        let mut handler = Handler::new(HumanEmitter::new(&sm, &translator));
+        for diag in diags {
+            handler.emit_diagnostic(diag);
+        }
+        Ok((actual_errors, handler.flush()?))
+    });
+    let (actual_errors, stderr) = match rendered {
+        Ok(rendered) => rendered,
+        Err(err) => return Ok(TestOutcome::Failed(vec![err.to_string()])),
+    };
+
+    let mut problems = check_expected_errors(&expected_errors, &actual_errors);
+    let stderr_path = path.with_extension("stderr");
+    let expected_stderr = match fs::read_to_string(&stderr_path) {
+        Ok(expected) => expected,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+    let mut blessed = false;
+    if expected_stderr != stderr {
+        if config.bless {
+            if stderr.is_empty() {
+                fs::remove_file(&stderr_path)?;
+            } else {
+                fs::write(&stderr_path, &stderr)?;
+            }
+            blessed = true;
+        } else {
+            problems.push(format!(
+                "{} does not match the emitted diagnostics, run with `--bless` to update it:\n{}",
+                stderr_path.display(),
+                unified_diff(&expected_stderr, &stderr)
+            ));
+        }
+    }
+
+    Ok(match (problems.is_empty(), blessed) {
+        (false, _) => TestOutcome::Failed(problems),
+        (true, true) => TestOutcome::Blessed,
+        (true, false) => TestOutcome::Passed,
+    })
+}
+
+/// Flattens the diagnostics into what the annotations can express. Children
+/// without a span belong to the line of their parent, and every suggestion,
+/// hidden or not, is a `HELP`.
+fn actual_errors(
+    sm: &SourceMap,
+    translator: &Translator,
+    diags: &[Diagnostic],
+) -> Result<Vec<Error>, TranslateError> {
+    let line_of = |msp: &MultiSpan| {
+        let span = msp.primary_span()?;
+        Some(sm.lookup_char_pos(span.lo)?.line)
+    };
+    let mut errors = Vec::new();
+    for diag in diags {
+        let line = line_of(&diag.span);
+        errors.push(Error {
+            line_num: line,
+            kind: error_kind(diag.level),
+            msg: translator.translate_message(&diag.message)?.into_owned(),
+        });
+        for child in &diag.children {
+            errors.push(Error {
+                line_num: line_of(&child.span).or(line),
+                kind: error_kind(child.level),
+                msg: translator.translate_message(&child.message)?.into_owned(),
+            });
+        }
+        for suggestion in &diag.suggestions {
+            let span = suggestion
+                .substitutions
+                .first()
+                .and_then(|substitution| substitution.bounding_span());
+            errors.push(Error {
+                line_num: span
+                    .and_then(|span| sm.lookup_char_pos(span.lo))
+                    .map(|loc| loc.line)
+                    .or(line),
+                kind: ErrorKind::Help,
+                msg: translator.translate_message(&suggestion.msg)?.into_owned(),
+            });
+        }
+    }
+    Ok(errors)
+}
+
+fn error_kind(level: Level) -> ErrorKind {
+    match level {
+        Level::Bug | Level::Error => ErrorKind::Error,
+        Level::Warning => ErrorKind::Warning,
+        Level::Note => ErrorKind::Note,
+        Level::Help => ErrorKind::Help,
+    }
+}
+
+/// Every annotation must match a diagnostic on its line whose message
+/// contains the annotated text. Errors and warnings must all be annotated,
+/// notes and helps only when the test annotates some of that kind.
+fn check_expected_errors(expected: &[Error], actual: &[Error]) -> Vec<String> {
+    let mut found = vec![false; actual.len()];
+    let mut problems = Vec::new();
+    for exp in expected {
+        let matching = actual.iter().enumerate().position(|(i, act)| {
+            !found[i]
+                && act.line_num == exp.line_num
+                && act.kind == exp.kind
+                && act.msg.contains(&exp.msg)
+        });
+        match matching {
+            Some(i) => found[i] = true,
+            None => problems.push(format!("expected {exp} not found")),
+        }
+    }
+
+    let annotated_kinds: HashSet<_> = expected.iter().map(|exp| exp.kind).collect();
+    for (act, found) in actual.iter().zip(found) {
+        let must_be_annotated = matches!(act.kind, ErrorKind::Error | ErrorKind::Warning)
+            || annotated_kinds.contains(&act.kind);
+        if !found && must_be_annotated {
+            problems.push(format!("unexpected {act}"));
+        }
+    }
+    problems
+}
+
+fn unified_diff(expected: &str, actual: &str) -> String {
+    similar::TextDiff::from_lines(expected, actual)
+        .unified_diff()
+        .header("expected", "actual")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(line: usize, kind: ErrorKind, msg: &str) -> Error {
+        Error {
+            line_num: Some(line),
+            kind,
+            msg: msg.to_string(),
+        }
+    }
+
+    #[test]
+    fn unannotated_notes_are_ignored() {
+        let expected = [error(2, ErrorKind::Warning, "unused variable")];
+        let actual = [
+            error(2, ErrorKind::Warning, "unused variable: `x`"),
+            error(2, ErrorKind::Note, "on by default"),
+        ];
+        assert!(check_expected_errors(&expected, &actual).is_empty());
+    }
+
+    #[test]
+    fn reports_missing_and_unexpected_errors() {
+        let expected = [
+            error(2, ErrorKind::Warning, "unused variable"),
+            error(3, ErrorKind::Note, "declared here"),
+        ];
+        let actual = [
+            error(3, ErrorKind::Warning, "unused variable: `x`"),
+            error(4, ErrorKind::Note, "declared here"),
+        ];
+        assert_eq!(
+            check_expected_errors(&expected, &actual),
+            [
+                "expected 2:WARN: unused variable not found",
+                "expected 3:NOTE: declared here not found",
+                "unexpected 3:WARN: unused variable: `x`",
+                "unexpected 4:NOTE: declared here",
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_is_unified() {
+        assert_eq!(
+            unified_diff("a\nb\n", "a\nc\n"),
+            "--- expected\n+++ actual\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n"
+        );
+    }
+}
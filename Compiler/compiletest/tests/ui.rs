@@ -0,0 +1,7 @@
+//! Runs `Compiler/tests/ui`, use `cargo run -p compiletest -- --bless` to
+//! update the `.stderr` files.
+
+#[test]
+fn ui() {
+    assert!(compiletest::run_tests(&compiletest::Config::ui()).unwrap());
+}
@@ -0,0 +1,6 @@
+errors_unused_variable = unbenutzte Variable: `{$name}`
+    .label = diese Variable wird nie gelesen
+    .suggestion = falls dies beabsichtigt ist, stellen Sie einen Unterstrich voran
+
+errors_mismatched_types = nicht übereinstimmende Typen
+    .label = `{$expected}` erwartet, `{$found}` gefunden
@@ -0,0 +1,13 @@
+errors_unused_variable = unused variable: `{$name}`
+    .label = this variable is never read
+    .suggestion = if this is intentional, prefix it with an underscore
+
+errors_mismatched_types = mismatched types
+    .label = expected `{$expected}`, found `{$found}`
+
+errors_only_in_fallback = this message is only in the fallback bundle
+
+errors_declared_here = `{$name}` is declared here
+
+errors_undeclared_type = failed to resolve: use of undeclared type `{$name}`
+    .suggestion = a struct with a similar name exists
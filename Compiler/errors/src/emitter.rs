@@ -0,0 +1,593 @@
+use std::fmt::Write;
+
+use crate::{
+    BytePos, CodeSuggestion, Diagnostic, DiagnosticMessage, Level, MultiSpan, SourceFile,
+    SourceMap, Span, Substitution, SubstitutionPart, SuggestionStyle, TranslateError, Translator,
+};
+
+/// How many substitutions of one suggestion are rendered, like rustc.
+const MAX_SUGGESTIONS: usize = 4;
+
+/// Renders diagnostics the way rustc prints them to a terminal, minus the
+/// colours.
+pub struct HumanEmitter<'a> {
+    sm: &'a SourceMap,
+    translator: &'a Translator,
+}
+
+/// One underlined span, resolved to a line of a file.
+struct Annotation {
+    line: usize,
+    start_col: usize,
+    end_col: usize,
+    is_primary: bool,
+    label: Option<String>,
+}
+
+impl<'a> HumanEmitter<'a> {
+    pub fn new(sm: &'a SourceMap, translator: &'a Translator) -> HumanEmitter<'a> {
+        HumanEmitter { sm, translator }
+    }
+
+    pub fn source_map(&self) -> &'a SourceMap {
+        self.sm
+    }
+
+    pub fn emit_diagnostic(&self, diag: &Diagnostic) -> Result<String, TranslateError> {
+        let margin = self.margin(diag);
+        let (primary_span, suggestions) = self.primary_span_formatted(diag)?;
+        let mut out = String::new();
+
+        self.render_header(&mut out, diag.level, diag.code.as_deref(), &diag.message)?;
+        self.render_snippet(&mut out, &primary_span, margin)?;
+
+        for child in &diag.children {
+            if child.span.is_dummy() {
+                let message = self.translator.translate_message(&child.message)?;
+                render_secondary_message(&mut out, child.level, &message, margin);
+            } else {
+                self.render_header(&mut out, child.level, None, &child.message)?;
+                self.render_snippet(&mut out, &child.span, margin)?;
+            }
+        }
+
+        for suggestion in suggestions {
+            self.render_suggestion(&mut out, suggestion, margin)?;
+        }
+        Ok(out)
+    }
+
+    /// Turns a lone, short suggestion into a label of the span it replaces,
+    /// like rustc's `primary_span_formatted`. Returns the spans to render and
+    /// the suggestions left to render on their own.
+    fn primary_span_formatted<'d>(
+        &self,
+        diag: &'d Diagnostic,
+    ) -> Result<(MultiSpan, &'d [CodeSuggestion]), TranslateError> {
+        let mut msp = diag.span.clone();
+        let Some((suggestion, part)) = sole_substitution_part(diag) else {
+            return Ok((msp, &diag.suggestions));
+        };
+        let msg = self.translator.translate_message(&suggestion.msg)?;
+        // Don't inline long messages, multi-line code, or suggestions whose
+        // style asks for them to be hidden or shown in full.
+        if msg.split_whitespace().count() >= 10
+            || part.snippet.contains('\n')
+            || matches!(
+                suggestion.style,
+                SuggestionStyle::HideCodeAlways
+                    | SuggestionStyle::CompletelyHidden
+                    | SuggestionStyle::ShowAlways
+            )
+        {
+            return Ok((msp, &diag.suggestions));
+        }
+
+        let label = if part.snippet.is_empty() || suggestion.style.hide_inline() {
+            // "help: remove the semicolon"
+            format!("{}: {}", Level::Help.to_str(), msg)
+        } else {
+            // "help: a function with a similar name exists: `foo`"
+            let capitalization = if is_case_difference(self.sm, &part.snippet, part.span) {
+                " (notice the capitalization)"
+            } else {
+                ""
+            };
+            format!(
+                "{}: {}{}: `{}`",
+                Level::Help.to_str(),
+                msg,
+                capitalization,
+                part.snippet
+            )
+        };
+        msp.push_span_label(part.span, label);
+        Ok((msp, &[]))
+    }
+
+    /// Renders a suggestion that was not turned into a label: nothing for
+    /// [`SuggestionStyle::CompletelyHidden`], the message alone for
+    /// [`SuggestionStyle::HideCodeAlways`], and the spliced code otherwise.
+    fn render_suggestion(
+        &self,
+        out: &mut String,
+        suggestion: &CodeSuggestion,
+        margin: usize,
+    ) -> Result<(), TranslateError> {
+        match suggestion.style {
+            SuggestionStyle::CompletelyHidden => {}
+            SuggestionStyle::HideCodeAlways => {
+                let msg = self.translator.translate_message(&suggestion.msg)?;
+                render_secondary_message(out, Level::Help, &msg, margin);
+            }
+            SuggestionStyle::HideCodeInline
+            | SuggestionStyle::ShowCode
+            | SuggestionStyle::ShowAlways => {
+                let msg = self.translator.translate_message(&suggestion.msg)?;
+                writeln!(out, "{}: {}", Level::Help.to_str(), msg).unwrap();
+                for substitution in suggestion.substitutions.iter().take(MAX_SUGGESTIONS) {
+                    self.render_substitution(out, substitution, margin);
+                }
+                let others = suggestion
+                    .substitutions
+                    .len()
+                    .saturating_sub(MAX_SUGGESTIONS);
+                if others > 0 {
+                    let plural = if others == 1 { "" } else { "s" };
+                    writeln!(out, "{:margin$}and {others} other candidate{plural}", "").unwrap();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the lines touched by `substitution` with every part spliced
+    /// in, underlining replaced code with `~` and inserted code with `+`.
+    fn render_substitution(&self, out: &mut String, substitution: &Substitution, margin: usize) {
+        let Some(bounding_span) = substitution.bounding_span() else {
+            return;
+        };
+        let (Some(lo), Some(hi)) = (
+            self.sm.lookup_char_pos(bounding_span.lo),
+            self.sm.lookup_char_pos(bounding_span.hi),
+        ) else {
+            return;
+        };
+        let file = &lo.file;
+        let Some(first_line) = file.line_begin(lo.line - 1) else {
+            return;
+        };
+        let Some(last_line) = file.get_line(hi.line - 1) else {
+            return;
+        };
+        let last_line_end =
+            file.line_begin(hi.line - 1).unwrap() + BytePos::from_usize(last_line.len());
+        let offset = |pos: BytePos| (pos - file.start_pos).to_usize();
+
+        let mut parts: Vec<_> = substitution.parts.iter().collect();
+        parts.sort_by_key(|part| part.span.lo);
+
+        // The spliced code, and the byte ranges of it that come from a part.
+        let mut code = String::new();
+        let mut highlights = Vec::new();
+        let mut prev_hi = first_line;
+        for part in parts {
+            if part.span.lo < prev_hi || part.span.hi > last_line_end {
+                return;
+            }
+            code.push_str(&file.src[offset(prev_hi)..offset(part.span.lo)]);
+            let start = code.len();
+            code.push_str(&part.snippet);
+            highlights.push((start, code.len(), part.span.is_empty()));
+            prev_hi = part.span.hi;
+        }
+        code.push_str(&file.src[offset(prev_hi)..offset(last_line_end)]);
+
+        writeln!(out, "{:margin$} |", "").unwrap();
+        let mut line_start = 0;
+        for (i, line) in code.split('\n').enumerate() {
+            let line_end = line_start + line.len();
+            writeln!(out, "{:>margin$} | {}", lo.line + i, line).unwrap();
+
+            let mut underline = String::new();
+            for &(start, end, is_insertion) in &highlights {
+                let (start, end) = (start.max(line_start), end.min(line_end));
+                if start >= end {
+                    continue;
+                }
+                let col = code[line_start..start].chars().count();
+                let width = code[start..end].chars().count();
+                let padding = col.saturating_sub(underline.chars().count());
+                underline.extend(std::iter::repeat_n(' ', padding));
+                let marker = if is_insertion { '+' } else { '~' };
+                underline.extend(std::iter::repeat_n(marker, width));
+            }
+            if !underline.is_empty() {
+                writeln!(out, "{:margin$} | {}", "", underline).unwrap();
+            }
+            line_start = line_end + 1;
+        }
+    }
+
+    fn render_header(
+        &self,
+        out: &mut String,
+        level: Level,
+        code: Option<&str>,
+        message: &DiagnosticMessage,
+    ) -> Result<(), TranslateError> {
+        let message = self.translator.translate_message(message)?;
+        match code {
+            Some(code) => writeln!(out, "{}[{}]: {}", level.to_str(), code, message).unwrap(),
+            None => writeln!(out, "{}: {}", level.to_str(), message).unwrap(),
+        }
+        Ok(())
+    }
+
+    fn render_snippet(
+        &self,
+        out: &mut String,
+        msp: &MultiSpan,
+        margin: usize,
+    ) -> Result<(), TranslateError> {
+        let Some(primary) = msp
+            .primary_span()
+            .or_else(|| msp.span_labels.first().map(|(sp, _)| *sp))
+        else {
+            return Ok(());
+        };
+        let Some(loc) = self.sm.lookup_char_pos(primary.lo) else {
+            return Ok(());
+        };
+        writeln!(
+            out,
+            "{:margin$}--> {}:{}:{}",
+            "",
+            loc.file.name,
+            loc.line,
+            loc.col + 1
+        )
+        .unwrap();
+        writeln!(out, "{:margin$} |", "").unwrap();
+
+        let mut annotations = Vec::new();
+        for (span, label) in &msp.span_labels {
+            let is_primary = msp.primary_spans.contains(span);
+            annotations.extend(self.annotation(&loc.file, *span, is_primary, Some(label))?);
+        }
+        for &span in &msp.primary_spans {
+            if !msp.span_labels.iter().any(|(sp, _)| *sp == span) {
+                annotations.extend(self.annotation(&loc.file, span, true, None)?);
+            }
+        }
+        // Stable, so that several labels on one span keep their order.
+        annotations.sort_by_key(|ann| (ann.line, ann.start_col));
+
+        let mut prev_line = None;
+        for ann in &annotations {
+            if prev_line != Some(ann.line) {
+                if prev_line.is_some_and(|prev| prev + 1 < ann.line) {
+                    writeln!(out, "...").unwrap();
+                }
+                let text = loc.file.get_line(ann.line - 1).unwrap_or_default();
+                writeln!(out, "{:>margin$} | {}", ann.line, text).unwrap();
+                prev_line = Some(ann.line);
+            }
+            let marker = if ann.is_primary { "^" } else { "-" };
+            let underline = marker.repeat(ann.end_col.saturating_sub(ann.start_col).max(1));
+            let line = format!(
+                "{:margin$} | {:start$}{}",
+                "",
+                "",
+                underline,
+                start = ann.start_col
+            );
+            match &ann.label {
+                Some(label) => writeln!(out, "{} {}", line, label).unwrap(),
+                None => writeln!(out, "{}", line).unwrap(),
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolves `span` to an [`Annotation`], unless it lies outside of `file`.
+    fn annotation(
+        &self,
+        file: &SourceFile,
+        span: Span,
+        is_primary: bool,
+        label: Option<&DiagnosticMessage>,
+    ) -> Result<Option<Annotation>, TranslateError> {
+        let (Some(lo), Some(hi)) = (
+            self.sm.lookup_char_pos(span.lo),
+            self.sm.lookup_char_pos(span.hi),
+        ) else {
+            return Ok(None);
+        };
+        if lo.file.start_pos != file.start_pos {
+            return Ok(None);
+        }
+        let label = label
+            .map(|label| self.translator.translate_message(label))
+            .transpose()?;
+        // Multi-line spans are underlined up to the end of their first line.
+        let end_col = if hi.line == lo.line {
+            hi.col
+        } else {
+            lo.file
+                .get_line(lo.line - 1)
+                .map_or(lo.col + 1, |line| line.chars().count())
+        };
+        Ok(Some(Annotation {
+            line: lo.line,
+            start_col: lo.col,
+            end_col,
+            is_primary,
+            label: label.map(|label| label.into_owned()),
+        }))
+    }
+
+    /// Width of the widest line number the diagnostic will print.
+    fn margin(&self, diag: &Diagnostic) -> usize {
+        let span_lines = std::iter::once(&diag.span)
+            .chain(diag.children.iter().map(|child| &child.span))
+            .flat_map(|msp| {
+                msp.primary_spans
+                    .iter()
+                    .chain(msp.span_labels.iter().map(|(sp, _)| sp))
+            })
+            .filter_map(|sp| self.sm.lookup_char_pos(sp.lo))
+            .map(|loc| loc.line);
+        // Suggestions may print more lines than they replace.
+        let suggestion_lines = diag
+            .suggestions
+            .iter()
+            .flat_map(|suggestion| &suggestion.substitutions)
+            .flat_map(|substitution| &substitution.parts)
+            .filter_map(|part| {
+                let loc = self.sm.lookup_char_pos(part.span.hi)?;
+                Some(loc.line + part.snippet.matches('\n').count())
+            });
+        span_lines
+            .chain(suggestion_lines)
+            .map(|line| line.to_string().len())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Renders a message without a span as `= level: message`, below a `|`
+/// separator.
+fn render_secondary_message(out: &mut String, level: Level, message: &str, margin: usize) {
+    if !out.ends_with(" |\n") {
+        writeln!(out, "{:margin$} |", "").unwrap();
+    }
+    writeln!(out, "{:margin$} = {}: {}", "", level.to_str(), message).unwrap();
+}
+
+/// The only part of the only substitution of the only suggestion of `diag`.
+fn sole_substitution_part(diag: &Diagnostic) -> Option<(&CodeSuggestion, &SubstitutionPart)> {
+    let [suggestion] = &diag.suggestions[..] else {
+        return None;
+    };
+    let [substitution] = &suggestion.substitutions[..] else {
+        return None;
+    };
+    let [part] = &substitution.parts[..] else {
+        return None;
+    };
+    Some((suggestion, part))
+}
+
+/// Whether the original and suggested code only differ in the case of
+/// letters that look alike in both cases, which warrants a "notice the
+/// capitalization" hint, as explored in `icd_2/`.
+fn is_case_difference(sm: &SourceMap, suggested: &str, sp: Span) -> bool {
+    let Ok(found) = sm.span_to_snippet(sp) else {
+        return false;
+    };
+    let ascii_confusables = ['c', 'f', 'i', 'k', 'o', 's', 'u', 'v', 'w', 'x', 'y', 'z'];
+    // All the chars that differ in capitalization are confusable (above):
+    let confusable = std::iter::zip(found.chars(), suggested.chars())
+        .filter(|(f, s)| f != s)
+        .all(|(f, s)| ascii_confusables.contains(&f) || ascii_confusables.contains(&s));
+    confusable && found.to_lowercase() == suggested.to_lowercase()
+        // We sometimes suggest the same thing we already have, be defensive
+        // against that here.
+        && found != suggested
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::Applicability;
+
+    const SOURCE: &str = "fn main() {\n    let x = 42;\n}\n";
+
+    fn unused_variable(sm: &mut SourceMap, style: SuggestionStyle) -> Diagnostic {
+        let file = sm.new_source_file("main.rs", SOURCE);
+        let span = sm.span_of(&file, "x").unwrap();
+        let mut diag = Diagnostic::new(
+            Level::Warning,
+            DiagnosticMessage::fluent("errors_unused_variable").arg("name", "x"),
+        );
+        diag.set_span(span).span_suggestion_with_style(
+            span,
+            DiagnosticMessage::fluent("errors_unused_variable").with_attr("suggestion"),
+            "_x",
+            Applicability::MachineApplicable,
+            style,
+        );
+        diag
+    }
+
+    fn emit(sm: &SourceMap, diag: &Diagnostic) -> String {
+        HumanEmitter::new(sm, &Translator::fallback())
+            .emit_diagnostic(diag)
+            .unwrap()
+    }
+
+    #[test]
+    fn inline_suggestion_in_two_locales() {
+        let mut sm = SourceMap::new();
+        let diag = unused_variable(&mut sm, SuggestionStyle::ShowCode);
+
+        let german = Translator::with_locale(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("locales"),
+            "de".parse().unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            emit(&sm, &diag),
+            "warning: unused variable: `x`\n \
+             --> main.rs:2:9\n  \
+             |\n\
+             2 |     let x = 42;\n  \
+             |         ^ help: if this is intentional, prefix it with an underscore: `_x`\n"
+        );
+        assert_eq!(
+            HumanEmitter::new(&sm, &german)
+                .emit_diagnostic(&diag)
+                .unwrap(),
+            "warning: unbenutzte Variable: `x`\n \
+             --> main.rs:2:9\n  \
+             |\n\
+             2 |     let x = 42;\n  \
+             |         ^ help: falls dies beabsichtigt ist, stellen Sie einen Unterstrich voran: `_x`\n"
+        );
+    }
+
+    #[test]
+    fn short_suggestion_hides_code() {
+        let mut sm = SourceMap::new();
+        let diag = unused_variable(&mut sm, SuggestionStyle::HideCodeInline);
+        assert!(emit(&sm, &diag)
+            .ends_with("^ help: if this is intentional, prefix it with an underscore\n"));
+    }
+
+    #[test]
+    fn hidden_suggestion_shows_message_only() {
+        let mut sm = SourceMap::new();
+        let diag = unused_variable(&mut sm, SuggestionStyle::HideCodeAlways);
+        assert_eq!(
+            emit(&sm, &diag),
+            "warning: unused variable: `x`\n \
+             --> main.rs:2:9\n  \
+             |\n\
+             2 |     let x = 42;\n  \
+             |         ^\n  \
+             |\n  \
+             = help: if this is intentional, prefix it with an underscore\n"
+        );
+    }
+
+    #[test]
+    fn tool_only_suggestion_is_not_rendered() {
+        let mut sm = SourceMap::new();
+        let diag = unused_variable(&mut sm, SuggestionStyle::CompletelyHidden);
+        assert!(!emit(&sm, &diag).contains("help"));
+    }
+
+    #[test]
+    fn verbose_suggestion_renders_code_block() {
+        let mut sm = SourceMap::new();
+        let diag = unused_variable(&mut sm, SuggestionStyle::ShowAlways);
+        assert_eq!(
+            emit(&sm, &diag),
+            "warning: unused variable: `x`\n \
+             --> main.rs:2:9\n  \
+             |\n\
+             2 |     let x = 42;\n  \
+             |         ^\n\
+             help: if this is intentional, prefix it with an underscore\n  \
+             |\n\
+             2 |     let _x = 42;\n  \
+             |         ~~\n"
+        );
+    }
+
+    #[test]
+    fn multipart_suggestion_marks_insertions() {
+        let mut sm = SourceMap::new();
+        let file = sm.new_source_file("main.rs", "fn main() {\n    let v = vec![1];\n}\n");
+        let vec = sm.span_of(&file, "vec![1]").unwrap();
+        let mut diag = Diagnostic::new(Level::Error, "mismatched types");
+        diag.set_span(vec).multipart_suggestion(
+            "consider borrowing here",
+            vec![
+                (Span::new(vec.lo, vec.lo), "&".to_string()),
+                (
+                    Span::new(vec.lo, vec.lo + BytePos(3)),
+                    "Vec::from".to_string(),
+                ),
+            ],
+            Applicability::MaybeIncorrect,
+        );
+        assert!(emit(&sm, &diag).ends_with(
+            "help: consider borrowing here\n  \
+             |\n\
+             2 |     let v = &Vec::from![1];\n  \
+             |             +~~~~~~~~~\n"
+        ));
+    }
+
+    #[test]
+    fn long_messages_are_not_inlined() {
+        let mut sm = SourceMap::new();
+        let file = sm.new_source_file("main.rs", SOURCE);
+        let span = sm.span_of(&file, "42").unwrap();
+        let mut diag = Diagnostic::new(Level::Error, "mismatched types");
+        diag.set_span(span).span_suggestion(
+            span,
+            "you can convert an `i32` to a `u64` and panic if the converted value doesn't fit",
+            "42_u64",
+            Applicability::MachineApplicable,
+        );
+        assert!(emit(&sm, &diag).contains("help: you can convert"));
+        assert!(emit(&sm, &diag).ends_with("2 |     let x = 42_u64;\n  |             ~~~~~~\n"));
+    }
+
+    #[test]
+    fn notices_the_capitalization() {
+        let mut sm = SourceMap::new();
+        let file = sm.new_source_file("main.rs", "fn main() {\n    let s = string::new();\n}\n");
+        let span = sm.span_of(&file, "string").unwrap();
+        let mut diag = Diagnostic::new(Level::Error, "failed to resolve: use of undeclared type");
+        diag.set_span(span).span_suggestion(
+            span,
+            "a struct with a similar name exists",
+            "String",
+            Applicability::MaybeIncorrect,
+        );
+        assert!(emit(&sm, &diag).ends_with(
+            "^^^^^^ help: a struct with a similar name exists (notice the capitalization): `String`\n"
+        ));
+    }
+
+    #[test]
+    fn diagnostic_with_label_and_note() {
+        let mut sm = SourceMap::new();
+        let mut diag = unused_variable(&mut sm, SuggestionStyle::ShowCode);
+        let span = diag.span.primary_span().unwrap();
+        diag.span_label(
+            span,
+            DiagnosticMessage::fluent("errors_unused_variable").with_attr("label"),
+        )
+        .note("`#[warn(unused_variables)]` on by default");
+
+        assert_eq!(
+            emit(&sm, &diag),
+            "warning: unused variable: `x`\n \
+             --> main.rs:2:9\n  \
+             |\n\
+             2 |     let x = 42;\n  \
+             |         ^ this variable is never read\n  \
+             |         ^ help: if this is intentional, prefix it with an underscore: `_x`\n  \
+             |\n  \
+             = note: `#[warn(unused_variables)]` on by default\n"
+        );
+    }
+}
@@ -0,0 +1,310 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
+
+use crate::{CodeSuggestion, Diagnostic, HumanEmitter, IntoDiagnostic, Level, TranslateError};
+
+/// Limits on how many errors a [`Handler`] accepts.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct HandlerFlags {
+    /// Panic when the N-th error is emitted, like rustc's
+    /// `-Z treat-err-as-bug=N`, to get a backtrace of where it came from.
+    pub treat_err_as_bug: Option<NonZeroUsize>,
+    /// Drop every error after the N-th one.
+    pub max_errors: Option<NonZeroUsize>,
+}
+
+/// Buffers diagnostics until [`Handler::flush`], so that they can be
+/// deduplicated and printed in source order.
+pub struct Handler<'a> {
+    emitter: HumanEmitter<'a>,
+    flags: HandlerFlags,
+    buffered: Vec<Diagnostic>,
+    /// Hashes of every diagnostic accepted so far, see [`diagnostic_hash`].
+    emitted_diagnostics: HashSet<u64>,
+    err_count: usize,
+    warn_count: usize,
+    suppressed_err_count: usize,
+}
+
+/// Two diagnostics are considered the same when they say the same thing
+/// about the same spans and suggest the same fixes.
+fn diagnostic_hash(diag: &Diagnostic) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (&diag.message, &diag.span, &diag.suggestions).hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<'a> Handler<'a> {
+    pub fn new(emitter: HumanEmitter<'a>) -> Handler<'a> {
+        Handler::with_flags(emitter, HandlerFlags::default())
+    }
+
+    pub fn with_flags(emitter: HumanEmitter<'a>, flags: HandlerFlags) -> Handler<'a> {
+        Handler {
+            emitter,
+            flags,
+            buffered: Vec::new(),
+            emitted_diagnostics: HashSet::new(),
+            err_count: 0,
+            warn_count: 0,
+            suppressed_err_count: 0,
+        }
+    }
+
+    pub fn emit(&mut self, diag: impl IntoDiagnostic) {
+        self.emit_diagnostic(diag.into_diagnostic());
+    }
+
+    pub fn emit_diagnostic(&mut self, mut diag: Diagnostic) {
+        self.remove_noop_suggestions(&mut diag);
+        if !self.emitted_diagnostics.insert(diagnostic_hash(&diag)) {
+            return;
+        }
+
+        if diag.is_error()
+            && self
+                .flags
+                .max_errors
+                .is_some_and(|max| self.err_count >= max.get())
+        {
+            self.suppressed_err_count += 1;
+            return;
+        }
+
+        let is_error = diag.is_error();
+        match diag.level {
+            Level::Bug | Level::Error => self.err_count += 1,
+            Level::Warning => self.warn_count += 1,
+            Level::Note | Level::Help => {}
+        }
+        self.buffered.push(diag);
+
+        if is_error
+            && self
+                .flags
+                .treat_err_as_bug
+                .is_some_and(|n| self.err_count >= n.get())
+        {
+            self.panic_due_to_treat_err_as_bug();
+        }
+    }
+
+    /// Drops the substitutions that would leave the code as it is, and the
+    /// suggestions left without any substitution.
+    fn remove_noop_suggestions(&self, diag: &mut Diagnostic) {
+        let sm = self.emitter.source_map();
+        let is_noop = |suggestion: &CodeSuggestion, i: usize| {
+            suggestion.substitutions[i].parts.iter().all(|part| {
+                sm.span_to_snippet(part.span)
+                    .is_ok_and(|snippet| snippet == part.snippet)
+            })
+        };
+        for suggestion in &mut diag.suggestions {
+            let keep: Vec<bool> = (0..suggestion.substitutions.len())
+                .map(|i| !is_noop(suggestion, i))
+                .collect();
+            let mut keep = keep.into_iter();
+            suggestion.substitutions.retain(|_| keep.next().unwrap());
+        }
+        diag.suggestions
+            .retain(|suggestion| !suggestion.substitutions.is_empty());
+    }
+
+    fn panic_due_to_treat_err_as_bug(&mut self) {
+        let n = self.flags.treat_err_as_bug.unwrap().get();
+        let rendered = self.flush().unwrap_or_else(|e| format!("{e}\n"));
+        match n {
+            1 => panic!("aborting due to `-Z treat-err-as-bug=1`\n{rendered}"),
+            n => panic!("aborting after {n} errors due to `-Z treat-err-as-bug={n}`\n{rendered}"),
+        }
+    }
+
+    pub fn err_count(&self) -> usize {
+        self.err_count
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.err_count > 0
+    }
+
+    /// Renders every buffered diagnostic, sorted by the position of its
+    /// primary span, followed by the error count.
+    pub fn flush(&mut self) -> Result<String, TranslateError> {
+        let mut buffered = std::mem::take(&mut self.buffered);
+        // Diagnostics without a span are about the whole crate and go last.
+        buffered.sort_by_key(|diag| {
+            let span = diag.span.primary_span();
+            (span.is_none(), span.map(|span| span.lo))
+        });
+
+        let mut out = String::new();
+        for diag in &buffered {
+            out.push_str(&self.emitter.emit_diagnostic(diag)?);
+            out.push('\n');
+        }
+        out.push_str(&self.error_count_summary());
+        Ok(out)
+    }
+
+    fn error_count_summary(&self) -> String {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        let warnings = format!(
+            "{} warning{} emitted",
+            self.warn_count,
+            plural(self.warn_count)
+        );
+        let mut summary = match (self.err_count, self.warn_count) {
+            (0, 0) => String::new(),
+            (0, _) => format!("warning: {warnings}\n"),
+            (errors, 0) => format!(
+                "error: aborting due to {errors} previous error{}\n",
+                plural(errors)
+            ),
+            (errors, _) => {
+                format!(
+                    "error: aborting due to {errors} previous error{}; {warnings}\n",
+                    plural(errors)
+                )
+            }
+        };
+        if self.suppressed_err_count > 0 {
+            let max = self.flags.max_errors.map_or(0, NonZeroUsize::get);
+            summary.push_str(&format!(
+                "note: {} more error{} not shown after reaching the limit of {max}\n",
+                self.suppressed_err_count,
+                plural(self.suppressed_err_count),
+            ));
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Applicability, BytePos, SourceMap, Span, Translator};
+
+    fn source_map() -> (SourceMap, Span, Span) {
+        let mut sm = SourceMap::new();
+        let file = sm.new_source_file(
+            "main.rs",
+            "fn main() {\n    let a = 1;\n    let b = 2;\n}\n",
+        );
+        let a = sm.span_of(&file, "a = 1").unwrap();
+        let a = Span::new(a.lo, a.lo + BytePos(1));
+        let b = sm.span_of(&file, "b").unwrap();
+        (sm, a, b)
+    }
+
+    fn error(span: Span, msg: &'static str) -> Diagnostic {
+        let mut diag = Diagnostic::new(Level::Error, msg);
+        diag.set_span(span);
+        diag
+    }
+
+    #[test]
+    fn deduplicates_identical_diagnostics() {
+        let (sm, a, b) = source_map();
+        let translator = Translator::fallback();
+        let mut handler = Handler::new(HumanEmitter::new(&sm, &translator));
+
+        handler.emit_diagnostic(error(a, "first"));
+        handler.emit_diagnostic(error(a, "first"));
+        handler.emit_diagnostic(error(b, "first"));
+        handler.emit_diagnostic(error(a, "second"));
+        assert_eq!(handler.err_count(), 3);
+    }
+
+    #[test]
+    fn drops_suggestions_identical_to_snippet() {
+        let (sm, a, _) = source_map();
+        let translator = Translator::fallback();
+        let mut handler = Handler::new(HumanEmitter::new(&sm, &translator));
+
+        let mut diag = error(a, "unused variable: `a`");
+        diag.span_suggestion(a, "remove it", "a", Applicability::MaybeIncorrect);
+        diag.span_suggestion(
+            a,
+            "prefix it with an underscore",
+            "_a",
+            Applicability::MachineApplicable,
+        );
+        handler.emit_diagnostic(diag);
+
+        let [diag] = &handler.buffered[..] else {
+            panic!("expected one diagnostic")
+        };
+        let [suggestion] = &diag.suggestions[..] else {
+            panic!("expected one suggestion")
+        };
+        assert_eq!(suggestion.substitutions[0].parts[0].snippet, "_a");
+    }
+
+    #[test]
+    fn flush_sorts_by_primary_span() {
+        let (sm, a, b) = source_map();
+        let translator = Translator::fallback();
+        let mut handler = Handler::new(HumanEmitter::new(&sm, &translator));
+
+        handler.emit_diagnostic(Diagnostic::new(Level::Warning, "crate-level"));
+        handler.emit_diagnostic(error(b, "on b"));
+        handler.emit_diagnostic(error(a, "on a"));
+
+        let out = handler.flush().unwrap();
+        let headers: Vec<_> = out
+            .lines()
+            .filter(|line| !line.starts_with(' ') && !line.is_empty())
+            .collect();
+        assert_eq!(
+            headers,
+            [
+                "error: on a",
+                "2 |     let a = 1;",
+                "error: on b",
+                "3 |     let b = 2;",
+                "warning: crate-level",
+                "error: aborting due to 2 previous errors; 1 warning emitted",
+            ]
+        );
+        assert!(handler.flush().unwrap().ends_with("1 warning emitted\n"));
+    }
+
+    #[test]
+    fn max_errors_suppresses_the_rest() {
+        let (sm, a, b) = source_map();
+        let translator = Translator::fallback();
+        let flags = HandlerFlags {
+            max_errors: NonZeroUsize::new(1),
+            ..HandlerFlags::default()
+        };
+        let mut handler = Handler::with_flags(HumanEmitter::new(&sm, &translator), flags);
+
+        handler.emit_diagnostic(error(a, "first"));
+        handler.emit_diagnostic(error(b, "second"));
+        handler.emit_diagnostic(error(b, "third"));
+        assert_eq!(handler.err_count(), 1);
+        assert!(handler.flush().unwrap().ends_with(
+            "error: aborting due to 1 previous error\n\
+             note: 2 more errors not shown after reaching the limit of 1\n"
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "aborting after 2 errors due to `-Z treat-err-as-bug=2`")]
+    fn treat_err_as_bug_panics_on_nth_error() {
+        let (sm, a, b) = source_map();
+        let translator = Translator::fallback();
+        let flags = HandlerFlags {
+            treat_err_as_bug: NonZeroUsize::new(2),
+            ..HandlerFlags::default()
+        };
+        let mut handler = Handler::with_flags(HumanEmitter::new(&sm, &translator), flags);
+
+        handler.emit_diagnostic(Diagnostic::new(Level::Warning, "not an error"));
+        handler.emit_diagnostic(error(a, "first"));
+        handler.emit_diagnostic(error(a, "first"));
+        handler.emit_diagnostic(error(b, "second"));
+    }
+}
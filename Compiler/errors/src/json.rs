@@ -0,0 +1,256 @@
+//! Emits diagnostics as one JSON object per line, in the format of rustc's
+//! `--error-format=json`, so that tools like rustfix can apply suggestions.
+
+use serde::Serialize;
+
+use crate::{
+    Applicability, CodeSuggestion, Diagnostic, HumanEmitter, Level, MultiSpan, SourceMap, Span,
+    TranslateError, Translator,
+};
+
+pub struct JsonEmitter<'a> {
+    sm: &'a SourceMap,
+    translator: &'a Translator,
+}
+
+#[derive(Serialize)]
+struct JsonDiagnostic {
+    #[serde(rename = "$message_type")]
+    message_type: &'static str,
+    message: String,
+    code: Option<DiagnosticCode>,
+    level: &'static str,
+    spans: Vec<DiagnosticSpan>,
+    children: Vec<JsonChild>,
+    /// The diagnostic as the [`HumanEmitter`] would print it.
+    rendered: Option<String>,
+}
+
+#[derive(Serialize)]
+struct JsonChild {
+    message: String,
+    code: Option<DiagnosticCode>,
+    level: &'static str,
+    spans: Vec<DiagnosticSpan>,
+    children: Vec<JsonChild>,
+    rendered: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DiagnosticCode {
+    code: String,
+    explanation: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+struct DiagnosticSpan {
+    file_name: String,
+    /// Byte offsets from the start of the file.
+    byte_start: usize,
+    byte_end: usize,
+    /// One-based.
+    line_start: usize,
+    line_end: usize,
+    /// One-based, in chars.
+    column_start: usize,
+    column_end: usize,
+    is_primary: bool,
+    text: Vec<DiagnosticSpanLine>,
+    label: Option<String>,
+    /// The code a suggestion replaces this span with.
+    suggested_replacement: Option<String>,
+    suggestion_applicability: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DiagnosticSpanLine {
+    text: String,
+    /// One-based, in chars, covering the part of the line within the span.
+    highlight_start: usize,
+    highlight_end: usize,
+}
+
+impl<'a> JsonEmitter<'a> {
+    pub fn new(sm: &'a SourceMap, translator: &'a Translator) -> JsonEmitter<'a> {
+        JsonEmitter { sm, translator }
+    }
+
+    /// Renders `diag` as a single line of JSON, ending with a newline.
+    ///
+    /// Unlike the [`HumanEmitter`], every suggestion is exported, whatever
+    /// its [`SuggestionStyle`](crate::SuggestionStyle), as a `help` child
+    /// carrying the replacement code in its spans.
+    pub fn emit_diagnostic(&self, diag: &Diagnostic) -> Result<String, TranslateError> {
+        let rendered = HumanEmitter::new(self.sm, self.translator).emit_diagnostic(diag)?;
+
+        let mut children = Vec::new();
+        for child in &diag.children {
+            children.push(JsonChild {
+                message: self
+                    .translator
+                    .translate_message(&child.message)?
+                    .into_owned(),
+                code: None,
+                level: child.level.to_str(),
+                spans: self.spans_from_multispan(&child.span)?,
+                children: Vec::new(),
+                rendered: None,
+            });
+        }
+        for suggestion in &diag.suggestions {
+            children.push(JsonChild {
+                message: self
+                    .translator
+                    .translate_message(&suggestion.msg)?
+                    .into_owned(),
+                code: None,
+                level: Level::Help.to_str(),
+                spans: self.spans_from_suggestion(suggestion),
+                children: Vec::new(),
+                rendered: None,
+            });
+        }
+
+        let json = JsonDiagnostic {
+            message_type: "diagnostic",
+            message: self
+                .translator
+                .translate_message(&diag.message)?
+                .into_owned(),
+            code: diag.code.clone().map(|code| DiagnosticCode {
+                code,
+                explanation: None,
+            }),
+            level: diag.level.to_str(),
+            spans: self.spans_from_multispan(&diag.span)?,
+            children,
+            rendered: Some(rendered),
+        };
+        let mut out = serde_json::to_string(&json).expect("diagnostics serialize to JSON");
+        out.push('\n');
+        Ok(out)
+    }
+
+    fn spans_from_multispan(&self, msp: &MultiSpan) -> Result<Vec<DiagnosticSpan>, TranslateError> {
+        let mut spans = Vec::new();
+        for &span in &msp.primary_spans {
+            if !msp.span_labels.iter().any(|(sp, _)| *sp == span) {
+                spans.extend(self.span(span, true, None, None));
+            }
+        }
+        for (span, label) in &msp.span_labels {
+            let label = self.translator.translate_message(label)?.into_owned();
+            let is_primary = msp.primary_spans.contains(span);
+            spans.extend(self.span(*span, is_primary, Some(label), None));
+        }
+        Ok(spans)
+    }
+
+    fn spans_from_suggestion(&self, suggestion: &CodeSuggestion) -> Vec<DiagnosticSpan> {
+        suggestion
+            .substitutions
+            .iter()
+            .flat_map(|substitution| &substitution.parts)
+            .filter_map(|part| {
+                let replacement = (part.snippet.clone(), suggestion.applicability);
+                self.span(part.span, true, None, Some(replacement))
+            })
+            .collect()
+    }
+
+    fn span(
+        &self,
+        span: Span,
+        is_primary: bool,
+        label: Option<String>,
+        suggestion: Option<(String, Applicability)>,
+    ) -> Option<DiagnosticSpan> {
+        let lo = self.sm.lookup_char_pos(span.lo)?;
+        let hi = self.sm.lookup_char_pos(span.hi)?;
+        let file = &lo.file;
+
+        let text = (lo.line..=hi.line)
+            .filter_map(|line| {
+                let text = file.get_line(line - 1)?;
+                let highlight_start = if line == lo.line { lo.col + 1 } else { 1 };
+                let highlight_end = if line == hi.line {
+                    hi.col + 1
+                } else {
+                    text.chars().count() + 1
+                };
+                Some(DiagnosticSpanLine {
+                    text: text.to_string(),
+                    highlight_start,
+                    highlight_end,
+                })
+            })
+            .collect();
+
+        let (suggested_replacement, suggestion_applicability) = match suggestion {
+            Some((code, applicability)) => (Some(code), Some(format!("{applicability:?}"))),
+            None => (None, None),
+        };
+        Some(DiagnosticSpan {
+            file_name: file.name.clone(),
+            byte_start: (span.lo - file.start_pos).to_usize(),
+            byte_end: (span.hi - file.start_pos).to_usize(),
+            line_start: lo.line,
+            line_end: hi.line,
+            column_start: lo.col + 1,
+            column_end: hi.col + 1,
+            is_primary,
+            text,
+            label,
+            suggested_replacement,
+            suggestion_applicability,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    #[test]
+    fn tool_only_suggestions_are_exported() {
+        let mut sm = SourceMap::new();
+        let file = sm.new_source_file("main.rs", "fn main() {\n    let x = 42;\n}\n");
+        let span = sm.span_of(&file, "x").unwrap();
+        let translator = Translator::fallback();
+
+        let mut diag = Diagnostic::new(Level::Warning, "unused variable: `x`");
+        diag.set_span(span).tool_only_span_suggestion(
+            span,
+            "if this is intentional, prefix it with an underscore",
+            "_x",
+            Applicability::MachineApplicable,
+        );
+
+        let human = HumanEmitter::new(&sm, &translator)
+            .emit_diagnostic(&diag)
+            .unwrap();
+        assert!(!human.contains("help"));
+
+        let json = JsonEmitter::new(&sm, &translator)
+            .emit_diagnostic(&diag)
+            .unwrap();
+        let json: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["level"], "warning");
+        assert_eq!(json["rendered"], human);
+        assert_eq!(json["spans"][0]["line_start"], 2);
+        assert_eq!(json["spans"][0]["column_start"], 9);
+        assert_eq!(json["spans"][0]["text"][0]["text"], "    let x = 42;");
+
+        let help = &json["children"][0];
+        assert_eq!(help["level"], "help");
+        assert_eq!(help["spans"][0]["byte_start"], 20);
+        assert_eq!(help["spans"][0]["byte_end"], 21);
+        assert_eq!(help["spans"][0]["suggested_replacement"], "_x");
+        assert_eq!(
+            help["spans"][0]["suggestion_applicability"],
+            "MachineApplicable"
+        );
+    }
+}
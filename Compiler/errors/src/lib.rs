@@ -0,0 +1,477 @@
+//! A small re-implementation of the parts of `rustc_errors` that the scratch
+//! files next to this crate (`1.rs`, `2.rs`, `3.rs`, `splice/`, `icd_2/`)
+//! take apart: diagnostics, code suggestions and how they are rendered.
+
+pub use compiler_macros::{Diagnostic, Subdiagnostic};
+
+mod emitter;
+mod handler;
+mod json;
+mod message;
+mod span;
+mod translation;
+
+pub use emitter::HumanEmitter;
+pub use handler::{Handler, HandlerFlags};
+pub use json::JsonEmitter;
+pub use message::{
+    DiagnosticArgValue, DiagnosticArgs, DiagnosticMessage, FluentId, IntoDiagnosticArg,
+};
+pub use span::{BytePos, Loc, SourceFile, SourceMap, Span, SpanSnippetError};
+pub use translation::{
+    fallback_fluent_bundle, fluent_bundle, FluentBundle, LanguageIdentifier, TranslateError,
+    TranslationBundleError, Translator, FALLBACK_LOCALE,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Level {
+    Bug,
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+impl Level {
+    pub fn to_str(self) -> &'static str {
+        match self {
+            Level::Bug => "error: internal compiler error",
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Note => "note",
+            Level::Help => "help",
+        }
+    }
+}
+
+/// How confident we are that a suggestion is what the user meant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Applicability {
+    /// The suggestion is definitely what the user intended and can be
+    /// applied automatically.
+    MachineApplicable,
+    /// The suggestion may be what the user intended, but it is uncertain.
+    MaybeIncorrect,
+    /// The suggestion contains placeholders like `(...)` or `{ /* fields */ }`.
+    HasPlaceholders,
+    /// The applicability of the suggestion is unknown.
+    Unspecified,
+}
+
+/// How a suggestion is shown to the user. Every style, even
+/// [`SuggestionStyle::CompletelyHidden`], is exported to tools by the
+/// [`JsonEmitter`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SuggestionStyle {
+    /// Only the message is shown, as a label when the suggestion is short
+    /// enough, e.g. "help: add a semicolon".
+    HideCodeInline,
+    /// Only the message is shown, always as a separate `help:`.
+    HideCodeAlways,
+    /// Nothing is shown, the suggestion is only for tools such as rustfix.
+    CompletelyHidden,
+    /// The code is shown inline, "help: try: `foo`", when the suggestion is
+    /// short enough, and as a code block otherwise.
+    ShowCode,
+    /// The code is always shown as a code block below the diagnostic.
+    ShowAlways,
+}
+
+impl SuggestionStyle {
+    /// Whether the code must not be shown in an inline label.
+    pub fn hide_inline(self) -> bool {
+        !matches!(self, SuggestionStyle::ShowCode)
+    }
+}
+
+/// Replace the code at `span` with `snippet`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SubstitutionPart {
+    pub span: Span,
+    pub snippet: String,
+}
+
+/// One way of fixing the code, made of one or more replacements.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Substitution {
+    pub parts: Vec<SubstitutionPart>,
+}
+
+impl Substitution {
+    /// The smallest span covering every part of the substitution.
+    pub fn bounding_span(&self) -> Option<Span> {
+        let lo = self.parts.iter().map(|part| part.span.lo).min()?;
+        let hi = self.parts.iter().map(|part| part.span.hi).max()?;
+        Some(Span::new(lo, hi))
+    }
+
+    /// Returns the code of [`Substitution::bounding_span`] with every part
+    /// spliced in.
+    pub fn splice(&self, sm: &SourceMap) -> Result<String, SpanSnippetError> {
+        let Some(bounding_span) = self.bounding_span() else {
+            return Ok(String::new());
+        };
+        let original = sm.span_to_snippet(bounding_span)?;
+
+        let mut parts: Vec<_> = self.parts.iter().collect();
+        parts.sort_by_key(|part| part.span.lo);
+
+        let mut result = String::new();
+        let mut prev_hi = bounding_span.lo;
+        for part in parts {
+            let lo = (part.span.lo.max(prev_hi) - bounding_span.lo).to_usize();
+            let prev = (prev_hi - bounding_span.lo).to_usize();
+            result.push_str(
+                original
+                    .get(prev..lo)
+                    .ok_or(SpanSnippetError::MalformedForSourcemap(part.span))?,
+            );
+            result.push_str(&part.snippet);
+            prev_hi = prev_hi.max(part.span.hi);
+        }
+        result.push_str(
+            original
+                .get((prev_hi - bounding_span.lo).to_usize()..)
+                .ok_or(SpanSnippetError::MalformedForSourcemap(bounding_span))?,
+        );
+        Ok(result)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CodeSuggestion {
+    /// Each substitution is an alternative way of fixing the code.
+    pub substitutions: Vec<Substitution>,
+    pub msg: DiagnosticMessage,
+    pub style: SuggestionStyle,
+    pub applicability: Applicability,
+}
+
+/// The spans a diagnostic points at: the primary spans, underlined with
+/// `^`, and any number of labelled spans.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MultiSpan {
+    pub primary_spans: Vec<Span>,
+    pub span_labels: Vec<(Span, DiagnosticMessage)>,
+}
+
+impl MultiSpan {
+    pub fn new() -> MultiSpan {
+        MultiSpan::default()
+    }
+
+    pub fn from_span(span: Span) -> MultiSpan {
+        MultiSpan {
+            primary_spans: vec![span],
+            span_labels: Vec::new(),
+        }
+    }
+
+    pub fn push_span_label(&mut self, span: Span, label: impl Into<DiagnosticMessage>) {
+        self.span_labels.push((span, label.into()));
+    }
+
+    pub fn primary_span(&self) -> Option<Span> {
+        self.primary_spans.first().copied()
+    }
+
+    pub fn is_dummy(&self) -> bool {
+        self.primary_spans.is_empty() && self.span_labels.is_empty()
+    }
+}
+
+impl From<Span> for MultiSpan {
+    fn from(span: Span) -> MultiSpan {
+        MultiSpan::from_span(span)
+    }
+}
+
+/// A note or help attached to a [`Diagnostic`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SubDiagnostic {
+    pub level: Level,
+    pub message: DiagnosticMessage,
+    pub span: MultiSpan,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub code: Option<String>,
+    pub message: DiagnosticMessage,
+    pub span: MultiSpan,
+    pub children: Vec<SubDiagnostic>,
+    pub suggestions: Vec<CodeSuggestion>,
+}
+
+impl Diagnostic {
+    pub fn new(level: Level, message: impl Into<DiagnosticMessage>) -> Diagnostic {
+        Diagnostic {
+            level,
+            code: None,
+            message: message.into(),
+            span: MultiSpan::new(),
+            children: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    pub fn code(&mut self, code: impl Into<String>) -> &mut Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    pub fn set_span(&mut self, span: impl Into<MultiSpan>) -> &mut Self {
+        let span = span.into();
+        self.span.primary_spans = span.primary_spans;
+        self.span.span_labels.extend(span.span_labels);
+        self
+    }
+
+    pub fn span_label(&mut self, span: Span, label: impl Into<DiagnosticMessage>) -> &mut Self {
+        self.span.push_span_label(span, label);
+        self
+    }
+
+    fn sub(
+        &mut self,
+        level: Level,
+        message: impl Into<DiagnosticMessage>,
+        span: MultiSpan,
+    ) -> &mut Self {
+        self.children.push(SubDiagnostic {
+            level,
+            message: message.into(),
+            span,
+        });
+        self
+    }
+
+    pub fn note(&mut self, message: impl Into<DiagnosticMessage>) -> &mut Self {
+        self.sub(Level::Note, message, MultiSpan::new())
+    }
+
+    pub fn span_note(
+        &mut self,
+        span: impl Into<MultiSpan>,
+        message: impl Into<DiagnosticMessage>,
+    ) -> &mut Self {
+        self.sub(Level::Note, message, span.into())
+    }
+
+    pub fn help(&mut self, message: impl Into<DiagnosticMessage>) -> &mut Self {
+        self.sub(Level::Help, message, MultiSpan::new())
+    }
+
+    pub fn span_help(
+        &mut self,
+        span: impl Into<MultiSpan>,
+        message: impl Into<DiagnosticMessage>,
+    ) -> &mut Self {
+        self.sub(Level::Help, message, span.into())
+    }
+
+    /// Suggests replacing `span` with `suggestion`.
+    pub fn span_suggestion(
+        &mut self,
+        span: Span,
+        msg: impl Into<DiagnosticMessage>,
+        suggestion: impl Into<String>,
+        applicability: Applicability,
+    ) -> &mut Self {
+        self.span_suggestion_with_style(
+            span,
+            msg,
+            suggestion,
+            applicability,
+            SuggestionStyle::ShowCode,
+        )
+    }
+
+    pub fn span_suggestion_with_style(
+        &mut self,
+        span: Span,
+        msg: impl Into<DiagnosticMessage>,
+        suggestion: impl Into<String>,
+        applicability: Applicability,
+        style: SuggestionStyle,
+    ) -> &mut Self {
+        self.multipart_suggestion_with_style(
+            msg,
+            vec![(span, suggestion.into())],
+            applicability,
+            style,
+        )
+    }
+
+    /// Like [`Diagnostic::span_suggestion`], but only shows the message when
+    /// the suggestion is rendered inline.
+    pub fn span_suggestion_short(
+        &mut self,
+        span: Span,
+        msg: impl Into<DiagnosticMessage>,
+        suggestion: impl Into<String>,
+        applicability: Applicability,
+    ) -> &mut Self {
+        self.span_suggestion_with_style(
+            span,
+            msg,
+            suggestion,
+            applicability,
+            SuggestionStyle::HideCodeInline,
+        )
+    }
+
+    /// Never shows the code of the suggestion, only its message.
+    pub fn span_suggestion_hidden(
+        &mut self,
+        span: Span,
+        msg: impl Into<DiagnosticMessage>,
+        suggestion: impl Into<String>,
+        applicability: Applicability,
+    ) -> &mut Self {
+        self.span_suggestion_with_style(
+            span,
+            msg,
+            suggestion,
+            applicability,
+            SuggestionStyle::HideCodeAlways,
+        )
+    }
+
+    /// Always shows the suggestion as a code block, even when it is short.
+    pub fn span_suggestion_verbose(
+        &mut self,
+        span: Span,
+        msg: impl Into<DiagnosticMessage>,
+        suggestion: impl Into<String>,
+        applicability: Applicability,
+    ) -> &mut Self {
+        self.span_suggestion_with_style(
+            span,
+            msg,
+            suggestion,
+            applicability,
+            SuggestionStyle::ShowAlways,
+        )
+    }
+
+    /// Adds a suggestion that is not shown to the user, only to tools.
+    pub fn tool_only_span_suggestion(
+        &mut self,
+        span: Span,
+        msg: impl Into<DiagnosticMessage>,
+        suggestion: impl Into<String>,
+        applicability: Applicability,
+    ) -> &mut Self {
+        self.span_suggestion_with_style(
+            span,
+            msg,
+            suggestion,
+            applicability,
+            SuggestionStyle::CompletelyHidden,
+        )
+    }
+
+    /// Suggests replacing every span of `suggestion` at once.
+    pub fn multipart_suggestion(
+        &mut self,
+        msg: impl Into<DiagnosticMessage>,
+        suggestion: Vec<(Span, String)>,
+        applicability: Applicability,
+    ) -> &mut Self {
+        self.multipart_suggestion_with_style(
+            msg,
+            suggestion,
+            applicability,
+            SuggestionStyle::ShowCode,
+        )
+    }
+
+    pub fn multipart_suggestion_with_style(
+        &mut self,
+        msg: impl Into<DiagnosticMessage>,
+        suggestion: Vec<(Span, String)>,
+        applicability: Applicability,
+        style: SuggestionStyle,
+    ) -> &mut Self {
+        let parts = suggestion
+            .into_iter()
+            .map(|(span, snippet)| SubstitutionPart { span, snippet })
+            .collect();
+        self.suggestions.push(CodeSuggestion {
+            substitutions: vec![Substitution { parts }],
+            msg: msg.into(),
+            style,
+            applicability,
+        });
+        self
+    }
+
+    pub fn subdiagnostic(&mut self, sub: impl AddToDiagnostic) -> &mut Self {
+        sub.add_to_diagnostic(self);
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self.level, Level::Bug | Level::Error)
+    }
+}
+
+/// Turns a type into a [`Diagnostic`], usually via `#[derive(Diagnostic)]`.
+pub trait IntoDiagnostic {
+    fn into_diagnostic(self) -> Diagnostic;
+}
+
+/// Attaches a type to an existing [`Diagnostic`] as a label, note, help or
+/// suggestion, usually via `#[derive(Subdiagnostic)]`.
+pub trait AddToDiagnostic {
+    fn add_to_diagnostic(self, diag: &mut Diagnostic);
+}
+
+impl<T: AddToDiagnostic> AddToDiagnostic for Option<T> {
+    fn add_to_diagnostic(self, diag: &mut Diagnostic) {
+        if let Some(sub) = self {
+            sub.add_to_diagnostic(diag);
+        }
+    }
+}
+
+impl<T: AddToDiagnostic> AddToDiagnostic for Vec<T> {
+    fn add_to_diagnostic(self, diag: &mut Diagnostic) {
+        for sub in self {
+            sub.add_to_diagnostic(diag);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splice_multipart_substitution() {
+        let mut sm = SourceMap::new();
+        let file = sm.new_source_file("a.rs", "let v: Vec<i32> = Vec::new();");
+        let ty = sm.span_of(&file, "i32").unwrap();
+        let call = sm.span_of(&file, "new").unwrap();
+
+        let substitution = Substitution {
+            parts: vec![
+                SubstitutionPart {
+                    span: call,
+                    snippet: "with_capacity(8)".to_string(),
+                },
+                SubstitutionPart {
+                    span: ty,
+                    snippet: "u8".to_string(),
+                },
+            ],
+        };
+        assert_eq!(substitution.bounding_span(), Some(ty.to(call)));
+        assert_eq!(
+            substitution.splice(&sm).unwrap(),
+            "u8> = Vec::with_capacity(8)"
+        );
+    }
+}
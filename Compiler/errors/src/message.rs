@@ -0,0 +1,140 @@
+use std::borrow::Cow;
+
+/// Identifier of a Fluent message or attribute, e.g. `errors_unused_variable`.
+pub type FluentId = Cow<'static, str>;
+
+/// A value that can be interpolated into a Fluent message as `{ $name }`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DiagnosticArgValue {
+    Str(Cow<'static, str>),
+    Number(i64),
+}
+
+/// Converts a value into a [`DiagnosticArgValue`] so it can be passed to
+/// [`DiagnosticMessage::arg`].
+pub trait IntoDiagnosticArg {
+    fn into_diagnostic_arg(self) -> DiagnosticArgValue;
+}
+
+impl IntoDiagnosticArg for DiagnosticArgValue {
+    fn into_diagnostic_arg(self) -> DiagnosticArgValue {
+        self
+    }
+}
+
+impl IntoDiagnosticArg for &'static str {
+    fn into_diagnostic_arg(self) -> DiagnosticArgValue {
+        DiagnosticArgValue::Str(Cow::Borrowed(self))
+    }
+}
+
+impl IntoDiagnosticArg for String {
+    fn into_diagnostic_arg(self) -> DiagnosticArgValue {
+        DiagnosticArgValue::Str(Cow::Owned(self))
+    }
+}
+
+macro_rules! impl_into_diagnostic_arg_number {
+    ($($ty:ty),*) => {
+        $(
+            impl IntoDiagnosticArg for $ty {
+                fn into_diagnostic_arg(self) -> DiagnosticArgValue {
+                    DiagnosticArgValue::Number(self as i64)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_diagnostic_arg_number!(i8, i16, i32, i64, isize, u8, u16, u32, usize);
+
+/// Named arguments of a Fluent message, in insertion order.
+pub type DiagnosticArgs = Vec<(Cow<'static, str>, DiagnosticArgValue)>;
+
+/// The text of a diagnostic, a label or a suggestion.
+///
+/// Messages are either already formatted (`Eager`) or refer to a Fluent
+/// message that is only looked up when the diagnostic is emitted, so the
+/// same diagnostic can be rendered in whichever locale the user asked for.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DiagnosticMessage {
+    /// Non-translatable text, used as is.
+    Eager(Cow<'static, str>),
+    /// Identifier of a Fluent message, optionally of one of its attributes,
+    /// together with the arguments the message is formatted with.
+    FluentIdentifier {
+        id: FluentId,
+        attr: Option<FluentId>,
+        args: DiagnosticArgs,
+    },
+}
+
+impl DiagnosticMessage {
+    pub fn fluent(id: impl Into<FluentId>) -> DiagnosticMessage {
+        DiagnosticMessage::FluentIdentifier {
+            id: id.into(),
+            attr: None,
+            args: Vec::new(),
+        }
+    }
+
+    /// Refers to the attribute `attr` of the same Fluent message, e.g. the
+    /// `.suggestion` of `errors_unused_variable`. Has no effect on eager
+    /// messages.
+    pub fn with_attr(self, attr: impl Into<FluentId>) -> DiagnosticMessage {
+        match self {
+            DiagnosticMessage::FluentIdentifier { id, args, .. } => {
+                DiagnosticMessage::FluentIdentifier {
+                    id,
+                    attr: Some(attr.into()),
+                    args,
+                }
+            }
+            eager => eager,
+        }
+    }
+
+    /// Adds the argument `$name` to a Fluent message, replacing any previous
+    /// value. Has no effect on eager messages.
+    pub fn arg(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        value: impl IntoDiagnosticArg,
+    ) -> DiagnosticMessage {
+        if let DiagnosticMessage::FluentIdentifier { args, .. } = &mut self {
+            let name = name.into();
+            let value = value.into_diagnostic_arg();
+            match args.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, v)) => *v = value,
+                None => args.push((name, value)),
+            }
+        }
+        self
+    }
+
+    /// Adds every argument of `args`, see [`DiagnosticMessage::arg`].
+    pub fn with_args(self, args: &DiagnosticArgs) -> DiagnosticMessage {
+        args.iter().fold(self, |msg, (name, value)| {
+            msg.arg(name.clone(), value.clone())
+        })
+    }
+
+    pub fn as_eager(&self) -> Option<&str> {
+        match self {
+            DiagnosticMessage::Eager(s) => Some(s),
+            DiagnosticMessage::FluentIdentifier { .. } => None,
+        }
+    }
+}
+
+impl From<&'static str> for DiagnosticMessage {
+    fn from(s: &'static str) -> Self {
+        DiagnosticMessage::Eager(Cow::Borrowed(s))
+    }
+}
+
+impl From<String> for DiagnosticMessage {
+    fn from(s: String) -> Self {
+        DiagnosticMessage::Eager(Cow::Owned(s))
+    }
+}
@@ -0,0 +1,280 @@
+use std::fmt;
+use std::ops::{Add, Sub};
+use std::rc::Rc;
+
+/// A byte offset into the [`SourceMap`].
+///
+/// Keep this small (currently 32-bits), as diagnostics carry a lot of them.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct BytePos(pub u32);
+
+impl BytePos {
+    #[inline(always)]
+    pub fn from_usize(n: usize) -> BytePos {
+        BytePos(n as u32)
+    }
+
+    #[inline(always)]
+    pub fn to_usize(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Add for BytePos {
+    type Output = BytePos;
+
+    #[inline(always)]
+    fn add(self, rhs: BytePos) -> BytePos {
+        BytePos(self.0 + rhs.0)
+    }
+}
+
+impl Sub for BytePos {
+    type Output = BytePos;
+
+    #[inline(always)]
+    fn sub(self, rhs: BytePos) -> BytePos {
+        BytePos(self.0 - rhs.0)
+    }
+}
+
+impl fmt::Debug for BytePos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BytePos({})", self.0)
+    }
+}
+
+/// A half-open range of bytes `lo..hi` in the [`SourceMap`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug, Default)]
+pub struct Span {
+    pub lo: BytePos,
+    pub hi: BytePos,
+}
+
+impl Span {
+    pub fn new(lo: BytePos, hi: BytePos) -> Span {
+        if lo <= hi {
+            Span { lo, hi }
+        } else {
+            Span { lo: hi, hi: lo }
+        }
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.lo == self.hi
+    }
+
+    /// Returns the smallest span covering both `self` and `other`.
+    pub fn to(self, other: Span) -> Span {
+        Span::new(self.lo.min(other.lo), self.hi.max(other.hi))
+    }
+
+    pub fn contains(self, other: Span) -> bool {
+        self.lo <= other.lo && other.hi <= self.hi
+    }
+}
+
+/// A single file registered in a [`SourceMap`].
+#[derive(Debug)]
+pub struct SourceFile {
+    pub name: String,
+    pub src: String,
+    /// The position of the first byte of this file in the [`SourceMap`].
+    pub start_pos: BytePos,
+    /// Offsets (relative to `start_pos`) of the first byte of every line.
+    lines: Vec<usize>,
+}
+
+impl SourceFile {
+    fn new(name: String, src: String, start_pos: BytePos) -> SourceFile {
+        let lines = std::iter::once(0)
+            .chain(src.match_indices('\n').map(|(i, _)| i + 1))
+            .filter(|&i| i < src.len() || i == 0)
+            .collect();
+        SourceFile {
+            name,
+            src,
+            start_pos,
+            lines,
+        }
+    }
+
+    pub fn end_pos(&self) -> BytePos {
+        self.start_pos + BytePos::from_usize(self.src.len())
+    }
+
+    pub fn count_lines(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns the zero-based index of the line containing `pos`.
+    pub fn lookup_line(&self, pos: BytePos) -> usize {
+        let offset = (pos - self.start_pos).to_usize();
+        match self.lines.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        }
+    }
+
+    /// Returns the position of the first byte of the zero-based line `line_index`.
+    pub fn line_begin(&self, line_index: usize) -> Option<BytePos> {
+        let begin = *self.lines.get(line_index)?;
+        Some(self.start_pos + BytePos::from_usize(begin))
+    }
+
+    /// Returns the text of the zero-based line `line_index`, without the trailing newline.
+    pub fn get_line(&self, line_index: usize) -> Option<&str> {
+        let begin = *self.lines.get(line_index)?;
+        let end = self
+            .lines
+            .get(line_index + 1)
+            .copied()
+            .unwrap_or(self.src.len());
+        Some(self.src[begin..end].trim_end_matches(['\n', '\r']))
+    }
+}
+
+/// A source location, with one-based `line` and zero-based `col` (in chars).
+#[derive(Debug, Clone)]
+pub struct Loc {
+    pub file: Rc<SourceFile>,
+    pub line: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpanSnippetError {
+    /// The span does not lie within any registered file.
+    NotInSourceMap(Span),
+    /// The span starts and ends in different files.
+    DistinctSources(Span),
+    /// The span does not fall on char boundaries.
+    MalformedForSourcemap(Span),
+}
+
+impl fmt::Display for SpanSnippetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpanSnippetError::NotInSourceMap(sp) => {
+                write!(f, "span {:?} is not in the source map", sp)
+            }
+            SpanSnippetError::DistinctSources(sp) => {
+                write!(f, "span {:?} crosses source files", sp)
+            }
+            SpanSnippetError::MalformedForSourcemap(sp) => {
+                write!(f, "span {:?} does not fall on char boundaries", sp)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SpanSnippetError {}
+
+/// Owns every [`SourceFile`] diagnostics can point into, laid out
+/// back-to-back in one global [`BytePos`] space.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<Rc<SourceFile>>,
+}
+
+impl SourceMap {
+    pub fn new() -> SourceMap {
+        SourceMap::default()
+    }
+
+    pub fn new_source_file(
+        &mut self,
+        name: impl Into<String>,
+        src: impl Into<String>,
+    ) -> Rc<SourceFile> {
+        // Leave a one byte gap between files so that an empty span at the
+        // end of one file is not mistaken for the start of the next.
+        let start_pos = self
+            .files
+            .last()
+            .map_or(BytePos(0), |f| f.end_pos() + BytePos(1));
+        let file = Rc::new(SourceFile::new(name.into(), src.into(), start_pos));
+        self.files.push(file.clone());
+        file
+    }
+
+    pub fn files(&self) -> &[Rc<SourceFile>] {
+        &self.files
+    }
+
+    pub fn lookup_source_file(&self, pos: BytePos) -> Option<Rc<SourceFile>> {
+        self.files
+            .iter()
+            .find(|f| f.start_pos <= pos && pos <= f.end_pos())
+            .cloned()
+    }
+
+    pub fn lookup_char_pos(&self, pos: BytePos) -> Option<Loc> {
+        let file = self.lookup_source_file(pos)?;
+        let line = file.lookup_line(pos);
+        let line_start = file.lines[line];
+        let offset = (pos - file.start_pos).to_usize();
+        let col = file.src.get(line_start..offset)?.chars().count();
+        Some(Loc {
+            file,
+            line: line + 1,
+            col,
+        })
+    }
+
+    /// Returns a span covering `needle`'s first occurrence in `file`, if any.
+    pub fn span_of(&self, file: &SourceFile, needle: &str) -> Option<Span> {
+        let offset = file.src.find(needle)?;
+        let lo = file.start_pos + BytePos::from_usize(offset);
+        Some(Span::new(lo, lo + BytePos::from_usize(needle.len())))
+    }
+
+    pub fn span_to_snippet(&self, sp: Span) -> Result<String, SpanSnippetError> {
+        let file = self
+            .lookup_source_file(sp.lo)
+            .ok_or(SpanSnippetError::NotInSourceMap(sp))?;
+        if sp.hi > file.end_pos() {
+            return Err(SpanSnippetError::DistinctSources(sp));
+        }
+        let lo = (sp.lo - file.start_pos).to_usize();
+        let hi = (sp.hi - file.start_pos).to_usize();
+        file.src
+            .get(lo..hi)
+            .map(str::to_string)
+            .ok_or(SpanSnippetError::MalformedForSourcemap(sp))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_positions_across_files() {
+        let mut sm = SourceMap::new();
+        let a = sm.new_source_file("a.rs", "fn main() {\n    let x = 1;\n}\n");
+        let b = sm.new_source_file("b.rs", "struct S;\n");
+
+        let sp = sm.span_of(&a, "x").unwrap();
+        let loc = sm.lookup_char_pos(sp.lo).unwrap();
+        assert_eq!((loc.file.name.as_str(), loc.line, loc.col), ("a.rs", 2, 8));
+        assert_eq!(sm.span_to_snippet(sp).unwrap(), "x");
+
+        let sp = sm.span_of(&b, "S").unwrap();
+        let loc = sm.lookup_char_pos(sp.lo).unwrap();
+        assert_eq!((loc.file.name.as_str(), loc.line, loc.col), ("b.rs", 1, 7));
+        assert_eq!(b.get_line(0), Some("struct S;"));
+    }
+
+    #[test]
+    fn snippet_rejects_spans_across_files() {
+        let mut sm = SourceMap::new();
+        let a = sm.new_source_file("a.rs", "fn a() {}");
+        let b = sm.new_source_file("b.rs", "fn b() {}");
+        let sp = Span::new(a.start_pos, b.end_pos());
+        assert_eq!(
+            sm.span_to_snippet(sp),
+            Err(SpanSnippetError::DistinctSources(sp))
+        );
+    }
+}
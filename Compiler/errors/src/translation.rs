@@ -0,0 +1,297 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use fluent_bundle::{FluentArgs, FluentError, FluentResource, FluentValue};
+use fluent_syntax::parser::ParserError;
+pub use unic_langid::LanguageIdentifier;
+
+use crate::message::{DiagnosticArgValue, DiagnosticArgs, DiagnosticMessage};
+
+pub type FluentBundle = fluent_bundle::FluentBundle<FluentResource>;
+
+/// Locale of the resources compiled into the crate.
+pub const FALLBACK_LOCALE: &str = "en-US";
+
+/// The fallback resources, always available even when no locale directory is.
+const FALLBACK_FTL: &str = include_str!("../locales/en-US/errors.ftl");
+
+#[derive(Debug)]
+pub enum TranslationBundleError {
+    /// The directory of the requested locale could not be read.
+    ReadLocaleDir(PathBuf, io::Error),
+    /// A `.ftl` file could not be read.
+    ReadFtl(PathBuf, io::Error),
+    /// A `.ftl` file is not valid Fluent.
+    ParseFtl(PathBuf, Vec<ParserError>),
+    /// A message is defined by more than one resource of the locale.
+    AddResource(PathBuf, Vec<FluentError>),
+}
+
+impl fmt::Display for TranslationBundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranslationBundleError::ReadLocaleDir(path, e) => {
+                write!(
+                    f,
+                    "could not read locale directory `{}`: {}",
+                    path.display(),
+                    e
+                )
+            }
+            TranslationBundleError::ReadFtl(path, e) => {
+                write!(f, "could not read `{}`: {}", path.display(), e)
+            }
+            TranslationBundleError::ParseFtl(path, errs) => {
+                write!(f, "could not parse `{}`: {:?}", path.display(), errs)
+            }
+            TranslationBundleError::AddResource(path, errs) => {
+                write!(
+                    f,
+                    "could not add `{}` to the bundle: {:?}",
+                    path.display(),
+                    errs
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for TranslationBundleError {}
+
+#[derive(Debug)]
+pub enum TranslateError {
+    /// No bundle, including the fallback, defines the message.
+    MessageMissing(String),
+    /// The message exists but has no such attribute.
+    AttributeMissing { id: String, attr: String },
+    /// The message exists but only has attributes.
+    ValueMissing(String),
+    /// Formatting the message failed, e.g. because an argument is missing.
+    Fluent(String, Vec<FluentError>),
+}
+
+impl fmt::Display for TranslateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TranslateError::MessageMissing(id) => write!(f, "message `{}` was not found", id),
+            TranslateError::AttributeMissing { id, attr } => {
+                write!(f, "message `{}` has no attribute `{}`", id, attr)
+            }
+            TranslateError::ValueMissing(id) => write!(f, "message `{}` has no value", id),
+            TranslateError::Fluent(id, errs) => {
+                write!(f, "failed to format message `{}`: {:?}", id, errs)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TranslateError {}
+
+fn new_bundle(locale: LanguageIdentifier) -> FluentBundle {
+    let mut bundle = FluentBundle::new(vec![locale]);
+    // Unicode isolation marks around arguments are only noise in a terminal.
+    bundle.set_use_isolating(false);
+    bundle
+}
+
+/// Builds the bundle for [`FALLBACK_LOCALE`] from the compiled-in resources.
+pub fn fallback_fluent_bundle() -> FluentBundle {
+    let mut bundle = new_bundle(FALLBACK_LOCALE.parse().unwrap());
+    let resource = FluentResource::try_new(FALLBACK_FTL.to_string())
+        .expect("the fallback Fluent resources should parse");
+    bundle
+        .add_resource(resource)
+        .expect("the fallback Fluent resources should not conflict");
+    bundle
+}
+
+/// Builds a bundle out of every `.ftl` file in `dir/<locale>/`.
+pub fn fluent_bundle(
+    dir: &Path,
+    locale: LanguageIdentifier,
+) -> Result<FluentBundle, TranslationBundleError> {
+    let locale_dir = dir.join(locale.to_string());
+    let entries = fs::read_dir(&locale_dir)
+        .map_err(|e| TranslationBundleError::ReadLocaleDir(locale_dir.clone(), e))?;
+
+    let mut paths = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| TranslationBundleError::ReadLocaleDir(locale_dir.clone(), e))?
+            .path();
+        if path.extension().is_some_and(|ext| ext == "ftl") {
+            paths.push(path);
+        }
+    }
+    // Keep the bundle independent of the directory iteration order.
+    paths.sort();
+
+    let mut bundle = new_bundle(locale);
+    for path in paths {
+        let source = fs::read_to_string(&path)
+            .map_err(|e| TranslationBundleError::ReadFtl(path.clone(), e))?;
+        let resource = FluentResource::try_new(source)
+            .map_err(|(_, errs)| TranslationBundleError::ParseFtl(path.clone(), errs))?;
+        bundle
+            .add_resource(resource)
+            .map_err(|errs| TranslationBundleError::AddResource(path, errs))?;
+    }
+    Ok(bundle)
+}
+
+fn to_fluent_args(args: &DiagnosticArgs) -> FluentArgs<'_> {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        let value = match value {
+            DiagnosticArgValue::Str(s) => FluentValue::from(&**s),
+            DiagnosticArgValue::Number(n) => FluentValue::from(*n),
+        };
+        fluent_args.set(&**name, value);
+    }
+    fluent_args
+}
+
+/// Resolves [`DiagnosticMessage`]s against the bundle of the requested
+/// locale, falling back to [`FALLBACK_LOCALE`] for messages it lacks.
+pub struct Translator {
+    bundle: Option<FluentBundle>,
+    fallback: FluentBundle,
+}
+
+impl Default for Translator {
+    fn default() -> Self {
+        Translator::fallback()
+    }
+}
+
+impl Translator {
+    /// A translator that only knows the [`FALLBACK_LOCALE`].
+    pub fn fallback() -> Translator {
+        Translator {
+            bundle: None,
+            fallback: fallback_fluent_bundle(),
+        }
+    }
+
+    /// A translator for `locale`, reading its resources from `dir/<locale>/`.
+    pub fn with_locale(
+        dir: &Path,
+        locale: LanguageIdentifier,
+    ) -> Result<Translator, TranslationBundleError> {
+        Ok(Translator {
+            bundle: Some(fluent_bundle(dir, locale)?),
+            fallback: fallback_fluent_bundle(),
+        })
+    }
+
+    pub fn locale(&self) -> &LanguageIdentifier {
+        let bundle = self.bundle.as_ref().unwrap_or(&self.fallback);
+        &bundle.locales[0]
+    }
+
+    pub fn translate_message<'a>(
+        &'a self,
+        message: &'a DiagnosticMessage,
+    ) -> Result<Cow<'a, str>, TranslateError> {
+        let (id, attr, args) = match message {
+            DiagnosticMessage::Eager(msg) => return Ok(Cow::Borrowed(msg)),
+            DiagnosticMessage::FluentIdentifier { id, attr, args } => (id, attr, args),
+        };
+
+        let bundle = self
+            .bundle
+            .iter()
+            .chain(std::iter::once(&self.fallback))
+            .find(|bundle| bundle.has_message(id))
+            .ok_or_else(|| TranslateError::MessageMissing(id.to_string()))?;
+        let fluent_message = bundle.get_message(id).unwrap();
+
+        let pattern = match attr {
+            Some(attr) => fluent_message
+                .get_attribute(attr)
+                .ok_or_else(|| TranslateError::AttributeMissing {
+                    id: id.to_string(),
+                    attr: attr.to_string(),
+                })?
+                .value(),
+            None => fluent_message
+                .value()
+                .ok_or_else(|| TranslateError::ValueMissing(id.to_string()))?,
+        };
+
+        let fluent_args = to_fluent_args(args);
+        let mut errs = Vec::new();
+        let translated = bundle.format_pattern(pattern, Some(&fluent_args), &mut errs);
+        if errs.is_empty() {
+            Ok(Cow::Owned(translated.into_owned()))
+        } else {
+            Err(TranslateError::Fluent(id.to_string(), errs))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locales_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("locales")
+    }
+
+    #[test]
+    fn eager_messages_are_untouched() {
+        let translator = Translator::fallback();
+        let msg = DiagnosticMessage::from("already formatted");
+        assert_eq!(
+            translator.translate_message(&msg).unwrap(),
+            "already formatted"
+        );
+    }
+
+    #[test]
+    fn fallback_formats_arguments() {
+        let translator = Translator::fallback();
+        let msg = DiagnosticMessage::fluent("errors_unused_variable").arg("name", "x");
+        assert_eq!(
+            translator.translate_message(&msg).unwrap(),
+            "unused variable: `x`"
+        );
+    }
+
+    #[test]
+    fn missing_messages_fall_back() {
+        let translator = Translator::with_locale(&locales_dir(), "de".parse().unwrap()).unwrap();
+        let msg = DiagnosticMessage::fluent("errors_only_in_fallback");
+        assert_eq!(
+            translator.translate_message(&msg).unwrap(),
+            "this message is only in the fallback bundle"
+        );
+    }
+
+    #[test]
+    fn unknown_messages_are_errors() {
+        let translator = Translator::fallback();
+        let msg = DiagnosticMessage::fluent("errors_does_not_exist");
+        assert!(matches!(
+            translator.translate_message(&msg),
+            Err(TranslateError::MessageMissing(_))
+        ));
+
+        let msg = DiagnosticMessage::fluent("errors_unused_variable").with_attr("nope");
+        assert!(matches!(
+            translator.translate_message(&msg),
+            Err(TranslateError::AttributeMissing { .. })
+        ));
+    }
+
+    #[test]
+    fn missing_locales_are_errors() {
+        let err = Translator::with_locale(&locales_dir(), "tlh".parse().unwrap())
+            .err()
+            .unwrap();
+        assert!(matches!(err, TranslationBundleError::ReadLocaleDir(..)));
+    }
+}
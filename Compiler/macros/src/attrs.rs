@@ -0,0 +1,236 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Field, Fields, Ident, LitStr, Meta, Token, Type};
+
+/// The arguments of `#[suggestion(name, code = "...", applicability = "...",
+/// style = "...")]`.
+pub(crate) struct Suggestion {
+    pub(crate) name: Option<Ident>,
+    pub(crate) code: LitStr,
+    pub(crate) applicability: TokenStream,
+    pub(crate) style: TokenStream,
+}
+
+/// Returns the named fields of a struct, or an error for anything else.
+pub(crate) fn named_fields<'a>(
+    input: &'a DeriveInput,
+    derive: &str,
+) -> syn::Result<Vec<&'a Field>> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(fields.named.iter().collect()),
+            Fields::Unit => Ok(Vec::new()),
+            Fields::Unnamed(_) => Err(syn::Error::new_spanned(
+                &input.ident,
+                format!("`#[derive({derive})]` does not support tuple structs"),
+            )),
+        },
+        _ => Err(syn::Error::new_spanned(
+            &input.ident,
+            format!("`#[derive({derive})]` can only be used on structs"),
+        )),
+    }
+}
+
+/// Returns `true` for types spelled `Span` or `some::path::Span`.
+pub(crate) fn is_span(ty: &Type) -> bool {
+    match ty {
+        Type::Path(ty) => {
+            ty.qself.is_none()
+                && ty
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|seg| seg.ident == "Span")
+        }
+        _ => false,
+    }
+}
+
+pub(crate) fn require_span(field: &Field, attr: &Attribute) -> syn::Result<()> {
+    if is_span(&field.ty) {
+        Ok(())
+    } else {
+        Err(syn::Error::new_spanned(
+            attr,
+            format!(
+                "`#[{}]` is only valid on fields of type `Span`",
+                attr_name(attr)
+            ),
+        ))
+    }
+}
+
+pub(crate) fn attr_name(attr: &Attribute) -> String {
+    attr.path()
+        .get_ident()
+        .map_or_else(String::new, Ident::to_string)
+}
+
+/// Parses `#[attr]` or `#[attr(name)]`.
+pub(crate) fn parse_optional_name(attr: &Attribute) -> syn::Result<Option<Ident>> {
+    match &attr.meta {
+        Meta::Path(_) => Ok(None),
+        Meta::List(_) => {
+            let mut name = None;
+            attr.parse_nested_meta(|meta| match meta.path.get_ident() {
+                Some(ident) if name.is_none() && !meta.input.peek(Token![=]) => {
+                    name = Some(ident.clone());
+                    Ok(())
+                }
+                _ => Err(meta.error(format!(
+                    "`#[{}]` takes at most one message name",
+                    attr_name(attr)
+                ))),
+            })?;
+            Ok(name)
+        }
+        Meta::NameValue(_) => Err(syn::Error::new_spanned(
+            attr,
+            format!("expected `#[{0}]` or `#[{0}(name)]`", attr_name(attr)),
+        )),
+    }
+}
+
+/// Parses `#[suggestion(name, code = "...", applicability = "...", style =
+/// "...")]`, where only `code` is required.
+pub(crate) fn parse_suggestion(attr: &Attribute) -> syn::Result<Suggestion> {
+    let mut name = None;
+    let mut code = None;
+    let mut applicability = None;
+    let mut style = None;
+
+    if let Meta::List(_) = &attr.meta {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("code") {
+                code = Some(meta.value()?.parse::<LitStr>()?);
+            } else if meta.path.is_ident("applicability") {
+                applicability = Some(parse_applicability(&meta.value()?.parse()?)?);
+            } else if meta.path.is_ident("style") {
+                style = Some(parse_style(&meta.value()?.parse()?)?);
+            } else if meta.input.peek(Token![=]) {
+                let key = meta
+                    .path
+                    .get_ident()
+                    .map_or_else(String::new, Ident::to_string);
+                return Err(meta.error(format!("unknown argument `{key}` of `#[suggestion]`")));
+            } else {
+                match meta.path.get_ident() {
+                    Some(ident) if name.is_none() => name = Some(ident.clone()),
+                    _ => return Err(meta.error("`#[suggestion]` takes at most one message name")),
+                }
+            }
+            Ok(())
+        })?;
+    }
+
+    let code = code.ok_or_else(|| {
+        syn::Error::new_spanned(attr, "`#[suggestion]` is missing `code = \"...\"`")
+    })?;
+    let applicability =
+        applicability.unwrap_or_else(|| quote!(::compiler_errors::Applicability::Unspecified));
+    let style = style.unwrap_or_else(|| quote!(::compiler_errors::SuggestionStyle::ShowCode));
+    Ok(Suggestion {
+        name,
+        code,
+        applicability,
+        style,
+    })
+}
+
+fn parse_applicability(lit: &LitStr) -> syn::Result<TokenStream> {
+    let variant = match lit.value().as_str() {
+        "machine-applicable" => quote!(MachineApplicable),
+        "maybe-incorrect" => quote!(MaybeIncorrect),
+        "has-placeholders" => quote!(HasPlaceholders),
+        "unspecified" => quote!(Unspecified),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                lit,
+                "invalid applicability, expected one of `machine-applicable`, \
+                 `maybe-incorrect`, `has-placeholders` or `unspecified`",
+            ))
+        }
+    };
+    Ok(quote!(::compiler_errors::Applicability::#variant))
+}
+
+/// The styles are named after the `span_suggestion_*` method using them.
+fn parse_style(lit: &LitStr) -> syn::Result<TokenStream> {
+    let variant = match lit.value().as_str() {
+        "normal" => quote!(ShowCode),
+        "short" => quote!(HideCodeInline),
+        "hidden" => quote!(HideCodeAlways),
+        "verbose" => quote!(ShowAlways),
+        "tool-only" => quote!(CompletelyHidden),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                lit,
+                "invalid suggestion style, expected one of `normal`, `short`, \
+                 `hidden`, `verbose` or `tool-only`",
+            ))
+        }
+    };
+    Ok(quote!(::compiler_errors::SuggestionStyle::#variant))
+}
+
+/// Builds the `format!` call producing the code of a suggestion, binding
+/// every `{field}` of the template to the field of the same name.
+pub(crate) fn format_code(code: &LitStr, fields: &[&Field]) -> syn::Result<TokenStream> {
+    let template = code.value();
+    let mut names: Vec<String> = Vec::new();
+    let mut rest = template.as_str();
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        if let Some(escaped) = rest.strip_prefix('{') {
+            rest = escaped;
+            continue;
+        }
+        let end = rest.find(['}', ':']).unwrap_or(rest.len());
+        let name = &rest[..end];
+        if !name.is_empty()
+            && !name.chars().all(|c| c.is_ascii_digit())
+            && !names.iter().any(|n| n == name)
+        {
+            names.push(name.to_string());
+        }
+        rest = &rest[end..];
+    }
+
+    let mut bindings = Vec::new();
+    for name in &names {
+        let Some(field) = fields
+            .iter()
+            .find(|field| field.ident.as_ref().is_some_and(|ident| ident == name))
+        else {
+            return Err(syn::Error::new_spanned(
+                code,
+                format!("`{{{name}}}` does not name a field of the struct"),
+            ));
+        };
+        let ident = field.ident.as_ref().unwrap();
+        bindings.push(quote!(#ident = #ident));
+    }
+    Ok(quote!(::std::format!(#code, #(#bindings),*)))
+}
+
+/// `UnusedVariable` -> `unused_variable`.
+pub(crate) fn to_snake_case(ident: &Ident) -> String {
+    let mut snake = String::new();
+    for (i, c) in ident.to_string().chars().enumerate() {
+        if c.is_uppercase() {
+            if i != 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// A local whose name cannot clash with the fields bound from the struct.
+pub(crate) fn local(name: &str) -> Ident {
+    Ident::new(name, Span::mixed_site())
+}
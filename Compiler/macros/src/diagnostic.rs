@@ -0,0 +1,208 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, Ident, LitStr, Meta};
+
+use crate::attrs::{self, local};
+
+/// The arguments of `#[diag(slug, code = "...")]`.
+struct DiagAttr {
+    slug: Option<Ident>,
+    code: Option<LitStr>,
+}
+
+fn parse_diag_attr(attr: &syn::Attribute) -> syn::Result<DiagAttr> {
+    let mut diag = DiagAttr {
+        slug: None,
+        code: None,
+    };
+    if let Meta::List(_) = &attr.meta {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("code") {
+                diag.code = Some(meta.value()?.parse()?);
+            } else if meta.input.peek(syn::Token![=]) {
+                let key = meta
+                    .path
+                    .get_ident()
+                    .map_or_else(String::new, Ident::to_string);
+                return Err(meta.error(format!("unknown argument `{key}` of `#[diag]`")));
+            } else {
+                match meta.path.get_ident() {
+                    Some(ident) if diag.slug.is_none() => diag.slug = Some(ident.clone()),
+                    _ => return Err(meta.error("`#[diag]` takes at most one slug")),
+                }
+            }
+            Ok(())
+        })?;
+    }
+    Ok(diag)
+}
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let fields = attrs::named_fields(input, "Diagnostic")?;
+    let diag = local("diag");
+    let args = local("args");
+    let msg = local("msg");
+
+    let mut diag_attr = None;
+    let mut stmts = Vec::new();
+    for attr in &input.attrs {
+        let name = attrs::attr_name(attr);
+        match name.as_str() {
+            "diag" if diag_attr.is_some() => {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "`#[diag]` specified multiple times",
+                ));
+            }
+            "diag" => diag_attr = Some(parse_diag_attr(attr)?),
+            "note" | "help" => {
+                let fluent_attr =
+                    attrs::parse_optional_name(attr)?.map_or(name.clone(), |n| n.to_string());
+                let method = Ident::new(&name, proc_macro2::Span::call_site());
+                stmts.push(quote!(#diag.#method(#msg(::std::option::Option::Some(#fluent_attr)));));
+            }
+            "primary_span" | "label" | "suggestion" | "subdiagnostic" | "skip_arg" => {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    format!("`#[{name}]` is only valid on fields"),
+                ));
+            }
+            _ => {}
+        }
+    }
+    let Some(diag_attr) = diag_attr else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "diagnostic slug and code not specified, add `#[diag(slug, code = \"...\")]`",
+        ));
+    };
+    let slug = diag_attr.slug.map_or_else(
+        || attrs::to_snake_case(&input.ident),
+        |slug| slug.to_string(),
+    );
+    if let Some(code) = &diag_attr.code {
+        stmts.insert(0, quote!(#diag.code(#code);));
+    }
+
+    let mut bindings = Vec::new();
+    let mut codes = Vec::new();
+    let mut arg_pairs = Vec::new();
+    let mut primary_span = None;
+    for field in &fields {
+        let ident = field.ident.as_ref().unwrap();
+        let mut is_arg = true;
+        let mut is_bound = true;
+        for attr in &field.attrs {
+            let name = attrs::attr_name(attr);
+            match name.as_str() {
+                "primary_span" => {
+                    attrs::require_span(field, attr)?;
+                    if primary_span.is_some() {
+                        return Err(syn::Error::new_spanned(
+                            attr,
+                            "`#[primary_span]` specified multiple times",
+                        ));
+                    }
+                    primary_span = Some(ident);
+                    stmts.insert(0, quote!(#diag.set_span(#ident);));
+                }
+                "label" | "note" | "help" => {
+                    attrs::require_span(field, attr)?;
+                    let fluent_attr =
+                        attrs::parse_optional_name(attr)?.map_or(name.clone(), |n| n.to_string());
+                    let method = match name.as_str() {
+                        "label" => quote!(span_label),
+                        "note" => quote!(span_note),
+                        _ => quote!(span_help),
+                    };
+                    stmts.push(quote!(#diag.#method(#ident, #msg(::std::option::Option::Some(#fluent_attr)));));
+                }
+                "suggestion" => {
+                    attrs::require_span(field, attr)?;
+                    let attrs::Suggestion {
+                        name,
+                        code,
+                        applicability,
+                        style,
+                    } = attrs::parse_suggestion(attr)?;
+                    let fluent_attr =
+                        name.map_or_else(|| "suggestion".to_string(), |n| n.to_string());
+                    let code_local = local(&format!("code_{}", codes.len()));
+                    let format = attrs::format_code(&code, &fields)?;
+                    codes.push(quote!(let #code_local = #format;));
+                    stmts.push(quote!(
+                        #diag.span_suggestion_with_style(
+                            #ident,
+                            #msg(::std::option::Option::Some(#fluent_attr)),
+                            #code_local,
+                            #applicability,
+                            #style,
+                        );
+                    ));
+                }
+                "subdiagnostic" => {
+                    if field
+                        .attrs
+                        .iter()
+                        .filter(|a| attrs::attr_name(a) != "doc")
+                        .count()
+                        > 1
+                    {
+                        return Err(syn::Error::new_spanned(
+                            attr,
+                            "`#[subdiagnostic]` cannot be combined with other attributes",
+                        ));
+                    }
+                    stmts.push(quote!(#diag.subdiagnostic(#ident);));
+                }
+                "skip_arg" => is_bound = false,
+                "diag" => {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "`#[diag]` is only valid on the struct",
+                    ))
+                }
+                _ => continue,
+            }
+            is_arg = false;
+        }
+        if is_bound {
+            bindings.push(ident);
+        }
+        if is_arg {
+            let key = ident.to_string();
+            arg_pairs.push(quote!(
+                (
+                    ::std::borrow::Cow::Borrowed(#key),
+                    ::compiler_errors::IntoDiagnosticArg::into_diagnostic_arg(#ident),
+                )
+            ));
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::compiler_errors::IntoDiagnostic for #name #ty_generics #where_clause {
+            fn into_diagnostic(self) -> ::compiler_errors::Diagnostic {
+                let Self { #(#bindings,)* .. } = self;
+                #(#codes)*
+                let #args: ::compiler_errors::DiagnosticArgs = ::std::vec![#(#arg_pairs),*];
+                let #msg = |attr: ::std::option::Option<&'static str>| {
+                    let msg = ::compiler_errors::DiagnosticMessage::fluent(#slug);
+                    match attr {
+                        ::std::option::Option::Some(attr) => msg.with_attr(attr),
+                        ::std::option::Option::None => msg,
+                    }
+                    .with_args(&#args)
+                };
+                let mut #diag = ::compiler_errors::Diagnostic::new(
+                    ::compiler_errors::Level::Error,
+                    #msg(::std::option::Option::None),
+                );
+                #(#stmts)*
+                #diag
+            }
+        }
+    })
+}
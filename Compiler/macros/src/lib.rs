@@ -0,0 +1,77 @@
+//! Derives for the diagnostic types of `compiler_errors`, modelled on the
+//! `#[derive(Diagnostic)]` and `#[derive(Subdiagnostic)]` of `rustc_macros`.
+//!
+//! ```ignore (needs-fluent-resources)
+//! #[derive(Diagnostic)]
+//! #[diag(errors_unused_variable, code = "E0001")]
+//! struct UnusedVariable {
+//!     #[primary_span]
+//!     #[label]
+//!     span: Span,
+//!     #[suggestion(code = "_{name}", applicability = "machine-applicable")]
+//!     sugg: Span,
+//!     name: String,
+//! }
+//! ```
+//!
+//! Fields without an attribute become arguments of every Fluent message of
+//! the diagnostic, so `name` above can be used as `{$name}`.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod attrs;
+mod diagnostic;
+mod subdiagnostic;
+
+/// Implements `IntoDiagnostic`.
+///
+/// Struct attributes: `#[diag(slug, code = "...")]` (the slug defaults to
+/// the snake-cased struct name), `#[note]` and `#[help]`.
+///
+/// Field attributes: `#[primary_span]`, `#[label]`, `#[note]`, `#[help]`,
+/// `#[suggestion(code = "...", applicability = "...", style = "...")]` on
+/// `Span` fields,
+/// `#[subdiagnostic]` on fields implementing `AddToDiagnostic`, and
+/// `#[skip_arg]` on fields that should not become Fluent arguments.
+/// `#[label]`, `#[note]`, `#[help]` and `#[suggestion]` take an optional
+/// Fluent attribute name, which defaults to the name of the attribute.
+/// The `style` of a suggestion is one of `normal` (the default), `short`,
+/// `hidden`, `verbose` or `tool-only`, after the `span_suggestion_*`
+/// method with the same behaviour.
+#[proc_macro_derive(
+    Diagnostic,
+    attributes(
+        diag,
+        primary_span,
+        label,
+        note,
+        help,
+        suggestion,
+        subdiagnostic,
+        skip_arg
+    )
+)]
+pub fn diagnostic_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    diagnostic::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Implements `AddToDiagnostic`.
+///
+/// The struct takes exactly one of `#[label(slug)]`, `#[note(slug)]`,
+/// `#[help(slug)]` or `#[suggestion(slug, code = "...", applicability =
+/// "...", style = "...")]`, the slug defaulting to the snake-cased struct name. Fields
+/// take `#[primary_span]` or `#[skip_arg]`.
+#[proc_macro_derive(
+    Subdiagnostic,
+    attributes(primary_span, label, note, help, suggestion, skip_arg)
+)]
+pub fn subdiagnostic_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    subdiagnostic::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
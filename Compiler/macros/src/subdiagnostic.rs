@@ -0,0 +1,167 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::DeriveInput;
+
+use crate::attrs::{self, local};
+
+/// What the subdiagnostic adds to its parent.
+enum Kind {
+    Label,
+    Note,
+    Help,
+    Suggestion {
+        code: syn::LitStr,
+        applicability: TokenStream,
+        style: TokenStream,
+    },
+}
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let fields = attrs::named_fields(input, "Subdiagnostic")?;
+    let diag = local("diag");
+    let args = local("args");
+    let msg = local("msg");
+    let code = local("code");
+
+    let mut kind = None;
+    for attr in &input.attrs {
+        let name = attrs::attr_name(attr);
+        let (new_kind, slug) = match name.as_str() {
+            "label" => (Kind::Label, attrs::parse_optional_name(attr)?),
+            "note" => (Kind::Note, attrs::parse_optional_name(attr)?),
+            "help" => (Kind::Help, attrs::parse_optional_name(attr)?),
+            "suggestion" => {
+                let attrs::Suggestion {
+                    name,
+                    code,
+                    applicability,
+                    style,
+                } = attrs::parse_suggestion(attr)?;
+                (
+                    Kind::Suggestion {
+                        code,
+                        applicability,
+                        style,
+                    },
+                    name,
+                )
+            }
+            "primary_span" | "skip_arg" => {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    format!("`#[{name}]` is only valid on fields"),
+                ));
+            }
+            _ => continue,
+        };
+        if kind.is_some() {
+            return Err(syn::Error::new_spanned(attr, "a subdiagnostic can only be one of `#[label]`, `#[note]`, `#[help]` or `#[suggestion]`"));
+        }
+        let slug = slug.map_or_else(
+            || attrs::to_snake_case(&input.ident),
+            |slug| slug.to_string(),
+        );
+        kind = Some((new_kind, slug));
+    }
+    let Some((kind, slug)) = kind else {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "subdiagnostic kind not specified, add one of `#[label]`, `#[note]`, `#[help]` or `#[suggestion]`",
+        ));
+    };
+
+    let mut bindings = Vec::new();
+    let mut arg_pairs = Vec::new();
+    let mut primary_span = None;
+    for field in &fields {
+        let ident = field.ident.as_ref().unwrap();
+        let mut is_arg = true;
+        let mut is_bound = true;
+        for attr in &field.attrs {
+            match attrs::attr_name(attr).as_str() {
+                "primary_span" => {
+                    attrs::require_span(field, attr)?;
+                    if primary_span.is_some() {
+                        return Err(syn::Error::new_spanned(
+                            attr,
+                            "`#[primary_span]` specified multiple times",
+                        ));
+                    }
+                    primary_span = Some(ident);
+                }
+                "skip_arg" => is_bound = false,
+                name @ ("label" | "note" | "help" | "suggestion" | "subdiagnostic" | "diag") => {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        format!("`#[{name}]` is not supported on fields of a subdiagnostic"),
+                    ));
+                }
+                _ => continue,
+            }
+            is_arg = false;
+        }
+        if is_bound {
+            bindings.push(ident);
+        }
+        if is_arg {
+            let key = ident.to_string();
+            arg_pairs.push(quote!(
+                (
+                    ::std::borrow::Cow::Borrowed(#key),
+                    ::compiler_errors::IntoDiagnosticArg::into_diagnostic_arg(#ident),
+                )
+            ));
+        }
+    }
+
+    let require_primary_span = |attr: &str| {
+        primary_span.ok_or_else(|| {
+            syn::Error::new_spanned(
+                &input.ident,
+                format!("`#[{attr}]` subdiagnostics need a `#[primary_span]` field"),
+            )
+        })
+    };
+    let (format_code, add) = match kind {
+        Kind::Label => {
+            let span = require_primary_span("label")?;
+            (None, quote!(#diag.span_label(#span, #msg);))
+        }
+        Kind::Note | Kind::Help => {
+            let (method, span_method) = match kind {
+                Kind::Note => (quote!(note), quote!(span_note)),
+                _ => (quote!(help), quote!(span_help)),
+            };
+            match primary_span {
+                Some(span) => (None, quote!(#diag.#span_method(#span, #msg);)),
+                None => (None, quote!(#diag.#method(#msg);)),
+            }
+        }
+        Kind::Suggestion {
+            code: template,
+            applicability,
+            style,
+        } => {
+            let span = require_primary_span("suggestion")?;
+            let format = attrs::format_code(&template, &fields)?;
+            (
+                Some(quote!(let #code = #format;)),
+                quote!(#diag.span_suggestion_with_style(#span, #msg, #code, #applicability, #style);),
+            )
+        }
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::compiler_errors::AddToDiagnostic for #name #ty_generics #where_clause {
+            fn add_to_diagnostic(self, #diag: &mut ::compiler_errors::Diagnostic) {
+                let Self { #(#bindings,)* .. } = self;
+                #format_code
+                let #args: ::compiler_errors::DiagnosticArgs = ::std::vec![#(#arg_pairs),*];
+                let #msg = ::compiler_errors::DiagnosticMessage::fluent(#slug).with_args(&#args);
+                #add
+            }
+        }
+    })
+}
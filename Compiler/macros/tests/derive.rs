@@ -0,0 +1,151 @@
+use compiler_errors::{
+    AddToDiagnostic, Applicability, Diagnostic, DiagnosticMessage, HumanEmitter, IntoDiagnostic,
+    Level, SourceMap, Span, Subdiagnostic, SuggestionStyle, Translator,
+};
+
+#[derive(Diagnostic)]
+#[diag(errors_unused_variable, code = "E0001")]
+struct UnusedVariable {
+    #[primary_span]
+    #[label]
+    span: Span,
+    #[suggestion(code = "_{name}", applicability = "machine-applicable")]
+    sugg: Span,
+    name: String,
+    #[subdiagnostic]
+    declared: Option<DeclaredHere>,
+}
+
+#[derive(Diagnostic)]
+#[diag(errors_unused_variable)]
+struct UnusedVariableToolOnly {
+    #[primary_span]
+    #[suggestion(code = "_{name}", style = "tool-only")]
+    span: Span,
+    name: String,
+}
+
+#[derive(Subdiagnostic)]
+#[note(errors_declared_here)]
+struct DeclaredHere {
+    #[primary_span]
+    span: Span,
+    name: &'static str,
+}
+
+#[derive(Diagnostic)]
+#[diag]
+struct ErrorsOnlyInFallback {
+    #[skip_arg]
+    #[allow(dead_code)]
+    ignored: Vec<u8>,
+}
+
+#[test]
+fn derive_builds_diagnostic() {
+    let mut sm = SourceMap::new();
+    let file = sm.new_source_file("main.rs", "fn main() {\n    let x = 42;\n}\n");
+    let span = sm.span_of(&file, "x").unwrap();
+
+    let diag = UnusedVariable {
+        span,
+        sugg: span,
+        name: "x".to_string(),
+        declared: None,
+    }
+    .into_diagnostic();
+    assert_eq!(diag.level, Level::Error);
+    assert_eq!(diag.code.as_deref(), Some("E0001"));
+    assert_eq!(
+        diag.message,
+        DiagnosticMessage::fluent("errors_unused_variable").arg("name", "x")
+    );
+    assert_eq!(diag.span.primary_spans, vec![span]);
+
+    let [suggestion] = &diag.suggestions[..] else {
+        panic!("expected one suggestion")
+    };
+    assert_eq!(suggestion.applicability, Applicability::MachineApplicable);
+    assert_eq!(suggestion.substitutions[0].parts[0].snippet, "_x");
+    assert_eq!(
+        suggestion.msg,
+        DiagnosticMessage::fluent("errors_unused_variable")
+            .with_attr("suggestion")
+            .arg("name", "x")
+    );
+
+    let translator = Translator::fallback();
+    assert_eq!(
+        HumanEmitter::new(&sm, &translator)
+            .emit_diagnostic(&diag)
+            .unwrap(),
+        "error[E0001]: unused variable: `x`\n \
+         --> main.rs:2:9\n  \
+         |\n\
+         2 |     let x = 42;\n  \
+         |         ^ this variable is never read\n  \
+         |         ^ help: if this is intentional, prefix it with an underscore: `_x`\n"
+    );
+}
+
+#[test]
+fn derive_sets_suggestion_style() {
+    let mut sm = SourceMap::new();
+    let file = sm.new_source_file("main.rs", "fn main() {\n    let x = 42;\n}\n");
+    let span = sm.span_of(&file, "x").unwrap();
+
+    let diag = UnusedVariableToolOnly {
+        span,
+        name: "x".to_string(),
+    }
+    .into_diagnostic();
+    assert_eq!(diag.suggestions[0].style, SuggestionStyle::CompletelyHidden);
+
+    let translator = Translator::fallback();
+    let rendered = HumanEmitter::new(&sm, &translator)
+        .emit_diagnostic(&diag)
+        .unwrap();
+    assert!(!rendered.contains("help"));
+}
+
+#[test]
+fn derive_adds_subdiagnostics() {
+    let mut sm = SourceMap::new();
+    let file = sm.new_source_file("main.rs", "fn main() {\n    let x = 42;\n}\n");
+    let span = sm.span_of(&file, "x").unwrap();
+
+    let declared = DeclaredHere { span, name: "x" };
+    let diag = UnusedVariable {
+        span,
+        sugg: span,
+        name: "x".to_string(),
+        declared: Some(declared),
+    }
+    .into_diagnostic();
+    let [note] = &diag.children[..] else {
+        panic!("expected one note")
+    };
+    assert_eq!(note.level, Level::Note);
+    assert_eq!(note.span.primary_spans, vec![span]);
+    assert_eq!(
+        note.message,
+        DiagnosticMessage::fluent("errors_declared_here").arg("name", "x")
+    );
+
+    let mut diag = compiler_errors::Diagnostic::new(Level::Warning, "unused");
+    DeclaredHere { span, name: "y" }.add_to_diagnostic(&mut diag);
+    assert_eq!(diag.children.len(), 1);
+}
+
+#[test]
+fn slug_defaults_to_struct_name() {
+    let diag = ErrorsOnlyInFallback {
+        ignored: vec![1, 2, 3],
+    }
+    .into_diagnostic();
+    assert_eq!(
+        diag.message,
+        DiagnosticMessage::fluent("errors_only_in_fallback")
+    );
+    assert_eq!(diag.code, None);
+}
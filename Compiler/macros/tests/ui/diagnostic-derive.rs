@@ -0,0 +1,87 @@
+use compiler_errors::{Diagnostic, Span};
+
+#[derive(Diagnostic)]
+struct MissingDiag {
+    #[primary_span]
+    span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(errors_unused_variable, kode = "E0001")]
+struct UnknownDiagArgument {}
+
+#[derive(Diagnostic)]
+#[diag(errors_unused_variable)]
+#[diag(errors_unused_variable)]
+struct DuplicateDiag {}
+
+#[derive(Diagnostic)]
+#[diag(errors_unused_variable)]
+enum NotAStruct {
+    A,
+}
+
+#[derive(Diagnostic)]
+#[diag(errors_unused_variable)]
+struct LabelOnString {
+    #[label]
+    name: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(errors_unused_variable)]
+struct DuplicatePrimarySpan {
+    #[primary_span]
+    a: Span,
+    #[primary_span]
+    b: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(errors_unused_variable)]
+struct SuggestionWithoutCode {
+    #[suggestion(applicability = "machine-applicable")]
+    span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(errors_unused_variable)]
+struct UnknownSuggestionArgument {
+    #[suggestion(cod = "_x")]
+    span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(errors_unused_variable)]
+struct InvalidApplicability {
+    #[suggestion(code = "_x", applicability = "always")]
+    span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(errors_unused_variable)]
+struct InvalidStyle {
+    #[suggestion(code = "_x", style = "inline")]
+    span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(errors_unused_variable)]
+struct UnknownCodeField {
+    #[suggestion(code = "_{nam}")]
+    span: Span,
+    name: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(errors_unused_variable)]
+#[primary_span]
+struct PrimarySpanOnStruct {}
+
+#[derive(Diagnostic)]
+#[diag(errors_unused_variable)]
+struct NotAnArgument {
+    items: Vec<u8>,
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+use compiler_errors::{Span, Subdiagnostic};
+
+#[derive(Subdiagnostic)]
+struct MissingKind {
+    #[primary_span]
+    span: Span,
+}
+
+#[derive(Subdiagnostic)]
+#[note]
+#[help]
+struct TwoKinds {}
+
+#[derive(Subdiagnostic)]
+#[label(errors_declared_here)]
+struct LabelWithoutSpan {
+    name: String,
+}
+
+#[derive(Subdiagnostic)]
+#[note(errors_declared_here)]
+struct LabelOnField {
+    #[label]
+    span: Span,
+}
+
+#[derive(Subdiagnostic)]
+#[suggestion(errors_declared_here, code = "_x", applicability = "maybe-incorrect")]
+struct SuggestionWithoutSpan {}
+
+#[derive(Subdiagnostic)]
+#[note(errors_declared_here, errors_unused_variable)]
+struct TwoSlugs {}
+
+fn main() {}
@@ -0,0 +1,23 @@
+//! The [`Report`] trait through which both the `collect-lang-items` driver
+//! and the `syn` tools of `collect_source` print what they find. It has a
+//! crate of its own so that neither has to depend on the other.
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+/// What a tool prints, as text or as JSON.
+pub trait Report: Serialize {
+    fn write_text(&self, out: impl Write) -> io::Result<()>;
+
+    fn write_json(&self, mut out: impl Write) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut out, self)?;
+        writeln!(out)
+    }
+
+    /// Whether the tool succeeds once the report is printed, which it is
+    /// even when the report lists what could not be read.
+    fn is_complete(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,206 @@
+//! The crates a snippet uses besides those of the standard library, and the
+//! directory made by `cargo vendor` they are taken from, as the snippets are
+//! built offline.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use syn::visit::{self, Visit};
+
+/// Not crates, though paths may start with them.
+const NOT_CRATES: &[&str] = &[
+    "std",
+    "core",
+    "alloc",
+    "proc_macro",
+    "test",
+    "crate",
+    "self",
+    "super",
+    "Self",
+    "bool",
+    "char",
+    "str",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "u128",
+    "usize",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "i128",
+    "isize",
+    "f32",
+    "f64",
+    "clippy",
+    "rustfmt",
+];
+
+/// The crates the crate root `source` uses: those of its `extern crate`s,
+/// and the first segments of its `use`s and of its other paths, unless they
+/// are modules of its own or names brought in by a `use`.
+pub fn used_crates(source: &str) -> Vec<String> {
+    let Ok(file) = syn::parse_file(source) else {
+        return Vec::new();
+    };
+    let mut paths = Paths::default();
+    paths.visit_file(&file);
+    let Paths {
+        crates,
+        first_segments,
+        local,
+    } = paths;
+    crates
+        .into_iter()
+        .chain(first_segments.into_iter().filter(|name| {
+            // Not the generic parameters, types or variants.
+            name.starts_with(|c: char| c.is_ascii_lowercase()) && !local.contains(name)
+        }))
+        .filter(|name| !NOT_CRATES.contains(&name.as_str()))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+#[derive(Default)]
+struct Paths {
+    /// Crates for sure: of an `extern crate`, or of a path starting with `::`.
+    crates: Vec<String>,
+    /// What the paths of two segments or more, and the `use`s, start with.
+    first_segments: Vec<String>,
+    /// The modules of the snippet, and the names its `use`s bring in.
+    local: BTreeSet<String>,
+}
+
+impl<'ast> Visit<'ast> for Paths {
+    fn visit_item_extern_crate(&mut self, item: &'ast syn::ItemExternCrate) {
+        self.crates.push(item.ident.to_string());
+        if let Some((_, rename)) = &item.rename {
+            self.local.insert(rename.to_string());
+        }
+    }
+
+    fn visit_item_mod(&mut self, item: &'ast syn::ItemMod) {
+        self.local.insert(item.ident.to_string());
+        visit::visit_item_mod(self, item);
+    }
+
+    fn visit_item_use(&mut self, item: &'ast syn::ItemUse) {
+        let first = match &item.tree {
+            syn::UseTree::Path(path) => Some(path.ident.to_string()),
+            _ => None,
+        };
+        match first {
+            Some(first) if item.leading_colon.is_some() => self.crates.push(first),
+            Some(first) => self.first_segments.push(first),
+            None => {}
+        }
+        visit::visit_item_use(self, item);
+    }
+
+    fn visit_use_path(&mut self, path: &'ast syn::UsePath) {
+        let is_self =
+            |tree: &syn::UseTree| matches!(tree, syn::UseTree::Name(name) if name.ident == "self");
+        let brings_itself = match &*path.tree {
+            syn::UseTree::Group(group) => group.items.iter().any(is_self),
+            tree => is_self(tree),
+        };
+        if brings_itself {
+            self.local.insert(path.ident.to_string());
+        }
+        visit::visit_use_path(self, path);
+    }
+
+    fn visit_use_name(&mut self, name: &'ast syn::UseName) {
+        self.local.insert(name.ident.to_string());
+    }
+
+    fn visit_use_rename(&mut self, rename: &'ast syn::UseRename) {
+        self.local.insert(rename.rename.to_string());
+    }
+
+    fn visit_path(&mut self, path: &'ast syn::Path) {
+        if let Some(first) = path.segments.first() {
+            if path.leading_colon.is_some() {
+                self.crates.push(first.ident.to_string());
+            } else if path.segments.len() > 1 {
+                self.first_segments.push(first.ident.to_string());
+            }
+        }
+        visit::visit_path(self, path);
+    }
+}
+
+/// The packages of `vendor` for `crates`, or the crates that are not there.
+/// A package may be named with `-` where its crate has `_`,
+/// and `cargo vendor` puts the version after the name of those it has
+/// several versions of.
+pub fn vendored(vendor: &Path, crates: &[String]) -> Result<Vec<String>, Vec<String>> {
+    let vendored: Vec<String> = fs::read_dir(vendor)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+        .collect();
+    let is_vendored = |package: &str| {
+        vendored.iter().any(|dir| {
+            dir.strip_prefix(package).is_some_and(|version| {
+                version.is_empty()
+                    || version
+                        .strip_prefix('-')
+                        .is_some_and(|v| v.starts_with(|c: char| c.is_ascii_digit()))
+            })
+        })
+    };
+    let mut packages = Vec::new();
+    let mut missing = Vec::new();
+    for krate in crates {
+        let dashed = krate.replace('_', "-");
+        if is_vendored(krate) {
+            packages.push(krate.clone());
+        } else if is_vendored(&dashed) {
+            packages.push(dashed);
+        } else {
+            missing.push(krate.clone());
+        }
+    }
+    if missing.is_empty() {
+        Ok(packages)
+    } else {
+        Err(missing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crates_of_the_paths() {
+        let source = "\
+use std::io::{self, Write};
+use nannou::prelude::*;
+use helpers::shout;
+mod helpers { pub fn shout() {} }
+
+#[tokio::main]
+async fn main() {
+    io::stdout().flush().unwrap();
+    let n = u8::MAX;
+    let v = Vec::<u8>::new();
+    #[rustfmt::skip]
+    let _ = <Vec<u8> as IntoIterator>::IntoIter::default();
+    let _ = ::rand::random::<u8>();
+    extern crate serde_json as json;
+    json::Value::Null;
+}
+";
+        assert_eq!(
+            used_crates(source),
+            ["nannou", "rand", "serde_json", "tokio"]
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! The diagnostics of rustc for the snippets, taken from the JSON messages of
+//! cargo, and the report of the `compile_fail` snippets that shows them next
+//! to the code they are about.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::discover::Snippet;
+use crate::runtest::{self, Outcome};
+
+pub struct Diagnostic {
+    /// `error`, `warning`, ...
+    pub level: String,
+    pub code: Option<String>,
+    /// As rustc prints it, for the source of the crate of the snippet.
+    pub rendered: String,
+}
+
+/// The diagnostics of the `compiler-message`s among the lines that cargo
+/// prints with `--message-format=json`.
+pub fn parse(stdout: &str) -> Vec<Diagnostic> {
+    stdout
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-message")
+        .map(|message| {
+            let message = &message["message"];
+            Diagnostic {
+                level: message["level"].as_str().unwrap_or_default().to_string(),
+                code: message["code"]["code"].as_str().map(str::to_string),
+                rendered: message["rendered"].as_str().unwrap_or_default().to_string(),
+            }
+        })
+        .collect()
+}
+
+pub fn errors(diagnostics: &[Diagnostic]) -> impl Iterator<Item = &Diagnostic> {
+    diagnostics.iter().filter(|diag| diag.level == "error")
+}
+
+/// Why the errors do not match the codes a `compile_fail` snippet expects,
+/// if they do not: each of the codes must be among those of the errors.
+pub fn check_error_codes(expected: &[String], diagnostics: &[Diagnostic]) -> Option<String> {
+    let found: Vec<&str> = errors(diagnostics)
+        .filter_map(|diag| diag.code.as_deref())
+        .collect();
+    let missing: Vec<&str> = expected
+        .iter()
+        .map(String::as_str)
+        .filter(|code| !found.contains(code))
+        .collect();
+    if missing.is_empty() {
+        return None;
+    }
+    let mut found = found;
+    found.sort_unstable();
+    found.dedup();
+    Some(format!(
+        "expected {}, but rustc found {}",
+        missing.join(", "),
+        if found.is_empty() {
+            "no error with a code".to_string()
+        } else {
+            found.join(", ")
+        }
+    ))
+}
+
+/// Writes the markdown report of the `compile_fail` snippets: the source of
+/// the crate of each, with the errors rustc gives for it.
+pub fn write_report(
+    path: &Path,
+    snippets: &[Snippet],
+    outcomes: &[Option<Outcome>],
+) -> io::Result<()> {
+    let mut report = String::from("# Errors of the `compile_fail` snippets\n");
+    for (snippet, outcome) in snippets.iter().zip(outcomes) {
+        let Some(outcome) = outcome else {
+            continue;
+        };
+        if !snippet.annotations.compile_fail {
+            continue;
+        }
+        let codes = &snippet.annotations.error_codes;
+        let verdict = match (outcome.problems.is_empty(), codes.is_empty()) {
+            (true, true) => "fails to compile, as expected".to_string(),
+            (true, false) => format!("fails with {}, as expected", codes.join(", ")),
+            (false, _) => outcome.problems.join("; "),
+        };
+        let _ = write!(
+            report,
+            "\n## {}\n\n{}\n\n```rust\n{}```\n",
+            snippet.name(),
+            verdict.trim_end(),
+            runtest::crate_root(&snippet.source)
+        );
+        let rendered: Vec<_> = errors(&outcome.diagnostics)
+            .map(|diag| diag.rendered.trim_end())
+            .collect();
+        if !rendered.is_empty() {
+            let _ = write!(report, "\n```text\n{}\n```\n", rendered.join("\n\n"));
+        }
+    }
+    fs::write(path, report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_of_the_errors() {
+        let stdout = r#"{"reason":"compiler-message","message":{"level":"error","code":{"code":"E0502","explanation":""},"rendered":"error[E0502]: cannot borrow\n"}}
+{"reason":"compiler-message","message":{"level":"warning","code":{"code":"unused_variables","explanation":null},"rendered":"warning: unused variable\n"}}
+{"reason":"build-finished","success":false}"#;
+        let diagnostics = parse(stdout);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            check_error_codes(&["E0502".to_string()], &diagnostics),
+            None
+        );
+        assert_eq!(
+            check_error_codes(&["E0499".to_string()], &diagnostics).unwrap(),
+            "expected E0499, but rustc found E0502"
+        );
+    }
+}
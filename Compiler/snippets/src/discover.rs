@@ -0,0 +1,325 @@
+//! Finding the snippets: the `.rs` files that are not part of a cargo
+//! package, the fenced Rust blocks of the markdown files of `examples/`, with
+//! their markers, and those of the other notes that expect a given error.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::crates;
+use crate::markers::{self, Marker};
+use crate::runtest;
+use crate::Config;
+
+pub struct Snippet {
+    /// The file of the snippet, relative to the root.
+    pub path: PathBuf,
+    /// The line of the opening fence, for a block of a markdown file.
+    pub line: Option<usize>,
+    pub source: String,
+    pub annotations: Annotations,
+    /// The marker before the block, for a block of `examples/*.md`.
+    pub marker: Option<Marker>,
+    /// The packages of the vendor directory it depends on.
+    pub dependencies: Vec<String>,
+    /// Why it is not run, when it needs crates that are not vendored.
+    pub skip: Option<String>,
+}
+
+impl Snippet {
+    /// The snippet, depending on the vendored packages of the crates it uses
+    /// or `declared`, or skipped if some are not vendored.
+    fn new(
+        config: &Config,
+        path: PathBuf,
+        line: Option<usize>,
+        source: String,
+        annotations: Annotations,
+        declared: &[String],
+    ) -> Snippet {
+        let mut used = crates::used_crates(&runtest::crate_root(&source));
+        used.extend(declared.iter().cloned());
+        used.sort();
+        used.dedup();
+        let vendor = config.vendor();
+        let (dependencies, skip) = match crates::vendored(&vendor, &used) {
+            Ok(dependencies) => (dependencies, None),
+            Err(missing) => {
+                let vendor = vendor.strip_prefix(&config.root).unwrap_or(&vendor);
+                let missing: Vec<_> = missing.iter().map(|krate| format!("`{krate}`")).collect();
+                let skip = format!("needs {}, not in {}", missing.join(", "), vendor.display());
+                (Vec::new(), Some(skip))
+            }
+        };
+        Snippet {
+            path,
+            line,
+            source,
+            annotations,
+            marker: None,
+            dependencies,
+            skip,
+        }
+    }
+
+    /// How the snippet is named in the matrix and by the filters.
+    pub fn name(&self) -> String {
+        let path = self.path.to_string_lossy().replace('\\', "/");
+        match self.line {
+            Some(line) => format!("{path}:{line}"),
+            None => path,
+        }
+    }
+}
+
+/// The annotations of rustdoc that the runner honors, from the info string
+/// of a fence, or from a `// snippet:` comment on the first line of a file.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Annotations {
+    /// Not compiled at all.
+    pub ignore: bool,
+    /// Compiled, but neither `main` nor the tests are run.
+    pub no_run: bool,
+    /// Must not compile.
+    pub compile_fail: bool,
+    /// `main` must panic.
+    pub should_panic: bool,
+    /// Like `edition2018`, the edition otherwise being 2021.
+    pub edition: Option<String>,
+    /// Like `E0499`, the codes of the errors a `compile_fail` snippet must
+    /// fail with.
+    pub error_codes: Vec<String>,
+}
+
+impl Annotations {
+    /// Parses an info string like `rust,compile_fail`. `None` if the block
+    /// is not Rust: it must start with `rust` or `rs`, or, as for rustdoc,
+    /// be made of annotations only.
+    pub fn parse(info: &str) -> Option<Annotations> {
+        let mut tokens = info
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|token| !token.is_empty())
+            .peekable();
+        let tagged = tokens.next_if(|token| matches!(*token, "rust" | "rs"));
+        let mut annotations = Annotations::default();
+        let mut any = false;
+        for token in tokens {
+            match token {
+                "ignore" => annotations.ignore = true,
+                "no_run" => annotations.no_run = true,
+                "compile_fail" => annotations.compile_fail = true,
+                "should_panic" => annotations.should_panic = true,
+                _ if is_error_code(token) => annotations.error_codes.push(token.to_string()),
+                _ => match token.strip_prefix("edition") {
+                    Some(edition) if !edition.is_empty() => {
+                        annotations.edition = Some(edition.to_string())
+                    }
+                    _ if tagged.is_some() => continue,
+                    _ => return None,
+                },
+            }
+            any = true;
+        }
+        (tagged.is_some() || any).then_some(annotations)
+    }
+}
+
+fn is_error_code(token: &str) -> bool {
+    token.len() == 5 && token.starts_with('E') && token[1..].bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Every snippet under `config.root`, sorted by file then line.
+///
+/// The notes outside of `examples/` are mostly excerpts that do not build on
+/// their own, so only those of their blocks that name the error they are
+/// about, like `rust,compile_fail,E0499`, are snippets.
+pub fn collect_snippets(config: &Config) -> io::Result<Vec<Snippet>> {
+    let mut files = Vec::new();
+    collect_files(&config.root, &config.vendor(), &mut files)?;
+    files.sort();
+
+    let examples = config.root.join("examples");
+    let mut snippets = Vec::new();
+    for file in files {
+        let path = file
+            .strip_prefix(&config.root)
+            .unwrap_or(&file)
+            .to_path_buf();
+        if file.extension().is_some_and(|ext| ext == "rs") {
+            let source = fs::read_to_string(&file)?;
+            let annotations = source
+                .lines()
+                .next()
+                .and_then(|line| line.trim().strip_prefix("// snippet:"))
+                .and_then(Annotations::parse)
+                .unwrap_or_default();
+            snippets.push(Snippet::new(config, path, None, source, annotations, &[]));
+        } else {
+            let example = file.parent() == Some(&examples);
+            let markdown = fs::read_to_string(&file)?;
+            let lines: Vec<&str> = markdown.lines().collect();
+            // The line after the previous block, 0-based.
+            let mut prose_start = 0;
+            // The snippet of the previous block, if it must not compile.
+            let mut failing = None;
+            for block in fenced_blocks(&markdown) {
+                let prose = &lines[prose_start..block.line - 1];
+                prose_start = block.end;
+                let marker = Marker::of(prose.iter().copied()).filter(|_| example);
+                let previous = failing.take();
+                if marker == Some(Marker::Output) {
+                    // The errors of the log of a block that must not compile
+                    // are the ones it must fail with.
+                    if let Some(previous) = previous {
+                        let snippet: &mut Snippet = &mut snippets[previous];
+                        if snippet.annotations.error_codes.is_empty() {
+                            snippet.annotations.error_codes = markers::error_codes(&block.code);
+                        }
+                    }
+                    continue;
+                }
+                let Some(mut annotations) = Annotations::parse(block.info) else {
+                    continue;
+                };
+                if marker == Some(Marker::Fails) {
+                    annotations.compile_fail = true;
+                    failing = Some(snippets.len());
+                }
+                if example || (annotations.compile_fail && !annotations.error_codes.is_empty()) {
+                    let declared = markers::declared_crates(prose.iter().copied());
+                    let mut snippet = Snippet::new(
+                        config,
+                        path.clone(),
+                        Some(block.line),
+                        block.code,
+                        annotations,
+                        &declared,
+                    );
+                    snippet.marker = marker;
+                    snippets.push(snippet);
+                }
+            }
+        }
+    }
+    Ok(snippets)
+}
+
+/// The `.rs` and `.md` files under `dir`, leaving out the targets of cargo
+/// packages, which cargo already builds, the build directories, and the
+/// vendored crates.
+fn collect_files(dir: &Path, vendor: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let package = dir.join("Cargo.toml").is_file();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if name.starts_with('.') || name == "target" || path == vendor {
+            continue;
+        }
+        if path.is_dir() {
+            if !(package && matches!(&*name, "src" | "tests" | "benches" | "examples")) {
+                collect_files(&path, vendor, files)?;
+            }
+        } else if (name.ends_with(".rs") && !(package && name == "build.rs"))
+            || name.ends_with(".md")
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+struct FencedBlock<'a> {
+    /// The line of the opening fence, 1-based.
+    line: usize,
+    /// The line of the closing fence, 1-based.
+    end: usize,
+    info: &'a str,
+    code: String,
+}
+
+/// The blocks fenced with backticks, without the indentation of their fence,
+/// and with the hidden lines of rustdoc, those starting with `# `, shown.
+fn fenced_blocks(markdown: &str) -> Vec<FencedBlock<'_>> {
+    let mut blocks = Vec::new();
+    let mut open: Option<(usize, FencedBlock)> = None;
+    for (i, line) in markdown.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let indent = line.len() - trimmed.len();
+        match &mut open {
+            None => {
+                if let Some(info) = trimmed.strip_prefix("```") {
+                    let block = FencedBlock {
+                        line: i + 1,
+                        end: 0,
+                        info: info.trim(),
+                        code: String::new(),
+                    };
+                    open = Some((indent, block));
+                }
+            }
+            Some(_)
+                if trimmed.starts_with("```") && trimmed.trim_matches('`').trim().is_empty() =>
+            {
+                let (_, mut block) = open.take().unwrap();
+                block.end = i + 1;
+                blocks.push(block);
+            }
+            Some((fence_indent, block)) => {
+                let line = &line[indent.min(*fence_indent)..];
+                let line = match line.strip_prefix("# ") {
+                    Some(hidden) => hidden,
+                    None if line == "#" => "",
+                    None => line,
+                };
+                block.code.push_str(line);
+                block.code.push('\n');
+            }
+        }
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn info_strings() {
+        assert_eq!(Annotations::parse("rust"), Some(Annotations::default()));
+        assert_eq!(Annotations::parse("text"), None);
+        assert_eq!(Annotations::parse(""), None);
+        let annotations = Annotations::parse("rust,compile_fail,E0499 edition2018").unwrap();
+        assert!(annotations.compile_fail);
+        assert_eq!(annotations.edition.as_deref(), Some("2018"));
+        assert_eq!(annotations.error_codes, ["E0499"]);
+        assert!(Annotations::parse("should_panic").unwrap().should_panic);
+        assert_eq!(Annotations::parse("should_panic,text"), None);
+    }
+
+    #[test]
+    fn blocks_of_a_list() {
+        let markdown = "\
+- first
+
+  ```rust
+  fn main() {
+  # let hidden = ();
+      println!();
+  }
+  ```
+
+```
+plain
+```
+";
+        let blocks = fenced_blocks(markdown);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].line, 3);
+        assert_eq!(blocks[0].info, "rust");
+        assert_eq!(
+            blocks[0].code,
+            "fn main() {\nlet hidden = ();\n    println!();\n}\n"
+        );
+        assert_eq!(blocks[1].info, "");
+    }
+}
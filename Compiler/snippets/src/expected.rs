@@ -0,0 +1,165 @@
+//! What a snippet is expected to print: the `.stdout` file next to it, or the
+//! comment block that follows an `// expected-output:` line of its source.
+//!
+//! ```rust
+//! println!("{}", 1 + 1);
+//! // expected-output:
+//! // 2
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::discover::Snippet;
+use crate::Config;
+
+const MARKER: &str = "// expected-output:";
+
+pub enum Expected {
+    /// Nothing is checked.
+    Nothing,
+    /// The `.stdout` file of a `.rs` file, compared as is.
+    Sidecar { path: PathBuf, stdout: String },
+    /// The comment block after the marker, compared line by line.
+    Comment {
+        /// The line of the marker in the source of the snippet, 0-based.
+        marker: usize,
+        lines: Vec<String>,
+    },
+}
+
+impl Expected {
+    pub fn of(config: &Config, snippet: &Snippet) -> io::Result<Expected> {
+        let mut lines = snippet.source.lines();
+        if let Some(marker) = lines.position(|line| line.trim() == MARKER) {
+            let lines = lines.map_while(comment).map(str::to_string).collect();
+            return Ok(Expected::Comment { marker, lines });
+        }
+        if snippet.line.is_some() {
+            return Ok(Expected::Nothing);
+        }
+        let path = config.root.join(&snippet.path).with_extension("stdout");
+        match fs::read_to_string(&path) {
+            Ok(stdout) => Ok(Expected::Sidecar { path, stdout }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Expected::Nothing),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Whether `stdout` is what was expected. Anything is, when nothing is
+    /// expected.
+    pub fn matches(&self, stdout: &str) -> bool {
+        match self {
+            Expected::Nothing => true,
+            Expected::Sidecar {
+                stdout: expected, ..
+            } => expected == stdout,
+            Expected::Comment { lines, .. } => lines.iter().map(String::as_str).eq(stdout.lines()),
+        }
+    }
+
+    /// A unified diff from what was expected to `stdout`.
+    pub fn diff(&self, stdout: &str) -> String {
+        let expected = match self {
+            Expected::Nothing => String::new(),
+            Expected::Sidecar { stdout, .. } => stdout.clone(),
+            Expected::Comment { lines, .. } => {
+                lines.iter().map(|line| format!("{line}\n")).collect()
+            }
+        };
+        similar::TextDiff::from_lines(expected.as_str(), stdout)
+            .unified_diff()
+            .header("expected", "actual")
+            .to_string()
+    }
+
+    /// Where the expectation is, for the failures.
+    pub fn describe(&self, snippet: &Snippet) -> String {
+        match self {
+            Expected::Sidecar { path, .. } => path.display().to_string(),
+            _ => format!("the `{MARKER}` block of {}", snippet.name()),
+        }
+    }
+}
+
+/// Makes `stdout` what `snippet` is expected to print: rewrites its comment
+/// block, or its `.stdout` file, which a `.rs` file without any expectation
+/// gets when it prints something.
+///
+/// The comment block of a fenced block is rewritten in the markdown file, so
+/// the blocks of a file must be blessed from the last to the first, for their
+/// lines to still be right.
+pub fn bless(config: &Config, snippet: &Snippet, stdout: &str) -> io::Result<()> {
+    let path = config.root.join(&snippet.path);
+    match Expected::of(config, snippet)? {
+        Expected::Comment { marker, lines } => {
+            // The source of a fenced block starts on the line after its fence.
+            let marker = marker + snippet.line.unwrap_or(0);
+            let file = fs::read_to_string(&path)?;
+            let mut file_lines: Vec<&str> = file.split_inclusive('\n').collect();
+            let indent = &file_lines[marker]
+                [..file_lines[marker].len() - file_lines[marker].trim_start().len()];
+            let block = stdout
+                .lines()
+                .map(|line| match line {
+                    "" => format!("{indent}//\n"),
+                    _ => format!("{indent}// {line}\n"),
+                })
+                .collect::<Vec<_>>();
+            file_lines.splice(
+                marker + 1..marker + 1 + lines.len(),
+                block.iter().map(String::as_str),
+            );
+            fs::write(&path, file_lines.concat())
+        }
+        Expected::Sidecar { path, .. } if stdout.is_empty() => fs::remove_file(path),
+        Expected::Sidecar { path, .. } => fs::write(path, stdout),
+        Expected::Nothing if snippet.line.is_none() && !stdout.is_empty() => {
+            fs::write(path.with_extension("stdout"), stdout)
+        }
+        Expected::Nothing => Ok(()),
+    }
+}
+
+/// The text of a line of the comment block, without its `// `.
+fn comment(line: &str) -> Option<&str> {
+    let text = line.trim_start().strip_prefix("//")?;
+    Some(text.strip_prefix(' ').unwrap_or(text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comment_block() {
+        let snippet = Snippet {
+            path: "examples/1.md".into(),
+            line: Some(3),
+            source: "\
+fn main() {
+    println!(\"a\\n\\nb\");
+}
+// expected-output:
+// a
+//
+// b
+
+fn f() {}
+"
+            .to_string(),
+            annotations: Default::default(),
+            marker: None,
+            dependencies: Vec::new(),
+            skip: None,
+        };
+        let config = Config::repository();
+        let expected = Expected::of(&config, &snippet).unwrap();
+        assert!(
+            matches!(expected, Expected::Comment { marker: 3, ref lines } if lines == &["a", "", "b"])
+        );
+        assert!(expected.matches("a\n\nb\n"));
+        assert!(!expected.matches("a\nb\n"));
+    }
+}
@@ -0,0 +1,220 @@
+//! Runs the Rust snippets of the repository, which are not part of any cargo
+//! package: every `.rs` file outside of the packages, every fenced Rust
+//! block of `examples/*.md`, and the blocks of the other notes that expect
+//! an error, like `rust,compile_fail,E0499`. Each one is built as a crate of its own, then
+//! its `main` and its tests are run, and the outcomes printed as a matrix.
+//!
+//! The annotations of rustdoc are honored: `ignore`, `no_run`,
+//! `compile_fail`, with the codes of the errors expected, `should_panic` and
+//! `edition2018`, in the info string of a fence, or in a `// snippet:
+//! compile_fail` comment on the first line of a file. With `--report FILE`,
+//! the errors of the `compile_fail` snippets are written next to their code
+//! in a markdown report.
+//!
+//! In `examples/*.md`, a `COMPILE!` or `COMPILE ERROR!` line before a block
+//! says whether it must compile, and the errors of the log after an `ERROR`
+//! line are those it must fail with, see [`Marker`]. The crates a snippet
+//! uses, or adds with a `cargo add` line before its block, are taken from the
+//! directory of `--vendor DIR`, `vendor/` by default, as made by `cargo
+//! vendor`. The snippets that need a crate that is not there are ignored.
+//!
+//! What `main` prints is compared with the `.stdout` file next to a `.rs`
+//! file, or with the comment block after an `// expected-output:` line of a
+//! snippet, see [`expected`]. With `--bless`, those that differ are updated,
+//! and the `.rs` files that print something get a `.stdout` file.
+//!
+//! ```text
+//! cargo run -p snippets -- [--bless] [--report FILE] [--root DIR] [--vendor DIR] [--jobs N] [FILTER]...
+//! ```
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+mod crates;
+pub mod diagnostics;
+mod discover;
+pub mod expected;
+mod markers;
+mod runtest;
+
+pub use discover::{collect_snippets, Annotations, Snippet};
+pub use markers::Marker;
+pub use runtest::{run_snippet, Outcome, Status};
+
+pub struct Config {
+    /// The directory the snippets are collected from.
+    pub root: PathBuf,
+    /// Update the expected output of the snippets that do not print it,
+    /// instead of failing.
+    pub bless: bool,
+    /// Where to write the report of the `compile_fail` snippets.
+    pub report: Option<PathBuf>,
+    /// Only run the snippets whose name contains one of these.
+    pub filters: Vec<String>,
+    /// How many snippets are built at once.
+    pub jobs: usize,
+    /// The directory made by `cargo vendor` the crates the snippets use are
+    /// taken from, `vendor/` under the root if `None`.
+    pub vendor: Option<PathBuf>,
+}
+
+impl Config {
+    /// The configuration running every snippet of the repository.
+    pub fn repository() -> Config {
+        Config {
+            root: Path::new(env!("CARGO_MANIFEST_DIR")).join("../.."),
+            bless: false,
+            report: None,
+            filters: Vec::new(),
+            jobs: thread::available_parallelism().map_or(1, |jobs| jobs.get()),
+            vendor: None,
+        }
+    }
+
+    pub fn vendor(&self) -> PathBuf {
+        self.vendor
+            .clone()
+            .unwrap_or_else(|| self.root.join("vendor"))
+    }
+}
+
+/// Runs every snippet selected by `config`, then prints the matrix of their
+/// outcomes and why those that failed did. Returns whether all of them
+/// passed.
+pub fn run_snippets(config: &Config) -> io::Result<bool> {
+    let mut snippets = collect_snippets(config)?;
+    snippets.retain(|snippet| {
+        let name = snippet.name();
+        config.filters.is_empty() || config.filters.iter().any(|f| name.contains(f.as_str()))
+    });
+    println!("\nrunning {} snippets", snippets.len());
+
+    let temp = env::temp_dir().join(format!("snippets-{}", std::process::id()));
+    let outcomes = run_all(config, &snippets, &temp);
+    fs::remove_dir_all(&temp).or_else(|err| match err.kind() {
+        io::ErrorKind::NotFound => Ok(()),
+        _ => Err(err),
+    })?;
+    let outcomes = outcomes?;
+    // From the last snippet, as blessing a fenced block moves the next ones.
+    for (snippet, outcome) in snippets.iter().zip(&outcomes).rev() {
+        if let Some(stdout) = outcome.as_ref().and_then(|o| o.blessed.as_ref()) {
+            expected::bless(config, snippet, stdout)?;
+        }
+    }
+
+    if let Some(report) = &config.report {
+        diagnostics::write_report(report, &snippets, &outcomes)?;
+    }
+
+    print_matrix(&snippets, &outcomes);
+    let failures: Vec<_> = snippets
+        .iter()
+        .zip(&outcomes)
+        .filter(|(_, outcome)| outcome.as_ref().is_some_and(|o| !o.problems.is_empty()))
+        .collect();
+    if !failures.is_empty() {
+        println!("\nfailures:\n");
+        for (snippet, outcome) in &failures {
+            println!("---- {} ----", snippet.name());
+            for problem in &outcome.as_ref().unwrap().problems {
+                println!("{}", problem.trim_end());
+            }
+            println!();
+        }
+    }
+    let ignored = outcomes.iter().filter(|outcome| outcome.is_none()).count();
+    println!(
+        "\nsnippet result: {}. {} passed; {} failed; {} ignored\n",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        snippets.len() - failures.len() - ignored,
+        failures.len(),
+        ignored
+    );
+    Ok(failures.is_empty())
+}
+
+/// The outcome of every snippet, `None` for the ignored ones, built by
+/// `config.jobs` threads in directories of `temp`.
+fn run_all(config: &Config, snippets: &[Snippet], temp: &Path) -> io::Result<Vec<Option<Outcome>>> {
+    let next = AtomicUsize::new(0);
+    let outcomes: Mutex<Vec<_>> = Mutex::new(snippets.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..config.jobs.max(1))
+            .map(|_| {
+                scope.spawn(|| -> io::Result<()> {
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(snippet) = snippets.get(i) else {
+                            return Ok(());
+                        };
+                        if snippet.annotations.ignore || snippet.skip.is_some() {
+                            continue;
+                        }
+                        let dir = temp.join(i.to_string());
+                        let outcome = run_snippet(config, snippet, &dir)?;
+                        fs::remove_dir_all(&dir)?;
+                        outcomes.lock().unwrap()[i] = Some(outcome);
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .try_for_each(|worker| worker.join().unwrap())
+    })?;
+    Ok(outcomes.into_inner().unwrap())
+}
+
+fn print_matrix(snippets: &[Snippet], outcomes: &[Option<Outcome>]) {
+    let names: Vec<_> = snippets.iter().map(Snippet::name).collect();
+    let width = names
+        .iter()
+        .map(|name| name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("snippet".len());
+    println!(
+        "\n{:width$}  compile   main      tests     stdout    result",
+        "snippet"
+    );
+    for ((name, snippet), outcome) in names.iter().zip(snippets).zip(outcomes) {
+        let Some(outcome) = outcome else {
+            let reason = snippet
+                .skip
+                .as_ref()
+                .map(|reason| format!(", {reason}"))
+                .unwrap_or_default();
+            println!(
+                "{name:width$}  {0:8}  {0:8}  {0:8}  {0:8}  ignored{reason}",
+                Status::NotRun
+            );
+            continue;
+        };
+        let result = if outcome.problems.is_empty() {
+            "ok"
+        } else {
+            "FAILED"
+        };
+        let annotations = &snippet.annotations;
+        let compile_fail = [&["compile_fail".to_string()], &annotations.error_codes[..]].concat();
+        let expected = [
+            (compile_fail.join(" "), annotations.compile_fail),
+            ("should_panic".to_string(), annotations.should_panic),
+            ("no_run".to_string(), annotations.no_run),
+        ]
+        .iter()
+        .filter(|(_, annotated)| *annotated)
+        .map(|(annotation, _)| format!(" ({annotation})"))
+        .collect::<String>();
+        println!(
+            "{name:width$}  {:8}  {:8}  {:8}  {:8}  {result}{expected}",
+            outcome.compile, outcome.main, outcome.tests, outcome.stdout
+        );
+    }
+}
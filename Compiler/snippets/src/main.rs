@@ -0,0 +1,58 @@
+use std::process::ExitCode;
+
+use snippets::Config;
+
+fn main() -> ExitCode {
+    let mut config = Config::repository();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bless" => config.bless = true,
+            "--report" => match args.next() {
+                Some(file) => config.report = Some(file.into()),
+                None => {
+                    eprintln!("error: `--report` needs a file");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--root" => match args.next() {
+                Some(dir) => config.root = dir.into(),
+                None => {
+                    eprintln!("error: `--root` needs a directory");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--vendor" => match args.next() {
+                Some(dir) => config.vendor = Some(dir.into()),
+                None => {
+                    eprintln!("error: `--vendor` needs a directory");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "--jobs" | "-j" => match args.next().and_then(|jobs| jobs.parse().ok()) {
+                Some(jobs) => config.jobs = jobs,
+                None => {
+                    eprintln!("error: `--jobs` needs a number");
+                    return ExitCode::FAILURE;
+                }
+            },
+            "-h" | "--help" => {
+                println!(
+                    "usage: snippets [--bless] [--report FILE] [--root DIR] [--vendor DIR] [--jobs N] \
+                     [FILTER]..."
+                );
+                return ExitCode::SUCCESS;
+            }
+            _ => config.filters.push(arg),
+        }
+    }
+
+    match snippets::run_snippets(&config) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! The markers of `examples/*.md`: a line like `COMPILE!` or `COMPILE
+//! ERROR!` before a fenced block, with maybe some prose in between, says
+//! whether the block compiles, and an `ERROR` or `OUTPUT` line says that the
+//! block is what rustc, or the program, printed.
+//!
+//! ````markdown
+//! COMPILE ERROR!
+//!
+//! ```rust
+//! fn main() { let n: i32 = "1"; }
+//! ```
+//!
+//! ERROR
+//!
+//! ```rs
+//! error[E0308]: mismatched types
+//! ```
+//! ````
+//!
+//! The markers were written by hand, so they are read leniently: in any case,
+//! with or without their `!` or a `:`, and with the misspellings found in the
+//! notes.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Marker {
+    /// `COMPILE!`: the block must compile.
+    Compiles,
+    /// `COMPILE ERROR!`: the block must not compile.
+    Fails,
+    /// `ERROR` or `OUTPUT`: the block is not code.
+    Output,
+}
+
+impl Marker {
+    pub fn parse(line: &str) -> Option<Marker> {
+        let line = line.trim().trim_end_matches(['!', ':']).to_uppercase();
+        match line.split_whitespace().collect::<Vec<_>>()[..] {
+            ["COMPILE" | "COMPILES"] => Some(Marker::Compiles),
+            ["COMPILE" | "COMPILER", "ERROR" | "ERRROR"]
+            | ["NOT", "COMPILE"]
+            | ["DOES", "NOT", "COMPILE"] => Some(Marker::Fails),
+            ["ERROR" | "OUTPUT"] => Some(Marker::Output),
+            _ => None,
+        }
+    }
+
+    /// The last marker among the lines of prose before a block.
+    pub fn of<'a>(prose: impl DoubleEndedIterator<Item = &'a str>) -> Option<Marker> {
+        prose.rev().find_map(Marker::parse)
+    }
+}
+
+/// The crates the lines of prose before a block add, like `cargo add nannou`.
+pub fn declared_crates<'a>(prose: impl Iterator<Item = &'a str>) -> Vec<String> {
+    prose
+        .filter_map(|line| line.trim().strip_prefix("cargo add "))
+        .flat_map(|args| {
+            args.split_whitespace()
+                .take_while(|arg| !arg.starts_with('-'))
+        })
+        .map(|krate| krate.split('@').next().unwrap_or(krate).to_string())
+        .collect()
+}
+
+/// The codes of the errors of a log of rustc, like `E0308` for
+/// `error[E0308]: mismatched types`, once each.
+pub fn error_codes(log: &str) -> Vec<String> {
+    let mut codes: Vec<String> = log
+        .match_indices("error[")
+        .filter_map(|(i, _)| log[i + "error[".len()..].split_once(']'))
+        .map(|(code, _)| code.to_string())
+        .collect();
+    codes.dedup();
+    codes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markers_as_written() {
+        for line in ["COMPILE!", "COMPILE", "COMPILE ", "COMPILE !", "COMPILE:"] {
+            assert_eq!(Marker::parse(line), Some(Marker::Compiles), "{line:?}");
+        }
+        for line in [
+            "COMPILE ERROR!",
+            "COMPILE ERROR",
+            "COMPILER ERROR!",
+            "COMPILE ERRROR!",
+            "NOT COMPILE!",
+        ] {
+            assert_eq!(Marker::parse(line), Some(Marker::Fails), "{line:?}");
+        }
+        assert_eq!(Marker::parse("ERROR"), Some(Marker::Output));
+        assert_eq!(Marker::parse("Output:"), Some(Marker::Output));
+        assert_eq!(Marker::parse("PROTOTYPE"), None);
+        assert_eq!(Marker::parse("It will compile!"), None);
+    }
+
+    #[test]
+    fn cargo_add() {
+        let prose = "\ncargo add nannou\n\ncargo add tokio@1 serde --features full\n";
+        assert_eq!(declared_crates(prose.lines()), ["nannou", "tokio", "serde"]);
+    }
+}
@@ -0,0 +1,351 @@
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::diagnostics::{self, Diagnostic};
+use crate::discover::Snippet;
+use crate::expected::Expected;
+use crate::markers::Marker;
+use crate::Config;
+
+/// How long `main`, or the tests, may run before they are killed, as some
+/// snippets serve forever or wait on stdin.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What became of one step of a snippet: compiling it, running its `main`,
+/// or running its tests.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Failed,
+    /// Exited with the code of a panic.
+    Panicked,
+    TimedOut,
+    /// The output did not match, and its expectation has been updated.
+    Blessed,
+    NotRun,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Status::Ok => "ok",
+            Status::Failed => "failed",
+            Status::Panicked => "panicked",
+            Status::TimedOut => "timeout",
+            Status::Blessed => "blessed",
+            Status::NotRun => "-",
+        })
+    }
+}
+
+impl Status {
+    /// What happened to a step that did not succeed, for the failures.
+    fn verb(self) -> &'static str {
+        match self {
+            Status::Panicked => "panicked",
+            Status::TimedOut => "timed out",
+            _ => "failed",
+        }
+    }
+}
+
+pub struct Outcome {
+    pub compile: Status,
+    pub main: Status,
+    pub tests: Status,
+    /// Whether `main` printed what the snippet expects.
+    pub stdout: Status,
+    /// What rustc said when building the snippet.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Every reason the snippet failed, given its annotations.
+    pub problems: Vec<String>,
+    /// The output to bless the expectation of the snippet with.
+    pub blessed: Option<String>,
+}
+
+impl Outcome {
+    fn new() -> Outcome {
+        Outcome {
+            compile: Status::NotRun,
+            main: Status::NotRun,
+            tests: Status::NotRun,
+            stdout: Status::NotRun,
+            diagnostics: Vec::new(),
+            problems: Vec::new(),
+            blessed: None,
+        }
+    }
+}
+
+/// Builds the snippet as the `main.rs` of a crate of its own in `dir`, then
+/// runs its `main`, checking what it prints, and, if it has some, its tests.
+pub fn run_snippet(config: &Config, snippet: &Snippet, dir: &Path) -> io::Result<Outcome> {
+    let annotations = &snippet.annotations;
+    let mut outcome = Outcome::new();
+    let edition = annotations.edition.as_deref().unwrap_or("2021");
+    fs::create_dir_all(dir.join("src"))?;
+    let dependencies: String = snippet
+        .dependencies
+        .iter()
+        .map(|package| format!("{package} = \"*\"\n"))
+        .collect();
+    fs::write(
+        dir.join("Cargo.toml"),
+        format!(
+            "[package]\nname = \"snippet\"\nversion = \"0.0.0\"\nedition = \"{edition}\"\n\n\
+             # Not a member of whatever workspace the directory is in.\n[workspace]\n\n\
+             [dependencies]\n{dependencies}"
+        ),
+    )?;
+    if !snippet.dependencies.is_empty() {
+        // Cargo reads its configuration from the directory it is run in.
+        fs::create_dir_all(dir.join(".cargo"))?;
+        fs::write(
+            dir.join(".cargo/config.toml"),
+            format!(
+                "[source.crates-io]\nreplace-with = \"vendored-sources\"\n\n\
+                 [source.vendored-sources]\ndirectory = {:?}\n",
+                std::path::absolute(config.vendor())?
+            ),
+        )?;
+    }
+    fs::write(dir.join("src/main.rs"), crate_root(&snippet.source))?;
+
+    let build = cargo(dir, "build").arg("--message-format=json").output()?;
+    outcome.diagnostics = diagnostics::parse(&String::from_utf8_lossy(&build.stdout));
+    outcome.compile = if build.status.success() {
+        Status::Ok
+    } else {
+        Status::Failed
+    };
+    match (build.status.success(), annotations.compile_fail) {
+        (true, true) if snippet.marker == Some(Marker::Fails) => outcome
+            .problems
+            .push("compiled, but is marked `COMPILE ERROR!`".to_string()),
+        (true, true) => outcome
+            .problems
+            .push("compiled, but is annotated `compile_fail`".to_string()),
+        (false, true) => outcome.problems.extend(diagnostics::check_error_codes(
+            &annotations.error_codes,
+            &outcome.diagnostics,
+        )),
+        (false, false) => {
+            let errors: String = diagnostics::errors(&outcome.diagnostics)
+                .map(|diag| diag.rendered.as_str())
+                .collect();
+            outcome.problems.push(format!(
+                "does not compile:\n{errors}{}",
+                String::from_utf8_lossy(&build.stderr)
+            ))
+        }
+        (true, false) => {}
+    }
+    if !build.status.success() || annotations.no_run {
+        return Ok(outcome);
+    }
+
+    let run = run_with_timeout(Command::new(dir.join("target/debug/snippet")))?;
+    outcome.main = run.status;
+    match (run.status, annotations.should_panic) {
+        (Status::Ok, true) => outcome
+            .problems
+            .push("`main` returned, but is annotated `should_panic`".to_string()),
+        (Status::Panicked, true) | (Status::Ok, false) => {}
+        (status, _) => outcome
+            .problems
+            .push(format!("`main` {}:\n{}", status.verb(), run.stderr)),
+    }
+    if run.status != Status::TimedOut {
+        check_stdout(config, snippet, &run.stdout, &mut outcome)?;
+    }
+
+    if snippet.source.contains("#[test]") {
+        let tests = run_tests(dir)?;
+        outcome.tests = tests.status;
+        if tests.status != Status::Ok {
+            outcome.problems.push(format!(
+                "the tests {}:\n{}{}",
+                tests.status.verb(),
+                tests.stdout,
+                tests.stderr
+            ));
+        }
+    }
+    Ok(outcome)
+}
+
+fn check_stdout(
+    config: &Config,
+    snippet: &Snippet,
+    stdout: &str,
+    outcome: &mut Outcome,
+) -> io::Result<()> {
+    let expected = Expected::of(config, snippet)?;
+    let nothing = matches!(expected, Expected::Nothing);
+    outcome.stdout = if expected.matches(stdout) && !nothing {
+        Status::Ok
+    } else if config.bless && !(nothing && (snippet.line.is_some() || stdout.is_empty())) {
+        outcome.blessed = Some(stdout.to_string());
+        Status::Blessed
+    } else if nothing {
+        Status::NotRun
+    } else {
+        outcome.problems.push(format!(
+            "{} does not match the output of `main`, run with `--bless` to update it:\n{}",
+            expected.describe(snippet),
+            expected.diff(stdout)
+        ));
+        Status::Failed
+    };
+    Ok(())
+}
+
+/// The snippet as the root of a binary crate. Like rustdoc, statements are
+/// wrapped in a `main`, and items without one get an empty `main` after them,
+/// where any tests among them are still found.
+pub(crate) fn crate_root(source: &str) -> String {
+    match syn::parse_file(source) {
+        Ok(file) => {
+            let has_main = file
+                .items
+                .iter()
+                .any(|item| matches!(item, syn::Item::Fn(f) if f.sig.ident == "main"));
+            if has_main {
+                source.to_string()
+            } else {
+                format!("{source}\nfn main() {{}}\n")
+            }
+        }
+        Err(_) => {
+            // The inner attributes of the crate cannot go in the `main`.
+            let body_start = source
+                .lines()
+                .take_while(|line| line.trim().is_empty() || line.trim_start().starts_with("#!["))
+                .map(|line| line.len() + 1)
+                .sum::<usize>()
+                .min(source.len());
+            let (attributes, body) = source.split_at(body_start);
+            format!(
+                "{attributes}#![allow(unused)]\nfn main() {{\n{}\n}}\n",
+                body.trim_end()
+            )
+        }
+    }
+}
+
+/// Cargo, on its own, for the crate of `dir`: the snippets may not need any
+/// dependency from the network, only from the vendor directory, and every
+/// crate has its own target directory.
+fn cargo(dir: &Path, subcommand: &str) -> Command {
+    let mut cargo = Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()));
+    cargo
+        .arg(subcommand)
+        .args(["--offline", "--quiet", "--manifest-path"])
+        .arg(dir.join("Cargo.toml"))
+        .current_dir(dir)
+        .env("CARGO_TARGET_DIR", dir.join("target"))
+        .stdin(Stdio::null());
+    cargo
+}
+
+/// Builds the test harness of the crate of `dir`, then runs it.
+fn run_tests(dir: &Path) -> io::Result<Run> {
+    let build = cargo(dir, "test")
+        .args(["--no-run", "--message-format=json-render-diagnostics"])
+        .output()?;
+    let harness = String::from_utf8_lossy(&build.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .find_map(|message| message["executable"].as_str().map(PathBuf::from));
+    match harness {
+        Some(harness) if build.status.success() => {
+            let mut run = run_with_timeout(Command::new(harness))?;
+            // The harness exits with the code of a panic when a test fails.
+            if run.status == Status::Panicked {
+                run.status = Status::Failed;
+            }
+            Ok(run)
+        }
+        _ => Ok(Run {
+            status: Status::Failed,
+            stdout: String::new(),
+            stderr: String::from_utf8_lossy(&build.stderr).into_owned(),
+        }),
+    }
+}
+
+struct Run {
+    status: Status,
+    stdout: String,
+    stderr: String,
+}
+
+/// Runs `command` without stdin, killing it after [`TIMEOUT`].
+fn run_with_timeout(mut command: Command) -> io::Result<Run> {
+    let mut child = command
+        .env_remove("RUST_BACKTRACE")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Read on their own threads, so that a full pipe does not block the child.
+    let stdout = read_to_end(child.stdout.take());
+    let stderr = read_to_end(child.stderr.take());
+
+    let deadline = Instant::now() + TIMEOUT;
+    let status = loop {
+        if let Some(exit) = child.try_wait()? {
+            break exit_status(exit);
+        }
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            break Status::TimedOut;
+        }
+        thread::sleep(Duration::from_millis(20));
+    };
+    Ok(Run {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    })
+}
+
+fn read_to_end(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        String::from_utf8_lossy(&buf).into_owned()
+    })
+}
+
+fn exit_status(exit: ExitStatus) -> Status {
+    match exit.code() {
+        Some(0) => Status::Ok,
+        // The exit code of the panics of `main`.
+        Some(101) => Status::Panicked,
+        _ => Status::Failed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_what_is_not_a_crate() {
+        assert_eq!(
+            crate_root("#![allow(dead_code)]\nlet x = 1;\n"),
+            "#![allow(dead_code)]\n#![allow(unused)]\nfn main() {\nlet x = 1;\n}\n"
+        );
+        assert_eq!(crate_root("fn f() {}\n"), "fn f() {}\n\nfn main() {}\n");
+        assert_eq!(crate_root("fn main() {}\n"), "fn main() {}\n");
+    }
+}
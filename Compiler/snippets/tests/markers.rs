@@ -0,0 +1,29 @@
+use std::path::Path;
+use std::process::Command;
+
+#[test]
+fn markers() {
+    let root = Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/markers"));
+    let output = Command::new(env!("CARGO_BIN_EXE_snippets"))
+        .arg("--root")
+        .arg(root)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!output.status.success(), "{stdout}");
+    let expected = "
+running 5 snippets
+
+snippet                 compile   main      tests     stdout    result
+examples/markers.md:5   ok        ok        -         -         ok
+examples/markers.md:13  failed    -         -         -         ok (compile_fail E0308)
+examples/markers.md:28  failed    -         -         -         FAILED
+examples/markers.md:36  ok        ok        -         -         FAILED (compile_fail)
+examples/markers.md:42  -         -         -         -         ignored, needs `tokio`, not in vendor
+";
+    assert!(stdout.starts_with(expected), "{stdout}");
+    assert!(stdout.contains("---- examples/markers.md:28 ----\ndoes not compile:\nerror[E0308]"));
+    assert!(stdout
+        .contains("---- examples/markers.md:36 ----\ncompiled, but is marked `COMPILE ERROR!`\n"));
+    assert!(stdout.ends_with("snippet result: FAILED. 2 passed; 2 failed; 1 ignored\n\n"));
+}
@@ -0,0 +1,136 @@
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn snippets(root: &Path, args: &[&str]) -> (Output, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_snippets"))
+        .arg("--root")
+        .arg(root)
+        .args(args)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout.clone()).unwrap();
+    (output, stdout)
+}
+
+fn fixtures() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/snippets"))
+}
+
+#[test]
+fn matrix() {
+    let (output, stdout) = snippets(fixtures(), &[]);
+    assert!(!output.status.success(), "{stdout}");
+    let expected = "
+running 16 snippets
+
+snippet                compile   main      tests     stdout    result
+broken.rs              failed    -         -         -         FAILED
+examples/blocks.md:3   ok        ok        -         -         ok
+examples/blocks.md:8   failed    -         -         -         ok (compile_fail E0502)
+examples/blocks.md:15  ok        panicked  -         -         ok (should_panic)
+examples/blocks.md:22  ok        panicked  -         -         FAILED
+examples/blocks.md:28  ok        -         -         -         ok (no_run)
+examples/blocks.md:34  -         -         -         -         ignored
+examples/blocks.md:44  ok        ok        -         -         ok
+examples/output.md:3   ok        ok        -         ok        ok
+examples/output.md:12  ok        ok        -         failed    FAILED
+greet.rs               ok        ok        -         -         ok
+hello.rs               ok        ok        -         ok        ok
+notes/errors.md:5      failed    -         -         -         ok (compile_fail E0499)
+notes/errors.md:13     failed    -         -         -         FAILED (compile_fail E0277)
+rejected.rs            failed    -         -         -         ok (compile_fail)
+tested.rs              ok        ok        ok        -         ok
+";
+    assert!(stdout.starts_with(expected), "{stdout}");
+    assert!(stdout.contains("---- broken.rs ----\ndoes not compile:\nerror[E0308]"));
+    assert!(stdout.contains("---- examples/blocks.md:22 ----\n`main` panicked:\n"));
+    assert!(stdout.contains(
+        "\
+---- examples/output.md:12 ----
+the `// expected-output:` block of examples/output.md:12 does not match the output of `main`, \
+run with `--bless` to update it:
+--- expected
++++ actual
+@@ -1,3 +1,3 @@
+ 0
+ 1
+-2
++4
+"
+    ));
+    assert!(stdout.ends_with("snippet result: FAILED. 11 passed; 4 failed; 1 ignored\n\n"));
+}
+
+#[test]
+fn bless() {
+    let root = std::env::temp_dir().join(format!("snippets-bless-{}", std::process::id()));
+    fs::create_dir_all(root.join("examples")).unwrap();
+    for file in ["greet.rs", "hello.rs", "hello.stdout", "examples/output.md"] {
+        fs::copy(fixtures().join(file), root.join(file)).unwrap();
+    }
+
+    let (output, stdout) = snippets(&root, &["--bless"]);
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("examples/output.md:12  ok        ok        -         blessed   ok\n"));
+    assert!(stdout.contains("greet.rs               ok        ok        -         blessed   ok\n"));
+    let markdown = fs::read_to_string(root.join("examples/output.md")).unwrap();
+    assert!(markdown.ends_with("   // expected-output:\n   // 0\n   // 1\n   // 4\n   ```\n"));
+    assert_eq!(
+        fs::read_to_string(root.join("greet.stdout")).unwrap(),
+        "hello, Ferris\nhello, Corro\n"
+    );
+
+    let (output, stdout) = snippets(&root, &[]);
+    fs::remove_dir_all(&root).unwrap();
+    assert!(output.status.success(), "{stdout}");
+}
+
+#[test]
+fn report() {
+    let report = std::env::temp_dir().join(format!("snippets-report-{}.md", std::process::id()));
+    let (_, stdout) = snippets(
+        fixtures(),
+        &["--report", report.to_str().unwrap(), "notes/"],
+    );
+    assert!(
+        stdout.contains("---- notes/errors.md:13 ----\nexpected E0277, but rustc found E0308\n")
+    );
+    let report_text = fs::read_to_string(&report).unwrap();
+    fs::remove_file(&report).unwrap();
+    let expected = "\
+# Errors of the `compile_fail` snippets
+
+## notes/errors.md:5
+
+fails with E0499, as expected
+
+```rust
+#![allow(unused)]
+fn main() {
+let mut s = String::new();
+let a = &mut s;
+let b = &mut s;
+a.push('a');
+b.push('b');
+}
+```
+
+```text
+error[E0499]: cannot borrow `s` as mutable more than once at a time
+ --> src/main.rs:5:9
+  |
+4 | let a = &mut s;
+  |         ------ first mutable borrow occurs here
+5 | let b = &mut s;
+  |         ^^^^^^ second mutable borrow occurs here
+6 | a.push('a');
+  | - first borrow later used here
+```
+
+## notes/errors.md:13
+
+expected E0277, but rustc found E0308
+";
+    assert!(report_text.starts_with(expected), "{report_text}");
+}
@@ -0,0 +1,5 @@
+fn main() {
+    for name in ["Ferris", "Corro"] {
+        println!("hello, {name}");
+    }
+}
@@ -0,0 +1,2 @@
+// Built by cargo, not by the snippet runner.
+pub fn broken() -> i32 { "broken" }
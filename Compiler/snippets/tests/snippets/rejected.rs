@@ -0,0 +1,6 @@
+// snippet: compile_fail
+fn main() {
+    let s = String::new();
+    let t = s;
+    println!("{s}");
+}
@@ -0,0 +1,20 @@
+//! `collect-hrtb`: lists which lifetime parameters of each function of a
+//! file are late-bound, and every higher-ranked bound, spelling out the
+//! `for<'a>` that `Fn(&T)` and `fn(&T)` leave implicit. Like
+//! `collect-items`, it reads the source with `syn`, so it builds with any
+//! toolchain:
+//!
+//! ```text
+//! cargo run --bin collect-hrtb -- [--format text|json] FILE
+//! ```
+
+use std::process::ExitCode;
+
+use collect_source::hrtb::analyze_file;
+use collect_source::{read_file, run};
+
+fn main() -> ExitCode {
+    run("collect-hrtb [--format text|json] FILE", |path| {
+        read_file(path, analyze_file)
+    })
+}
@@ -0,0 +1,21 @@
+//! `collect-items`: lists the items of a crate, their generic parameters,
+//! lifetime parameters and where clauses, file by file, reading the source
+//! with `syn`. Unlike the `collect-lang-items` driver it needs neither nightly
+//! nor `rustc_private`, so it builds with any toolchain:
+//!
+//! ```text
+//! cargo run --bin collect-items -- [--format text|json] src/lib.rs
+//! ```
+
+use std::path::Path;
+use std::process::ExitCode;
+
+use collect_source::run;
+use collect_source::source::analyze_crate;
+
+fn main() -> ExitCode {
+    // The report still lists what could be read.
+    run("collect-items [--format text|json] CRATE_ROOT", |root| {
+        Ok(analyze_crate(Path::new(root)))
+    })
+}
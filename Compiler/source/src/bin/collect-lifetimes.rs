@@ -0,0 +1,19 @@
+//! `collect-lifetimes`: lists where the lifetimes of a file are declared and
+//! used, and spells out the lifetimes that elision gives each signature
+//! leaving some out. Like `collect-items`, it reads the source with `syn`, so
+//! it builds with any toolchain:
+//!
+//! ```text
+//! cargo run --bin collect-lifetimes -- [--format text|json] FILE
+//! ```
+
+use std::process::ExitCode;
+
+use collect_source::lifetimes::analyze_file;
+use collect_source::{read_file, run};
+
+fn main() -> ExitCode {
+    run("collect-lifetimes [--format text|json] FILE", |path| {
+        read_file(path, analyze_file)
+    })
+}
@@ -0,0 +1,651 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::mem;
+
+use serde::{Deserialize, Serialize};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+use crate::lifetimes::{position, types_with_lifetimes, Edit, ElisionSites, SourceText};
+use crate::Report;
+
+/// The binders of a file: which lifetime parameters of each function are
+/// late-bound, so that the function is higher-ranked over them, or
+/// early-bound, and every higher-ranked bound, with the lifetimes that
+/// `Fn(&T)` sugar and `fn(&T)` pointers bind without saying so.
+#[derive(Serialize, Deserialize)]
+pub struct HrtbReport {
+    pub path: String,
+    /// The functions with lifetime parameters, in source order.
+    pub functions: Vec<FunctionBinders>,
+    /// In source order.
+    pub bounds: Vec<HigherRankedBound>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct FunctionBinders {
+    /// E.g. `longest` or `Parser::next_token`.
+    pub function: String,
+    pub position: String,
+    /// Chosen anew at each call, like those of a `for<'a>` binder.
+    pub late_bound: Vec<String>,
+    /// Chosen once for the function item, like its type parameters.
+    pub early_bound: Vec<EarlyBound>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct EarlyBound {
+    pub lifetime: String,
+    /// `appears in a bound`, `does not appear in the arguments`, or `only
+    /// appears in the arguments under a projection`.
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct HigherRankedBound {
+    /// The item the bound is in.
+    pub item: String,
+    pub position: String,
+    /// `where clause` for `for<'a> &'a T: Trait`, `bound`, `trait object`
+    /// or `impl Trait` for a `for<'a> Trait<'a>` bound, or `fn pointer`.
+    pub kind: String,
+    pub written: String,
+    /// The lifetimes of the binder, written or elided.
+    pub binds: Vec<String>,
+    /// Whether some of [`Self::binds`] are elided, as in `Fn(&T)`.
+    pub elided: bool,
+    /// With every lifetime of the binder written out, e.g.
+    /// `for<'a> Fn(&'a T) -> &'a U`. `None` when the lifetime of the output
+    /// cannot be elided.
+    pub desugared: Option<String>,
+    /// For `Fn`, `FnMut` and `FnOnce`, without the parenthesized sugar, e.g.
+    /// `for<'a> Fn<(&'a T,), Output = &'a U>`.
+    pub unsugared: Option<String>,
+    /// Why the lifetime of the output cannot be elided.
+    pub error: Option<String>,
+}
+
+/// Reads the file `path`, whose source is `src`.
+pub fn analyze_file(path: &str, src: &str) -> syn::Result<HrtbReport> {
+    let file = syn::parse_file(src)?;
+    let mut collector = Collector {
+        text: SourceText::new(src),
+        with_lifetimes: types_with_lifetimes(&file),
+        item: String::new(),
+        kind: "bound",
+        in_scope: Vec::new(),
+        functions: Vec::new(),
+        bounds: Vec::new(),
+    };
+    collector.visit_file(&file);
+    Ok(HrtbReport {
+        path: path.to_string(),
+        functions: collector.functions,
+        bounds: collector.bounds,
+    })
+}
+
+/// The lifetimes named in a node.
+#[derive(Default)]
+struct Names {
+    names: Vec<String>,
+    /// Leaves out the projections like `<I as Trait<'a>>::Assoc`, which do
+    /// not constrain the lifetimes in them.
+    skip_projections: bool,
+}
+
+impl<'ast> Visit<'ast> for Names {
+    fn visit_lifetime(&mut self, i: &'ast syn::Lifetime) {
+        self.names.push(i.to_string());
+    }
+
+    fn visit_type_path(&mut self, i: &'ast syn::TypePath) {
+        if !(self.skip_projections && i.qself.is_some()) {
+            visit::visit_type_path(self, i);
+        }
+    }
+}
+
+fn names(visit: impl FnOnce(&mut Names)) -> Vec<String> {
+    let mut names = Names::default();
+    visit(&mut names);
+    names.names
+}
+
+/// Names the lifetimes of the bounds of the `impl Trait`s in a type.
+struct ImplTraitBounds<'n>(&'n mut Names);
+
+impl<'ast> Visit<'ast> for ImplTraitBounds<'_> {
+    fn visit_type_impl_trait(&mut self, i: &'ast syn::TypeImplTrait) {
+        self.0.visit_type_impl_trait(i);
+    }
+}
+
+/// Which lifetime parameters of `sig` are late-bound, and why the others
+/// are not, as rustc decides it: a lifetime is late-bound when the arguments
+/// constrain it and no bound mentions it, including those of the `impl
+/// Trait`s of the arguments, which are anonymous type parameters.
+fn binders(sig: &syn::Signature) -> (Vec<String>, Vec<EarlyBound>) {
+    let in_bounds = names(|names| {
+        for param in &sig.generics.params {
+            match param {
+                syn::GenericParam::Lifetime(param) if !param.bounds.is_empty() => {
+                    names.visit_lifetime_param(param)
+                }
+                syn::GenericParam::Lifetime(_) => {}
+                syn::GenericParam::Type(param) => {
+                    for bound in &param.bounds {
+                        names.visit_type_param_bound(bound);
+                    }
+                }
+                syn::GenericParam::Const(_) => {}
+            }
+        }
+        if let Some(where_clause) = &sig.generics.where_clause {
+            names.visit_where_clause(where_clause);
+        }
+        for input in &sig.inputs {
+            if let syn::FnArg::Typed(arg) = input {
+                ImplTraitBounds(names).visit_type(&arg.ty);
+            }
+        }
+    });
+    let inputs = |names: &mut Names| {
+        for input in &sig.inputs {
+            match input {
+                syn::FnArg::Receiver(receiver) => match &receiver.reference {
+                    Some((_, lifetime)) => {
+                        if let Some(lifetime) = lifetime {
+                            names.visit_lifetime(lifetime);
+                        }
+                    }
+                    None => names.visit_type(&receiver.ty),
+                },
+                syn::FnArg::Typed(arg) => names.visit_type(&arg.ty),
+            }
+        }
+    };
+    let in_inputs = names(inputs);
+    let constrained = names(|names| {
+        names.skip_projections = true;
+        inputs(names);
+    });
+
+    let mut late_bound = Vec::new();
+    let mut early_bound = Vec::new();
+    for param in sig.generics.lifetimes() {
+        let lifetime = param.lifetime.to_string();
+        let reason = if in_bounds.contains(&lifetime) {
+            "appears in a bound"
+        } else if !in_inputs.contains(&lifetime) {
+            "does not appear in the arguments"
+        } else if !constrained.contains(&lifetime) {
+            "only appears in the arguments under a projection"
+        } else {
+            late_bound.push(lifetime);
+            continue;
+        };
+        early_bound.push(EarlyBound {
+            lifetime,
+            reason: reason.to_string(),
+        });
+    }
+    (late_bound, early_bound)
+}
+
+struct Collector<'s> {
+    text: SourceText<'s>,
+    with_lifetimes: HashMap<String, usize>,
+    item: String,
+    /// The kind of the trait bounds being visited.
+    kind: &'static str,
+    /// The lifetimes declared around what is being visited, which elided
+    /// lifetimes must not be named after.
+    in_scope: Vec<String>,
+    functions: Vec<FunctionBinders>,
+    bounds: Vec<HigherRankedBound>,
+}
+
+/// A binder with its elided lifetimes written out.
+struct Desugared {
+    binds: Vec<String>,
+    elided: bool,
+    edits: Vec<Edit>,
+    error: Option<String>,
+}
+
+impl Collector<'_> {
+    fn in_kind(&mut self, kind: &'static str, f: impl FnOnce(&mut Self)) {
+        let outer = mem::replace(&mut self.kind, kind);
+        f(self);
+        self.kind = outer;
+    }
+
+    /// Visits what `f` visits with `item` as the enclosing item, and
+    /// `lifetimes` in scope.
+    fn in_item<'l>(
+        &mut self,
+        item: String,
+        lifetimes: impl Iterator<Item = &'l syn::LifetimeParam>,
+        f: impl FnOnce(&mut Self),
+    ) {
+        let outer_item = mem::replace(&mut self.item, item);
+        let scope = self.in_scope.len();
+        self.in_scope
+            .extend(lifetimes.map(|param| param.lifetime.to_string()));
+        f(self);
+        self.in_scope.truncate(scope);
+        self.item = outer_item;
+    }
+
+    fn function(&mut self, name: String, sig: &syn::Signature, block: Option<&syn::Block>) {
+        if sig.generics.lifetimes().next().is_some() {
+            let (late_bound, early_bound) = binders(sig);
+            self.functions.push(FunctionBinders {
+                function: name.clone(),
+                position: position(sig.ident.span().start()),
+                late_bound,
+                early_bound,
+            });
+        }
+        self.in_item(name, sig.generics.lifetimes(), |this| {
+            visit::visit_signature(this, sig);
+            if let Some(block) = block {
+                this.visit_block(block);
+            }
+        });
+    }
+
+    /// Names the elided lifetimes of `inputs` after those of `binder`, and
+    /// gives the output the lifetime of the inputs if they have only one, as
+    /// elision does for the parameters of a `fn`. `start` is where a new
+    /// binder goes.
+    fn desugar<'t>(
+        &self,
+        binder: Option<&syn::BoundLifetimes>,
+        inputs: impl Iterator<Item = &'t syn::Type>,
+        output: &syn::ReturnType,
+        start: proc_macro2::LineColumn,
+    ) -> Desugared {
+        let mut binds: Vec<String> = binder
+            .into_iter()
+            .flat_map(|binder| binder.lifetimes.iter())
+            .filter_map(|param| match param {
+                syn::GenericParam::Lifetime(param) => Some(param.lifetime.to_string()),
+                _ => None,
+            })
+            .collect();
+        let taken: Vec<String> = self.in_scope.iter().chain(&binds).cloned().collect();
+        let mut fresh = ('a'..='z')
+            .map(|c| format!("'{c}"))
+            .filter(|name| !taken.contains(name));
+
+        let mut edits = Vec::new();
+        let mut added = Vec::new();
+        let mut lifetimes: Vec<String> = Vec::new();
+        for input in inputs {
+            let mut sites = ElisionSites::new(&self.with_lifetimes);
+            sites.visit_type(input);
+            for site in sites.sites {
+                let lifetime = match site.elided {
+                    Some(elided) => {
+                        let name = fresh.next().unwrap_or_else(|| "'_".to_string());
+                        edits.push(elided.edit(&name));
+                        added.push(name.clone());
+                        name
+                    }
+                    None => site.named,
+                };
+                if !lifetimes.contains(&lifetime) {
+                    lifetimes.push(lifetime);
+                }
+            }
+        }
+
+        let mut output_sites = ElisionSites::new(&self.with_lifetimes);
+        output_sites.visit_return_type(output);
+        let mut error = None;
+        for elided in output_sites
+            .sites
+            .iter()
+            .filter_map(|site| site.elided.as_ref())
+        {
+            match lifetimes.as_slice() {
+                [lifetime] => edits.push(elided.edit(lifetime)),
+                [] => {
+                    error = Some(
+                        "the output lifetime cannot be elided: there is no input lifetime"
+                            .to_string(),
+                    );
+                }
+                _ => {
+                    error = Some(format!(
+                        "the output lifetime cannot be elided: there are {} input lifetimes",
+                        lifetimes.len()
+                    ));
+                }
+            }
+        }
+
+        if !added.is_empty() {
+            let added = added.join(", ");
+            edits.push(match binder {
+                Some(binder) if binder.lifetimes.is_empty() => {
+                    Edit::insert(binder.lt_token.span.end(), added)
+                }
+                Some(binder) => Edit::insert(binder.gt_token.span.start(), format!(", {added}")),
+                None => Edit::insert(start, format!("for<{added}> ")),
+            });
+        }
+        let elided = !added.is_empty();
+        binds.extend(added);
+        Desugared {
+            binds,
+            elided,
+            edits,
+            error,
+        }
+    }
+
+    fn record(
+        &mut self,
+        node: &impl Spanned,
+        kind: &str,
+        desugared: Desugared,
+        unsugared: Option<String>,
+    ) {
+        let (desugared_text, unsugared) = match &desugared.error {
+            Some(_) => (None, None),
+            None => (
+                Some(self.text.text_with(node.span(), &desugared.edits)),
+                unsugared,
+            ),
+        };
+        self.bounds.push(HigherRankedBound {
+            item: self.item.clone(),
+            position: position(node.span().start()),
+            kind: kind.to_string(),
+            written: self.text.text(node),
+            binds: desugared.binds,
+            elided: desugared.elided,
+            desugared: desugared_text,
+            unsugared,
+            error: desugared.error,
+        });
+    }
+
+    /// `Fn<(A, B), Output = C>` for `Fn(A, B) -> C`, with the binder and
+    /// the lifetimes `desugared` names.
+    fn unsugared(
+        &self,
+        bound: &syn::TraitBound,
+        args: &syn::ParenthesizedGenericArguments,
+        desugared: &Desugared,
+    ) -> String {
+        let binder = if desugared.binds.is_empty() {
+            String::new()
+        } else {
+            format!("for<{}> ", desugared.binds.join(", "))
+        };
+        let mut path: Vec<String> = bound
+            .path
+            .segments
+            .iter()
+            .map(|segment| segment.ident.to_string())
+            .collect();
+        if bound.path.leading_colon.is_some() {
+            path.insert(0, String::new());
+        }
+        let inputs: Vec<String> = args
+            .inputs
+            .iter()
+            .map(|input| self.text.text_with(input.span(), &desugared.edits))
+            .collect();
+        let inputs = match inputs.as_slice() {
+            [input] => format!("({input},)"),
+            inputs => format!("({})", inputs.join(", ")),
+        };
+        let output = match &args.output {
+            syn::ReturnType::Default => "()".to_string(),
+            syn::ReturnType::Type(_, ty) => self.text.text_with(ty.span(), &desugared.edits),
+        };
+        format!("{binder}{}<{inputs}, Output = {output}>", path.join("::"))
+    }
+}
+
+impl<'ast> Visit<'ast> for Collector<'_> {
+    fn visit_predicate_type(&mut self, i: &'ast syn::PredicateType) {
+        let Some(binder) = &i.lifetimes else {
+            return visit::visit_predicate_type(self, i);
+        };
+        let binds = binder
+            .lifetimes
+            .iter()
+            .filter_map(|param| match param {
+                syn::GenericParam::Lifetime(param) => Some(param.lifetime.to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        self.record(
+            i,
+            "where clause",
+            Desugared {
+                binds: binds.clone(),
+                elided: false,
+                edits: Vec::new(),
+                error: None,
+            },
+            None,
+        );
+        let scope = self.in_scope.len();
+        self.in_scope.extend(binds);
+        visit::visit_predicate_type(self, i);
+        self.in_scope.truncate(scope);
+    }
+
+    fn visit_trait_bound(&mut self, i: &'ast syn::TraitBound) {
+        let sugar = match i.path.segments.last().map(|segment| &segment.arguments) {
+            Some(syn::PathArguments::Parenthesized(args)) => Some(args),
+            _ => None,
+        };
+        let desugared = match sugar {
+            Some(args) => self.desugar(
+                i.lifetimes.as_ref(),
+                args.inputs.iter(),
+                &args.output,
+                i.span().start(),
+            ),
+            None => self.desugar(
+                i.lifetimes.as_ref(),
+                std::iter::empty(),
+                &syn::ReturnType::Default,
+                i.span().start(),
+            ),
+        };
+        let binds = desugared.binds.clone();
+        if !binds.is_empty() {
+            let unsugared = sugar.map(|args| self.unsugared(i, args, &desugared));
+            self.record(i, self.kind, desugared, unsugared);
+        }
+        let scope = self.in_scope.len();
+        self.in_scope.extend(binds);
+        visit::visit_trait_bound(self, i);
+        self.in_scope.truncate(scope);
+    }
+
+    fn visit_type_bare_fn(&mut self, i: &'ast syn::TypeBareFn) {
+        let desugared = self.desugar(
+            i.lifetimes.as_ref(),
+            i.inputs.iter().map(|arg| &arg.ty),
+            &i.output,
+            i.span().start(),
+        );
+        let binds = desugared.binds.clone();
+        if !binds.is_empty() {
+            self.record(i, "fn pointer", desugared, None);
+        }
+        let scope = self.in_scope.len();
+        self.in_scope.extend(binds);
+        visit::visit_type_bare_fn(self, i);
+        self.in_scope.truncate(scope);
+    }
+
+    fn visit_type_param(&mut self, i: &'ast syn::TypeParam) {
+        self.in_kind("bound", |this| visit::visit_type_param(this, i));
+    }
+
+    fn visit_where_clause(&mut self, i: &'ast syn::WhereClause) {
+        self.in_kind("bound", |this| visit::visit_where_clause(this, i));
+    }
+
+    fn visit_type_trait_object(&mut self, i: &'ast syn::TypeTraitObject) {
+        self.in_kind("trait object", |this| {
+            visit::visit_type_trait_object(this, i)
+        });
+    }
+
+    fn visit_type_impl_trait(&mut self, i: &'ast syn::TypeImplTrait) {
+        self.in_kind("impl Trait", |this| visit::visit_type_impl_trait(this, i));
+    }
+
+    fn visit_item_fn(&mut self, i: &'ast syn::ItemFn) {
+        // Items of the body do not see the lifetimes around them.
+        let outer = mem::take(&mut self.in_scope);
+        self.function(i.sig.ident.to_string(), &i.sig, Some(&i.block));
+        self.in_scope = outer;
+    }
+
+    fn visit_item_struct(&mut self, i: &'ast syn::ItemStruct) {
+        self.in_item(i.ident.to_string(), i.generics.lifetimes(), |this| {
+            visit::visit_item_struct(this, i)
+        });
+    }
+
+    fn visit_item_enum(&mut self, i: &'ast syn::ItemEnum) {
+        self.in_item(i.ident.to_string(), i.generics.lifetimes(), |this| {
+            visit::visit_item_enum(this, i)
+        });
+    }
+
+    fn visit_item_type(&mut self, i: &'ast syn::ItemType) {
+        self.in_item(i.ident.to_string(), i.generics.lifetimes(), |this| {
+            visit::visit_item_type(this, i)
+        });
+    }
+
+    fn visit_item_trait(&mut self, i: &'ast syn::ItemTrait) {
+        let name = i.ident.to_string();
+        self.in_item(name.clone(), i.generics.lifetimes(), |this| {
+            this.visit_generics(&i.generics);
+            this.in_kind("bound", |this| {
+                for bound in &i.supertraits {
+                    this.visit_type_param_bound(bound);
+                }
+            });
+            for item in &i.items {
+                match item {
+                    syn::TraitItem::Fn(f) => this.function(
+                        format!("{name}::{}", f.sig.ident),
+                        &f.sig,
+                        f.default.as_ref(),
+                    ),
+                    item => this.visit_trait_item(item),
+                }
+            }
+        });
+    }
+
+    fn visit_item_impl(&mut self, i: &'ast syn::ItemImpl) {
+        let self_ty = self.text.text(&i.self_ty);
+        let header = match &i.trait_ {
+            Some((_, path, _)) => format!("impl {} for {self_ty}", self.text.text(path)),
+            None => format!("impl {self_ty}"),
+        };
+        self.in_item(header, i.generics.lifetimes(), |this| {
+            this.visit_generics(&i.generics);
+            if let Some((_, path, _)) = &i.trait_ {
+                this.visit_path(path);
+            }
+            this.visit_type(&i.self_ty);
+            for item in &i.items {
+                match item {
+                    syn::ImplItem::Fn(f) => this.function(
+                        format!("{self_ty}::{}", f.sig.ident),
+                        &f.sig,
+                        Some(&f.block),
+                    ),
+                    item => this.visit_impl_item(item),
+                }
+            }
+        });
+    }
+}
+
+impl Report for HrtbReport {
+    /// The binders of the functions, then the higher-ranked bounds:
+    ///
+    /// ```text
+    /// binders of hrtb.rs:
+    ///     3:4  longest
+    ///         late-bound:  'a
+    ///         early-bound: 'b, appears in a bound
+    ///
+    /// higher-ranked bounds of hrtb.rs:
+    ///     9:8  apply, bound
+    ///         written:   Fn(&T) -> &U
+    ///         binds:     'a (elided)
+    ///         desugared: for<'a> Fn(&'a T) -> &'a U
+    ///         unsugared: for<'a> Fn<(&'a T,), Output = &'a U>
+    /// ```
+    fn write_text(&self, mut out: impl Write) -> io::Result<()> {
+        if !self.functions.is_empty() {
+            writeln!(out, "binders of {}:", self.path)?;
+            for function in &self.functions {
+                writeln!(out, "    {}  {}", function.position, function.function)?;
+                if !function.late_bound.is_empty() {
+                    writeln!(
+                        out,
+                        "        late-bound:  {}",
+                        function.late_bound.join(", ")
+                    )?;
+                }
+                for early in &function.early_bound {
+                    writeln!(
+                        out,
+                        "        early-bound: {}, {}",
+                        early.lifetime, early.reason
+                    )?;
+                }
+            }
+            writeln!(out)?;
+        }
+
+        if self.bounds.is_empty() {
+            return writeln!(out, "no higher-ranked bounds in {}", self.path);
+        }
+        writeln!(out, "higher-ranked bounds of {}:", self.path)?;
+        for bound in &self.bounds {
+            writeln!(
+                out,
+                "    {}  {}, {}",
+                bound.position, bound.item, bound.kind
+            )?;
+            writeln!(out, "        written:   {}", bound.written)?;
+            let elided = if bound.elided { " (elided)" } else { "" };
+            writeln!(out, "        binds:     {}{elided}", bound.binds.join(", "))?;
+            match &bound.desugared {
+                Some(desugared) if *desugared != bound.written => {
+                    writeln!(out, "        desugared: {desugared}")?
+                }
+                _ => {}
+            }
+            if let Some(unsugared) = &bound.unsugared {
+                writeln!(out, "        unsugared: {unsugared}")?;
+            }
+            if let Some(error) = &bound.error {
+                writeln!(out, "        error:     {error}")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+//! Reports read from the source with `syn` rather than rustc, for machines
+//! without the nightly the `collect-lang-items` driver is pinned to:
+//! `collect-items` lists the items of a crate, `collect-lifetimes` the
+//! lifetimes of a file and `collect-hrtb` its late-bound lifetimes and
+//! higher-ranked bounds. Like the driver's reports, theirs are printed
+//! through the [`Report`] trait of `collect_report`.
+
+pub mod hrtb;
+pub mod lifetimes;
+pub mod source;
+
+pub use collect_report::Report;
+
+use std::env;
+use std::fs;
+use std::io;
+use std::process::ExitCode;
+
+/// The `main` of a tool taking `[--format text|json] PATH`: prints the
+/// report `analyze` makes of `PATH`, or the error it fails with. `usage` is
+/// printed without a path.
+pub fn run<R: Report>(usage: &str, analyze: impl FnOnce(&str) -> Result<R, String>) -> ExitCode {
+    let mut json = false;
+    let mut path: Option<String> = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().as_deref() {
+                Some("json") => json = true,
+                Some("text") => json = false,
+                _ => {
+                    eprintln!("error: `--format` expects `text` or `json`");
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ if path.is_none() => path = Some(arg),
+            _ => {
+                eprintln!("error: unexpected argument `{arg}`");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let Some(path) = path else {
+        eprintln!("usage: {usage}");
+        return ExitCode::FAILURE;
+    };
+
+    let report = match analyze(&path) {
+        Ok(report) => report,
+        Err(err) => {
+            eprintln!("error: {path}: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let stdout = io::stdout().lock();
+    let written = if json {
+        report.write_json(stdout)
+    } else {
+        report.write_text(stdout)
+    };
+    if let Err(err) = written {
+        eprintln!("error: failed to write the report: {err}");
+        return ExitCode::FAILURE;
+    }
+    if report.is_complete() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Reads the file `path` and passes it to `analyze` along with its path,
+/// for the tools that read a single file.
+pub fn read_file<R>(
+    path: &str,
+    analyze: impl FnOnce(&str, &str) -> syn::Result<R>,
+) -> Result<R, String> {
+    let src = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    analyze(path, &src).map_err(|err| error_at(&err))
+}
+
+/// `err` with the `line:column` it starts at.
+pub(crate) fn error_at(err: &syn::Error) -> String {
+    let start = err.span().start();
+    format!("{}:{}: {err}", start.line, start.column + 1)
+}
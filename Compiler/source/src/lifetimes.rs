@@ -0,0 +1,749 @@
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::mem;
+
+use proc_macro2::LineColumn;
+use serde::{Deserialize, Serialize};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+use crate::Report;
+
+/// Where the lifetimes of a file are declared and used, and what elision
+/// fills in for the signatures that leave some out. Read with `syn`, like
+/// [`crate::source::SourceReport`], from a single file.
+#[derive(Serialize, Deserialize)]
+pub struct LifetimeReport {
+    pub path: String,
+    /// In source order.
+    pub sites: Vec<LifetimeSite>,
+    /// The signatures with at least one elided lifetime, in source order.
+    pub signatures: Vec<ElidedSignature>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LifetimeSite {
+    /// E.g. `'a`, `'static` or `'_`.
+    pub lifetime: String,
+    /// Whether the lifetime is declared here rather than used.
+    pub declaration: bool,
+    /// For declarations, `struct parameter`, `enum parameter`,
+    /// `union parameter`, `trait parameter`, `type parameter` (of a type
+    /// alias), `impl parameter`, `fn parameter` or `higher-ranked` for a
+    /// `for<'a>` binder. For uses, `field`, `impl header`, `fn signature`,
+    /// `fn body`, `bound`, `trait object`, `type alias`, `associated type`,
+    /// `const` or `static`.
+    pub context: String,
+    /// The item the site is in, e.g. `Parser` or `Parser::next_token`.
+    pub item: String,
+    /// `line:column`, both starting at 1.
+    pub position: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ElidedSignature {
+    /// E.g. `longest` or `Parser::next_token`.
+    pub function: String,
+    pub position: String,
+    /// The signature as written, on one line.
+    pub written: String,
+    /// The signature with every elided lifetime spelled out, `None` when the
+    /// lifetime of the output cannot be elided.
+    pub explicit: Option<String>,
+    /// Which rule gave the output its lifetime, or why none could.
+    pub output: Option<String>,
+}
+
+/// Reads the file `path`, whose source is `src`.
+pub fn analyze_file(path: &str, src: &str) -> syn::Result<LifetimeReport> {
+    let file = syn::parse_file(src)?;
+    let mut collector = Collector {
+        text: SourceText::new(src),
+        with_lifetimes: types_with_lifetimes(&file),
+        item: String::new(),
+        declaring: "",
+        context: "",
+        outer: Vec::new(),
+        sites: Vec::new(),
+        signatures: Vec::new(),
+    };
+    collector.visit_file(&file);
+    Ok(LifetimeReport {
+        path: path.to_string(),
+        sites: collector.sites,
+        signatures: collector.signatures,
+    })
+}
+
+/// The types of the file declared with lifetime parameters, and how many,
+/// so that `Parser` in a signature can be told to be `Parser<'_>`.
+pub(crate) fn types_with_lifetimes(file: &syn::File) -> HashMap<String, usize> {
+    let mut types = HashMap::new();
+    for item in &file.items {
+        let (ident, generics) = match item {
+            syn::Item::Struct(s) => (&s.ident, &s.generics),
+            syn::Item::Enum(e) => (&e.ident, &e.generics),
+            syn::Item::Union(u) => (&u.ident, &u.generics),
+            _ => continue,
+        };
+        let lifetimes = generics.lifetimes().count();
+        if lifetimes > 0 {
+            types.insert(ident.to_string(), lifetimes);
+        }
+    }
+    types
+}
+
+pub(crate) fn position(at: LineColumn) -> String {
+    format!("{}:{}", at.line, at.column + 1)
+}
+
+/// The source of a file, to print what `syn` parsed as it was written.
+pub(crate) struct SourceText<'s> {
+    src: &'s str,
+    /// The byte offset of the start of each line.
+    line_starts: Vec<usize>,
+}
+
+impl<'s> SourceText<'s> {
+    pub(crate) fn new(src: &'s str) -> Self {
+        SourceText {
+            src,
+            line_starts: std::iter::once(0)
+                .chain(src.match_indices('\n').map(|(i, _)| i + 1))
+                .collect(),
+        }
+    }
+
+    fn offset(&self, at: LineColumn) -> usize {
+        let start = self.line_starts[at.line - 1];
+        let line = &self.src[start..];
+        start
+            + line
+                .chars()
+                .take(at.column)
+                .map(char::len_utf8)
+                .sum::<usize>()
+    }
+
+    /// The source text of `span` with those of `edits` that are within it
+    /// applied, on one line.
+    pub(crate) fn text_with(&self, span: proc_macro2::Span, edits: &[Edit]) -> String {
+        let (start, end) = (span.start(), span.end());
+        let mut edits: Vec<(usize, &Edit)> = edits
+            .iter()
+            .filter(|edit| start <= edit.start && edit.end <= end)
+            .enumerate()
+            .collect();
+        // From the end, so that each edit leaves the offsets of the next one
+        // alone. Edits at the same place go in the order given.
+        edits.sort_by_key(|&(i, edit)| std::cmp::Reverse((edit.start, i)));
+        let start = self.offset(start);
+        let mut text = self.src[start..self.offset(end)].to_string();
+        for (_, edit) in edits {
+            let range = self.offset(edit.start) - start..self.offset(edit.end) - start;
+            text.replace_range(range, &edit.text);
+        }
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    pub(crate) fn text(&self, node: &impl Spanned) -> String {
+        self.text_with(node.span(), &[])
+    }
+}
+
+struct Collector<'s> {
+    text: SourceText<'s>,
+    /// The number of lifetime parameters of the types of the file.
+    with_lifetimes: HashMap<String, usize>,
+    item: String,
+    /// The context of the lifetime parameters being visited.
+    declaring: &'static str,
+    /// The context of the lifetimes being visited.
+    context: &'static str,
+    /// The lifetimes the enclosing impl or trait declares.
+    outer: Vec<String>,
+    sites: Vec<LifetimeSite>,
+    signatures: Vec<ElidedSignature>,
+}
+
+impl Collector<'_> {
+    fn record(&mut self, lifetime: &syn::Lifetime, declaration: bool, context: &str) {
+        self.sites.push(LifetimeSite {
+            lifetime: lifetime.to_string(),
+            declaration,
+            context: context.to_string(),
+            item: self.item.clone(),
+            position: position(lifetime.span().start()),
+        });
+    }
+
+    fn in_context(&mut self, context: &'static str, f: impl FnOnce(&mut Self)) {
+        let outer = mem::replace(&mut self.context, context);
+        f(self);
+        self.context = outer;
+    }
+
+    fn in_item(&mut self, item: String, declaring: &'static str, f: impl FnOnce(&mut Self)) {
+        let outer_item = mem::replace(&mut self.item, item);
+        let outer_declaring = mem::replace(&mut self.declaring, declaring);
+        f(self);
+        self.item = outer_item;
+        self.declaring = outer_declaring;
+    }
+
+    fn function(&mut self, name: String, sig: &syn::Signature, block: Option<&syn::Block>) {
+        self.in_item(name, "fn parameter", |this| {
+            this.visit_generics(&sig.generics);
+            this.in_context("fn signature", |this| {
+                for input in &sig.inputs {
+                    this.visit_fn_arg(input);
+                }
+                this.visit_return_type(&sig.output);
+            });
+            if let Some(signature) = this.elide(sig) {
+                this.signatures.push(signature);
+            }
+            if let Some(block) = block {
+                this.in_context("fn body", |this| this.visit_block(block));
+            }
+        });
+    }
+
+    /// The signature with its elided lifetimes spelled out, if it has any.
+    fn elide(&self, sig: &syn::Signature) -> Option<ElidedSignature> {
+        let mut taken: Vec<String> = self.outer.clone();
+        taken.extend(sig.generics.lifetimes().map(|l| l.lifetime.to_string()));
+        let mut fresh = ('a'..='z')
+            .map(|c| format!("'{c}"))
+            .filter(|name| !taken.contains(name));
+
+        let mut edits = Vec::new();
+        let mut added = Vec::new();
+        // The distinct lifetimes of the inputs: a fresh one per elided
+        // lifetime, and the named ones.
+        let mut inputs: Vec<String> = Vec::new();
+        let mut self_lifetime = None;
+        // The parameters borrowing something, for when the output could
+        // borrow from several.
+        let mut borrowing = Vec::new();
+        for input in &sig.inputs {
+            let mut sites = ElisionSites::new(&self.with_lifetimes);
+            match input {
+                syn::FnArg::Receiver(receiver) => {
+                    match &receiver.reference {
+                        Some((and, lifetime)) => sites.reference(and, lifetime.as_ref()),
+                        // `self: &Self`, `self: Pin<&mut Self>`, ...
+                        None if receiver.colon_token.is_some() => sites.visit_type(&receiver.ty),
+                        None => {}
+                    }
+                }
+                syn::FnArg::Typed(arg) => sites.visit_type(&arg.ty),
+            }
+            if !sites.sites.is_empty() {
+                borrowing.push(match input {
+                    syn::FnArg::Receiver(_) => "`self`".to_string(),
+                    syn::FnArg::Typed(arg) => {
+                        format!("`{}`", self.text.text(&arg.pat))
+                    }
+                });
+            }
+            for site in sites.sites {
+                let lifetime = match site.elided {
+                    Some(elided) => {
+                        let name = fresh.next()?;
+                        edits.push(elided.edit(&name));
+                        added.push(name.clone());
+                        name
+                    }
+                    None => site.named,
+                };
+                if matches!(input, syn::FnArg::Receiver(_)) && self_lifetime.is_none() {
+                    self_lifetime = Some(lifetime.clone());
+                }
+                if !inputs.contains(&lifetime) {
+                    inputs.push(lifetime);
+                }
+            }
+        }
+
+        let mut output_sites = ElisionSites::new(&self.with_lifetimes);
+        output_sites.visit_return_type(&sig.output);
+        let elided_output: Vec<Elided> = output_sites
+            .sites
+            .into_iter()
+            .filter_map(|site| site.elided)
+            .collect();
+        if edits.is_empty() && elided_output.is_empty() {
+            return None;
+        }
+
+        let output = if elided_output.is_empty() {
+            None
+        } else if let Some(lifetime) = &self_lifetime {
+            Some(Ok(format!("{lifetime}, that of `self`")))
+        } else if let [lifetime] = inputs.as_slice() {
+            Some(Ok(format!("{lifetime}, the only input lifetime")))
+        } else if inputs.is_empty() {
+            Some(Err(
+                "cannot be elided: there is no input lifetime to borrow from".to_string(),
+            ))
+        } else if let [param] = borrowing.as_slice() {
+            Some(Err(format!(
+                "cannot be elided: it could borrow from any of the {} lifetimes of {param}",
+                inputs.len()
+            )))
+        } else {
+            Some(Err(format!(
+                "cannot be elided: it could borrow from any of {}",
+                borrowing.join(", ")
+            )))
+        };
+        let explicit = match &output {
+            Some(Err(_)) => None,
+            _ => {
+                if let Some(Ok(_)) = &output {
+                    let lifetime = self_lifetime.as_ref().unwrap_or(&inputs[0]);
+                    edits.extend(elided_output.iter().map(|elided| elided.edit(lifetime)));
+                }
+                if !added.is_empty() {
+                    edits.push(match &sig.generics.lt_token {
+                        Some(lt) => Edit::insert(lt.span.end(), format!("{}, ", added.join(", "))),
+                        None => {
+                            Edit::insert(sig.ident.span().end(), format!("<{}>", added.join(", ")))
+                        }
+                    });
+                }
+                Some(self.text.text_with(sig.span(), &edits))
+            }
+        };
+        Some(ElidedSignature {
+            function: self.item.clone(),
+            position: position(sig.ident.span().start()),
+            written: self.text.text(&sig),
+            explicit,
+            output: output.map(|output| match output {
+                Ok(rule) | Err(rule) => rule,
+            }),
+        })
+    }
+}
+
+pub(crate) struct Edit {
+    start: LineColumn,
+    end: LineColumn,
+    text: String,
+}
+
+impl Edit {
+    pub(crate) fn insert(at: LineColumn, text: String) -> Self {
+        Edit {
+            start: at,
+            end: at,
+            text,
+        }
+    }
+}
+
+/// A lifetime left out of a signature.
+pub(crate) enum Elided {
+    /// `&T`, the lifetime going after `&`.
+    Reference(LineColumn),
+    /// `'_`.
+    Placeholder(proc_macro2::Span),
+    /// The `index`th of the `count` lifetime parameters of `Parser` or
+    /// `Parser<T>`, going after `<`, or in new `<>` after the name. Each is
+    /// a lifetime of its own.
+    Arguments {
+        at: LineColumn,
+        angle_brackets: bool,
+        index: usize,
+        count: usize,
+    },
+}
+
+impl Elided {
+    pub(crate) fn edit(&self, lifetime: &str) -> Edit {
+        match *self {
+            Elided::Reference(at) => Edit::insert(at, format!("{lifetime} ")),
+            Elided::Placeholder(span) => Edit {
+                start: span.start(),
+                end: span.end(),
+                text: lifetime.to_string(),
+            },
+            Elided::Arguments {
+                at,
+                angle_brackets: true,
+                ..
+            } => Edit::insert(at, format!("{lifetime}, ")),
+            Elided::Arguments {
+                at,
+                angle_brackets: false,
+                index,
+                count,
+            } => {
+                let open = if index == 0 { "<" } else { ", " };
+                let close = if index + 1 == count { ">" } else { "" };
+                Edit::insert(at, format!("{open}{lifetime}{close}"))
+            }
+        }
+    }
+}
+
+pub(crate) struct Site {
+    /// The lifetime, when it is written.
+    pub(crate) named: String,
+    pub(crate) elided: Option<Elided>,
+}
+
+/// The lifetimes of a type that elision is about: not those of `fn`
+/// pointers, `Fn` bounds or `for<'a>` binders, which are higher-ranked.
+pub(crate) struct ElisionSites<'c> {
+    with_lifetimes: &'c HashMap<String, usize>,
+    pub(crate) sites: Vec<Site>,
+}
+
+impl<'c> ElisionSites<'c> {
+    pub(crate) fn new(with_lifetimes: &'c HashMap<String, usize>) -> Self {
+        ElisionSites {
+            with_lifetimes,
+            sites: Vec::new(),
+        }
+    }
+
+    fn lifetime(&mut self, lifetime: &syn::Lifetime) {
+        self.sites.push(if lifetime.ident == "_" {
+            Site {
+                named: String::new(),
+                elided: Some(Elided::Placeholder(lifetime.span())),
+            }
+        } else {
+            Site {
+                named: lifetime.to_string(),
+                elided: None,
+            }
+        });
+    }
+
+    fn reference(&mut self, and: &syn::Token![&], lifetime: Option<&syn::Lifetime>) {
+        match lifetime {
+            Some(lifetime) => self.lifetime(lifetime),
+            None => self.sites.push(Site {
+                named: String::new(),
+                elided: Some(Elided::Reference(and.span.end())),
+            }),
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for ElisionSites<'_> {
+    fn visit_type_reference(&mut self, i: &'ast syn::TypeReference) {
+        self.reference(&i.and_token, i.lifetime.as_ref());
+        self.visit_type(&i.elem);
+    }
+
+    fn visit_lifetime(&mut self, i: &'ast syn::Lifetime) {
+        self.lifetime(i);
+    }
+
+    fn visit_type_path(&mut self, i: &'ast syn::TypePath) {
+        if let (None, Some(last)) = (&i.qself, i.path.segments.last()) {
+            let count = self.with_lifetimes.get(&last.ident.to_string());
+            let written = match &last.arguments {
+                syn::PathArguments::AngleBracketed(args) => args
+                    .args
+                    .iter()
+                    .any(|arg| matches!(arg, syn::GenericArgument::Lifetime(_))),
+                _ => false,
+            };
+            if let (Some(&count), false) = (count, written) {
+                let (at, angle_brackets) = match &last.arguments {
+                    syn::PathArguments::AngleBracketed(args) => (args.lt_token.span.end(), true),
+                    _ => (last.ident.span().end(), false),
+                };
+                self.sites.extend((0..count).map(|index| Site {
+                    named: String::new(),
+                    elided: Some(Elided::Arguments {
+                        at,
+                        angle_brackets,
+                        index,
+                        count,
+                    }),
+                }));
+            }
+        }
+        visit::visit_type_path(self, i);
+    }
+
+    fn visit_type_bare_fn(&mut self, _: &'ast syn::TypeBareFn) {}
+
+    fn visit_parenthesized_generic_arguments(
+        &mut self,
+        _: &'ast syn::ParenthesizedGenericArguments,
+    ) {
+    }
+
+    fn visit_bound_lifetimes(&mut self, _: &'ast syn::BoundLifetimes) {}
+}
+
+impl<'ast> Visit<'ast> for Collector<'_> {
+    fn visit_lifetime(&mut self, i: &'ast syn::Lifetime) {
+        self.record(i, false, self.context);
+    }
+
+    fn visit_lifetime_param(&mut self, i: &'ast syn::LifetimeParam) {
+        self.record(&i.lifetime, true, self.declaring);
+        self.in_context("bound", |this| {
+            for bound in &i.bounds {
+                this.visit_lifetime(bound);
+            }
+        });
+    }
+
+    fn visit_bound_lifetimes(&mut self, i: &'ast syn::BoundLifetimes) {
+        let outer = mem::replace(&mut self.declaring, "higher-ranked");
+        visit::visit_bound_lifetimes(self, i);
+        self.declaring = outer;
+    }
+
+    fn visit_type_param(&mut self, i: &'ast syn::TypeParam) {
+        self.in_context("bound", |this| {
+            for bound in &i.bounds {
+                this.visit_type_param_bound(bound);
+            }
+        });
+        if let Some(default) = &i.default {
+            self.visit_type(default);
+        }
+    }
+
+    fn visit_where_clause(&mut self, i: &'ast syn::WhereClause) {
+        self.in_context("bound", |this| visit::visit_where_clause(this, i));
+    }
+
+    fn visit_type_trait_object(&mut self, i: &'ast syn::TypeTraitObject) {
+        self.in_context("trait object", |this| {
+            visit::visit_type_trait_object(this, i)
+        });
+    }
+
+    // `syn` also gives `&'a self` the type `&'a Self`, with the same lifetime.
+    fn visit_receiver(&mut self, i: &'ast syn::Receiver) {
+        match &i.reference {
+            Some((_, lifetime)) => {
+                if let Some(lifetime) = lifetime {
+                    self.visit_lifetime(lifetime);
+                }
+            }
+            None => self.visit_type(&i.ty),
+        }
+    }
+
+    // Loop labels are lifetimes to `syn`.
+    fn visit_label(&mut self, _: &'ast syn::Label) {}
+
+    fn visit_expr_break(&mut self, i: &'ast syn::ExprBreak) {
+        if let Some(expr) = &i.expr {
+            self.visit_expr(expr);
+        }
+    }
+
+    fn visit_expr_continue(&mut self, _: &'ast syn::ExprContinue) {}
+
+    fn visit_item_struct(&mut self, i: &'ast syn::ItemStruct) {
+        self.in_item(i.ident.to_string(), "struct parameter", |this| {
+            this.visit_generics(&i.generics);
+            this.in_context("field", |this| this.visit_fields(&i.fields));
+        });
+    }
+
+    fn visit_item_enum(&mut self, i: &'ast syn::ItemEnum) {
+        self.in_item(i.ident.to_string(), "enum parameter", |this| {
+            this.visit_generics(&i.generics);
+            this.in_context("field", |this| {
+                for variant in &i.variants {
+                    this.visit_fields(&variant.fields);
+                }
+            });
+        });
+    }
+
+    fn visit_item_union(&mut self, i: &'ast syn::ItemUnion) {
+        self.in_item(i.ident.to_string(), "union parameter", |this| {
+            this.visit_generics(&i.generics);
+            this.in_context("field", |this| this.visit_fields_named(&i.fields));
+        });
+    }
+
+    fn visit_item_type(&mut self, i: &'ast syn::ItemType) {
+        self.in_item(i.ident.to_string(), "type parameter", |this| {
+            this.visit_generics(&i.generics);
+            this.in_context("type alias", |this| this.visit_type(&i.ty));
+        });
+    }
+
+    fn visit_item_const(&mut self, i: &'ast syn::ItemConst) {
+        self.in_item(i.ident.to_string(), "", |this| {
+            this.in_context("const", |this| this.visit_type(&i.ty));
+            this.in_context("fn body", |this| this.visit_expr(&i.expr));
+        });
+    }
+
+    fn visit_item_static(&mut self, i: &'ast syn::ItemStatic) {
+        self.in_item(i.ident.to_string(), "", |this| {
+            this.in_context("static", |this| this.visit_type(&i.ty));
+            this.in_context("fn body", |this| this.visit_expr(&i.expr));
+        });
+    }
+
+    fn visit_item_fn(&mut self, i: &'ast syn::ItemFn) {
+        // Items of the body do not see the lifetimes of an enclosing impl.
+        let outer = mem::take(&mut self.outer);
+        self.function(i.sig.ident.to_string(), &i.sig, Some(&i.block));
+        self.outer = outer;
+    }
+
+    fn visit_item_trait(&mut self, i: &'ast syn::ItemTrait) {
+        let name = i.ident.to_string();
+        let lifetimes = i.generics.lifetimes().map(|l| l.lifetime.to_string());
+        let outer = mem::replace(&mut self.outer, lifetimes.collect());
+        self.in_item(name.clone(), "trait parameter", |this| {
+            this.visit_generics(&i.generics);
+            this.in_context("bound", |this| {
+                for bound in &i.supertraits {
+                    this.visit_type_param_bound(bound);
+                }
+            });
+            for item in &i.items {
+                match item {
+                    syn::TraitItem::Fn(f) => this.function(
+                        format!("{name}::{}", f.sig.ident),
+                        &f.sig,
+                        f.default.as_ref(),
+                    ),
+                    syn::TraitItem::Type(t) => {
+                        this.in_item(format!("{name}::{}", t.ident), "type parameter", |this| {
+                            this.visit_generics(&t.generics);
+                            this.in_context("bound", |this| {
+                                for bound in &t.bounds {
+                                    this.visit_type_param_bound(bound);
+                                }
+                            });
+                        })
+                    }
+                    item => this.visit_trait_item(item),
+                }
+            }
+        });
+        self.outer = outer;
+    }
+
+    fn visit_item_impl(&mut self, i: &'ast syn::ItemImpl) {
+        let self_ty = self.text.text(&i.self_ty);
+        let header = match &i.trait_ {
+            Some((_, path, _)) => format!("impl {} for {self_ty}", self.text.text(&path)),
+            None => format!("impl {self_ty}"),
+        };
+        let lifetimes = i.generics.lifetimes().map(|l| l.lifetime.to_string());
+        let outer = mem::replace(&mut self.outer, lifetimes.collect());
+        self.in_item(header, "impl parameter", |this| {
+            this.visit_generics(&i.generics);
+            this.in_context("impl header", |this| {
+                if let Some((_, path, _)) = &i.trait_ {
+                    this.visit_path(path);
+                }
+                this.visit_type(&i.self_ty);
+            });
+            for item in &i.items {
+                match item {
+                    syn::ImplItem::Fn(f) => this.function(
+                        format!("{self_ty}::{}", f.sig.ident),
+                        &f.sig,
+                        Some(&f.block),
+                    ),
+                    syn::ImplItem::Type(t) => this.in_item(
+                        format!("{self_ty}::{}", t.ident),
+                        "type parameter",
+                        |this| {
+                            this.visit_generics(&t.generics);
+                            this.in_context("associated type", |this| this.visit_type(&t.ty));
+                        },
+                    ),
+                    item => this.visit_impl_item(item),
+                }
+            }
+        });
+        self.outer = outer;
+    }
+}
+
+impl Report for LifetimeReport {
+    /// Every site, then every signature with elided lifetimes:
+    ///
+    /// ```text
+    /// lifetimes of parser.rs:
+    ///     1:15  'a  declared  struct parameter  Parser
+    ///     2:12  'a  used      field             Parser
+    ///
+    /// elided lifetimes:
+    ///     6:8  Parser::peek
+    ///         written:  fn peek(&self) -> &str
+    ///         explicit: fn peek<'b>(&'b self) -> &'b str
+    ///         output:   'b, that of `self`
+    /// ```
+    fn write_text(&self, mut out: impl Write) -> io::Result<()> {
+        writeln!(out, "lifetimes of {}:", self.path)?;
+        let rows: Vec<[&str; 5]> = self
+            .sites
+            .iter()
+            .map(|site| {
+                let role = if site.declaration { "declared" } else { "used" };
+                [
+                    site.position.as_str(),
+                    &site.lifetime,
+                    role,
+                    &site.context,
+                    &site.item,
+                ]
+            })
+            .collect();
+        let mut widths = [0; 4];
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        for row in &rows {
+            writeln!(
+                out,
+                "    {:w0$}  {:w1$}  {:w2$}  {:w3$}  {}",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                row[4],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+                w3 = widths[3],
+            )?;
+        }
+
+        if self.signatures.is_empty() {
+            return Ok(());
+        }
+        writeln!(out)?;
+        writeln!(out, "elided lifetimes:")?;
+        for signature in &self.signatures {
+            writeln!(out, "    {}  {}", signature.position, signature.function)?;
+            writeln!(out, "        written:  {}", signature.written)?;
+            if let Some(explicit) = &signature.explicit {
+                writeln!(out, "        explicit: {explicit}")?;
+            }
+            if let Some(output) = &signature.output {
+                writeln!(out, "        output:   {output}")?;
+            }
+        }
+        Ok(())
+    }
+}
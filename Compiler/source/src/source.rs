@@ -0,0 +1,423 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use syn::spanned::Spanned;
+
+use crate::{error_at, Report};
+
+/// The items of every file of a crate as written in its source, read with
+/// `syn` rather than rustc: the files are found by following `mod`
+/// declarations from the crate root, but macros are not expanded and
+/// `#[cfg]` is ignored.
+#[derive(Serialize, Deserialize)]
+pub struct SourceReport {
+    pub files: Vec<SourceFile>,
+    /// Modules whose file is missing, and files that do not parse.
+    pub errors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SourceFile {
+    /// Relative to the directory of the crate root.
+    pub path: String,
+    /// The module the file is, e.g. `crate::shapes`.
+    pub module: String,
+    pub items: Vec<SourceItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SourceItem {
+    /// `fn`, `struct`, `enum`, `union`, `trait`, `trait alias`, `impl`,
+    /// `const`, `static`, `type`, `mod` or `macro_rules`.
+    pub kind: String,
+    /// The identifier after the keyword, `None` for an `impl`.
+    pub name: Option<String>,
+    /// The path of the item, e.g. `crate::shapes::inner::Square`, or the
+    /// header of an impl. Associated items only have their name.
+    pub path: String,
+    /// As written, `None` when there is none.
+    pub visibility: Option<String>,
+    /// `line:column` of the name of the item, or of `impl`, both starting
+    /// at 1.
+    pub position: String,
+    /// The lifetime parameters, with their bounds, e.g. `'b: 'a`.
+    pub lifetimes: Vec<String>,
+    /// The type and const parameters, with their bounds and defaults.
+    pub generics: Vec<String>,
+    /// The predicates of the where clause.
+    pub where_clauses: Vec<String>,
+    /// The path between `impl` and `for` as written, with the `!` of a
+    /// negative impl.
+    pub trait_ref: Option<String>,
+    /// The type an `impl` is for as written, generics and all.
+    pub self_ty: Option<String>,
+    /// The items of a trait or impl.
+    pub items: Vec<SourceItem>,
+}
+
+/// Reads the crate whose root is `root`, e.g. `src/lib.rs`.
+pub fn analyze_crate(root: &Path) -> SourceReport {
+    let mut analyzer = Analyzer {
+        base: root.parent().unwrap_or(Path::new("")).to_path_buf(),
+        report: SourceReport {
+            files: Vec::new(),
+            errors: Vec::new(),
+        },
+    };
+    let dir = analyzer.base.clone();
+    analyzer.file(root, "crate".to_string(), dir);
+    analyzer.report
+}
+
+struct Analyzer {
+    /// The directory of the crate root.
+    base: PathBuf,
+    report: SourceReport,
+}
+
+impl Analyzer {
+    /// Reads the module `module` from `path`, its submodules being in `dir`.
+    fn file(&mut self, path: &Path, module: String, dir: PathBuf) {
+        let display = path
+            .strip_prefix(&self.base)
+            .unwrap_or(path)
+            .display()
+            .to_string();
+        let file = fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|src| syn::parse_file(&src).map_err(|err| error_at(&err)));
+        let file = match file {
+            Ok(file) => file,
+            Err(err) => {
+                self.report.errors.push(format!("{display}: {err}"));
+                return;
+            }
+        };
+
+        let index = self.report.files.len();
+        self.report.files.push(SourceFile {
+            path: display,
+            module: module.clone(),
+            items: Vec::new(),
+        });
+        let mut items = Vec::new();
+        self.items(&file.items, &module, path, &dir, &mut items);
+        self.report.files[index].items = items;
+    }
+
+    /// Lists `items` of `module` into `out`, and reads the files of the
+    /// modules they declare. `path` is the file they are in.
+    fn items(
+        &mut self,
+        items: &[syn::Item],
+        module: &str,
+        path: &Path,
+        dir: &Path,
+        out: &mut Vec<SourceItem>,
+    ) {
+        for item in items {
+            let Some(entry) = item_entry(item, module) else {
+                continue;
+            };
+            out.push(entry);
+
+            let syn::Item::Mod(m) = item else {
+                continue;
+            };
+            let name = m.ident.to_string();
+            let submodule = format!("{module}::{name}");
+            let path_attr = path_attr(&m.attrs);
+            match &m.content {
+                // The items of an inline module are listed with those of the
+                // file, and its own submodules are in a directory of its name.
+                Some((_, inner)) => {
+                    let dir = dir.join(path_attr.as_deref().unwrap_or(&name));
+                    self.items(inner, &submodule, path, &dir, out);
+                }
+                None => {
+                    let candidates = match path_attr {
+                        // Relative to the directory of the file, as for rustc.
+                        Some(attr) => {
+                            vec![path.parent().unwrap_or(Path::new("")).join(attr)]
+                        }
+                        None => vec![
+                            dir.join(format!("{name}.rs")),
+                            dir.join(&name).join("mod.rs"),
+                        ],
+                    };
+                    match candidates.iter().find(|candidate| candidate.is_file()) {
+                        Some(file) => {
+                            let subdir = if file.file_name().is_some_and(|f| f == "mod.rs") {
+                                file.parent().unwrap_or(Path::new("")).to_path_buf()
+                            } else {
+                                dir.join(&name)
+                            };
+                            self.file(file, submodule, subdir);
+                        }
+                        None => self.report.errors.push(format!(
+                            "module `{submodule}` not found at {}",
+                            candidates
+                                .iter()
+                                .map(|c| c
+                                    .strip_prefix(&self.base)
+                                    .unwrap_or(c)
+                                    .display()
+                                    .to_string())
+                                .collect::<Vec<_>>()
+                                .join(" or ")
+                        )),
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The value of `#[path = "..."]`.
+fn path_attr(attrs: &[syn::Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| {
+        let syn::Meta::NameValue(meta) = &attr.meta else {
+            return None;
+        };
+        if !meta.path.is_ident("path") {
+            return None;
+        }
+        match &meta.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) => Some(s.value()),
+            _ => None,
+        }
+    })
+}
+
+/// The source text of `node`, on one line.
+fn text(node: &impl Spanned) -> String {
+    let text = node.span().source_text().unwrap_or_default();
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn position(span: proc_macro2::Span) -> String {
+    let start = span.start();
+    format!("{}:{}", start.line, start.column + 1)
+}
+
+fn visibility(vis: &syn::Visibility) -> Option<String> {
+    match vis {
+        syn::Visibility::Inherited => None,
+        vis => Some(text(vis)),
+    }
+}
+
+impl SourceItem {
+    fn new(kind: &str, name: Option<String>, path: String, at: proc_macro2::Span) -> Self {
+        SourceItem {
+            kind: kind.to_string(),
+            name,
+            path,
+            visibility: None,
+            position: position(at),
+            lifetimes: Vec::new(),
+            generics: Vec::new(),
+            where_clauses: Vec::new(),
+            trait_ref: None,
+            self_ty: None,
+            items: Vec::new(),
+        }
+    }
+
+    fn with_generics(mut self, generics: &syn::Generics) -> Self {
+        for param in &generics.params {
+            match param {
+                syn::GenericParam::Lifetime(lifetime) => self.lifetimes.push(text(lifetime)),
+                param => self.generics.push(text(param)),
+            }
+        }
+        if let Some(where_clause) = &generics.where_clause {
+            self.where_clauses = where_clause.predicates.iter().map(text).collect();
+        }
+        self
+    }
+
+    fn with_visibility(mut self, vis: &syn::Visibility) -> Self {
+        self.visibility = visibility(vis);
+        self
+    }
+}
+
+/// The entry of `item`, or `None` for those that are not listed: `use`,
+/// `extern crate`, `extern` blocks and macro calls.
+fn item_entry(item: &syn::Item, module: &str) -> Option<SourceItem> {
+    let named = |kind: &str, ident: &syn::Ident| {
+        SourceItem::new(
+            kind,
+            Some(ident.to_string()),
+            format!("{module}::{ident}"),
+            ident.span(),
+        )
+    };
+    let entry = match item {
+        syn::Item::Fn(f) => named("fn", &f.sig.ident)
+            .with_visibility(&f.vis)
+            .with_generics(&f.sig.generics),
+        syn::Item::Struct(s) => named("struct", &s.ident)
+            .with_visibility(&s.vis)
+            .with_generics(&s.generics),
+        syn::Item::Enum(e) => named("enum", &e.ident)
+            .with_visibility(&e.vis)
+            .with_generics(&e.generics),
+        syn::Item::Union(u) => named("union", &u.ident)
+            .with_visibility(&u.vis)
+            .with_generics(&u.generics),
+        syn::Item::Trait(t) => {
+            let mut entry = named("trait", &t.ident)
+                .with_visibility(&t.vis)
+                .with_generics(&t.generics);
+            entry.items = t.items.iter().filter_map(trait_item_entry).collect();
+            entry
+        }
+        syn::Item::TraitAlias(t) => named("trait alias", &t.ident)
+            .with_visibility(&t.vis)
+            .with_generics(&t.generics),
+        syn::Item::Impl(imp) => {
+            let self_ty = text(&imp.self_ty);
+            let trait_ref = imp.trait_.as_ref().map(|(bang, path, _)| {
+                let bang = if bang.is_some() { "!" } else { "" };
+                format!("{bang}{}", text(path))
+            });
+            let header = match &trait_ref {
+                Some(trait_ref) => format!("impl {trait_ref} for {self_ty}"),
+                None => format!("impl {self_ty}"),
+            };
+            let mut entry = SourceItem::new("impl", None, header, imp.impl_token.span)
+                .with_generics(&imp.generics);
+            entry.trait_ref = trait_ref;
+            entry.self_ty = Some(self_ty);
+            entry.items = imp.items.iter().filter_map(impl_item_entry).collect();
+            entry
+        }
+        syn::Item::Const(c) => named("const", &c.ident)
+            .with_visibility(&c.vis)
+            .with_generics(&c.generics),
+        syn::Item::Static(s) => named("static", &s.ident).with_visibility(&s.vis),
+        syn::Item::Type(t) => named("type", &t.ident)
+            .with_visibility(&t.vis)
+            .with_generics(&t.generics),
+        syn::Item::Mod(m) => named("mod", &m.ident).with_visibility(&m.vis),
+        syn::Item::Macro(m) => {
+            let ident = m.ident.as_ref()?;
+            named("macro_rules", ident)
+        }
+        _ => return None,
+    };
+    Some(entry)
+}
+
+fn trait_item_entry(item: &syn::TraitItem) -> Option<SourceItem> {
+    let assoc = |kind: &str, ident: &syn::Ident| {
+        SourceItem::new(
+            kind,
+            Some(ident.to_string()),
+            ident.to_string(),
+            ident.span(),
+        )
+    };
+    let entry = match item {
+        syn::TraitItem::Fn(f) => assoc("fn", &f.sig.ident).with_generics(&f.sig.generics),
+        syn::TraitItem::Const(c) => assoc("const", &c.ident).with_generics(&c.generics),
+        syn::TraitItem::Type(t) => assoc("type", &t.ident).with_generics(&t.generics),
+        _ => return None,
+    };
+    Some(entry)
+}
+
+fn impl_item_entry(item: &syn::ImplItem) -> Option<SourceItem> {
+    let assoc = |kind: &str, ident: &syn::Ident| {
+        SourceItem::new(
+            kind,
+            Some(ident.to_string()),
+            ident.to_string(),
+            ident.span(),
+        )
+    };
+    let entry = match item {
+        syn::ImplItem::Fn(f) => assoc("fn", &f.sig.ident)
+            .with_visibility(&f.vis)
+            .with_generics(&f.sig.generics),
+        syn::ImplItem::Const(c) => assoc("const", &c.ident)
+            .with_visibility(&c.vis)
+            .with_generics(&c.generics),
+        syn::ImplItem::Type(t) => assoc("type", &t.ident)
+            .with_visibility(&t.vis)
+            .with_generics(&t.generics),
+        _ => return None,
+    };
+    Some(entry)
+}
+
+impl Report for SourceReport {
+    /// One block per file, with a line per item and its generics below it:
+    ///
+    /// ```text
+    /// # shapes.rs (crate::shapes)
+    /// 3:1  pub struct crate::shapes::Wrapper
+    ///     lifetimes: 'a
+    ///     generics: T: Display
+    ///     where: T: Clone
+    /// ```
+    ///
+    /// The items of traits and impls are indented under them.
+    fn write_text(&self, mut out: impl Write) -> io::Result<()> {
+        for (i, file) in self.files.iter().enumerate() {
+            if i > 0 {
+                writeln!(out)?;
+            }
+            writeln!(out, "# {} ({})", file.path, file.module)?;
+            for item in &file.items {
+                write_item(&mut out, item, "")?;
+            }
+        }
+        for error in &self.errors {
+            writeln!(out, "error: {error}")?;
+        }
+        Ok(())
+    }
+
+    fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+fn write_item(out: &mut impl Write, item: &SourceItem, indent: &str) -> io::Result<()> {
+    let vis = match &item.visibility {
+        Some(vis) => format!("{vis} "),
+        None => String::new(),
+    };
+    if item.kind == "impl" {
+        writeln!(out, "{indent}{}  {}", item.position, item.path)?;
+    } else {
+        writeln!(
+            out,
+            "{indent}{}  {vis}{} {}",
+            item.position, item.kind, item.path
+        )?;
+    }
+    let details = [
+        ("lifetimes", &item.lifetimes),
+        ("generics", &item.generics),
+        ("where", &item.where_clauses),
+    ];
+    for (label, values) in details {
+        if !values.is_empty() {
+            writeln!(out, "{indent}    {label}: {}", values.join(", "))?;
+        }
+    }
+    let indent = format!("{indent}    ");
+    for item in &item.items {
+        write_item(out, item, &indent)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use std::path::Path;
+use std::process::Command;
+
+use serde_json::Value;
+
+fn collect_hrtb(file: &str, format: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_collect-hrtb"))
+        .args(["--format", format, file])
+        .current_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/hrtb"))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn tells_late_from_early_bound() {
+    let stdout = collect_hrtb("bounds.rs", "text");
+    let expected = "\
+binders of bounds.rs:
+    3:4  longest
+        early-bound: 'a, appears in a bound
+        early-bound: 'b, appears in a bound
+    7:4  make
+        early-bound: 'a, does not appear in the arguments
+    18:4  call_with
+        late-bound:  'x
+";
+    assert!(stdout.starts_with(expected), "{stdout}");
+}
+
+#[test]
+fn impl_trait_bounds_and_projections_make_lifetimes_early_bound() {
+    let stdout = collect_hrtb("early.rs", "text");
+    let expected = "\
+binders of early.rs:
+    9:4  apit
+        early-bound: 'a, appears in a bound
+    13:4  project
+        late-bound:  'b
+        early-bound: 'a, only appears in the arguments under a projection
+";
+    assert!(stdout.starts_with(expected), "{stdout}");
+}
+
+#[test]
+fn each_hidden_lifetime_of_a_type_is_bound() {
+    let stdout = collect_hrtb("pair.rs", "text");
+    let expected = "\
+higher-ranked bounds of pair.rs:
+    3:18  first, impl Trait
+        written:   Fn(Pair) -> &str
+        binds:     'a, 'b (elided)
+        error:     the output lifetime cannot be elided: there are 2 input lifetimes
+    5:18  split, impl Trait
+        written:   Fn(&str) -> Pair
+        binds:     'a (elided)
+        desugared: for<'a> Fn(&'a str) -> Pair<'a, 'a>
+        unsugared: for<'a> Fn<(&'a str,), Output = Pair<'a, 'a>>
+";
+    assert!(stdout.ends_with(expected), "{stdout}");
+}
+
+#[test]
+fn desugars_higher_ranked_bounds() {
+    let stdout = collect_hrtb("bounds.rs", "text");
+    let expected = "
+higher-ranked bounds of bounds.rs:
+    13:8  apply, bound
+        written:   Fn(&T) -> &U
+        binds:     'a (elided)
+        desugared: for<'a> Fn(&'a T) -> &'a U
+        unsugared: for<'a> Fn<(&'a T,), Output = &'a U>
+    18:26  call_with, trait object
+        written:   for<'a> Fn(&'a str, &str) -> &'a str
+        binds:     'a, 'b (elided)
+        desugared: for<'a, 'b> Fn(&'a str, &'b str) -> &'a str
+        unsugared: for<'a, 'b> Fn<(&'a str, &'b str), Output = &'a str>
+    23:23  Callbacks, trait object
+        written:   FnMut(&str)
+        binds:     'a (elided)
+        desugared: for<'a> FnMut(&'a str)
+        unsugared: for<'a> FnMut<(&'a str,), Output = ()>
+    24:12  Callbacks, fn pointer
+        written:   fn(&[u8]) -> &str
+        binds:     'a (elided)
+        desugared: for<'a> fn(&'a [u8]) -> &'a str
+    25:11  Callbacks, fn pointer
+        written:   fn(&str, &str) -> &str
+        binds:     'a, 'b (elided)
+        error:     the output lifetime cannot be elided: there are 2 input lifetimes
+    31:5  print_all, where clause
+        written:   for<'a> &'a I::Item: Debug
+        binds:     'a
+";
+    assert!(stdout.ends_with(expected), "{stdout}");
+}
+
+#[test]
+fn json_report() {
+    let report: Value = serde_json::from_str(&collect_hrtb("bounds.rs", "json")).unwrap();
+    let call_with = &report["functions"][2];
+    assert_eq!(call_with["function"], "call_with");
+    assert_eq!(call_with["late_bound"][0], "'x");
+    let pick = &report["bounds"][4];
+    assert_eq!(pick["desugared"], Value::Null);
+    assert_eq!(pick["binds"][1], "'b");
+}
@@ -0,0 +1,33 @@
+use std::fmt::Debug;
+
+fn longest<'a, 'b: 'a>(x: &'a str, y: &'b str) -> &'a str {
+    x
+}
+
+fn make<'a>() -> &'a str {
+    ""
+}
+
+fn apply<T, U, F>(f: F, value: &T) -> &U
+where
+    F: Fn(&T) -> &U,
+{
+    f(value)
+}
+
+fn call_with<'x>(f: &dyn for<'a> Fn(&'a str, &str) -> &'a str, s: &'x str) -> &'x str {
+    f(s, "")
+}
+
+struct Callbacks {
+    on_event: Box<dyn FnMut(&str)>,
+    parse: fn(&[u8]) -> &str,
+    pick: fn(&str, &str) -> &str,
+}
+
+fn print_all<I>(items: I)
+where
+    I: IntoIterator,
+    for<'a> &'a I::Item: Debug,
+{
+}
@@ -0,0 +1,15 @@
+trait Lookup<'a> {
+    type Output;
+}
+
+impl<'a> Lookup<'a> for u8 {
+    type Output = &'a u8;
+}
+
+fn apit<'a>(f: impl Fn(&'a u8) -> &'a u8, x: &'a u8) -> &'a u8 {
+    f(x)
+}
+
+fn project<'a, 'b>(_: <u8 as Lookup<'a>>::Output, y: &'b u8) -> &'b u8 {
+    y
+}
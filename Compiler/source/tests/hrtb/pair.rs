@@ -0,0 +1,5 @@
+struct Pair<'x, 'y>(&'x str, &'y str);
+
+fn first(f: impl Fn(Pair) -> &str) {}
+
+fn split(f: impl Fn(&str) -> Pair) {}
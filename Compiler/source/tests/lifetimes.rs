@@ -0,0 +1,111 @@
+use std::path::Path;
+use std::process::Command;
+
+use serde_json::Value;
+
+fn collect_lifetimes(file: &str, format: &str) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_collect-lifetimes"))
+        .args(["--format", format, file])
+        .current_dir(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/lifetimes"))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn lists_declarations_and_uses() {
+    let stdout = collect_lifetimes("elision.rs", "text");
+    let expected = "\
+lifetimes of elision.rs:
+    6:15   'a       declared  struct parameter  Parser
+    7:13   'a       used      field             Parser
+    10:6   'a       declared  impl parameter    impl Parser<'a>
+    10:17  'a       used      impl header       impl Parser<'a>
+    15:38  'a       used      fn signature      Parser<'a>::rest
+    28:9   'x       declared  fn parameter      pick
+    28:17  'x       used      fn signature      pick
+    28:29  'x       used      fn signature      pick
+    34:57  '_       used      fn signature      parse
+    42:35  'b       declared  higher-ranked     show
+    42:40  'b       used      bound             show
+    43:33  'static  used      trait object      show
+    46:15  'static  used      static            NAME
+";
+    assert!(stdout.starts_with(expected), "{stdout}");
+}
+
+#[test]
+fn spells_out_elided_lifetimes() {
+    let stdout = collect_lifetimes("elision.rs", "text");
+    let expected = "
+elided lifetimes:
+    11:8  Parser<'a>::peek
+        written:  fn peek(&self) -> &str
+        explicit: fn peek<'b>(&'b self) -> &'b str
+        output:   'b, that of `self`
+    15:8  Parser<'a>::rest
+        written:  fn rest(&mut self, n: usize) -> &'a str
+        explicit: fn rest<'b>(&'b mut self, n: usize) -> &'a str
+    20:4  first_word
+        written:  fn first_word(s: &str) -> &str
+        explicit: fn first_word<'a>(s: &'a str) -> &'a str
+        output:   'a, the only input lifetime
+    24:4  longest
+        written:  fn longest(x: &str, y: &str) -> &str
+        output:   cannot be elided: it could borrow from any of `x`, `y`
+    28:4  pick
+        written:  fn pick<'x>(x: &'x str, y: &'x str) -> &str
+        explicit: fn pick<'x>(x: &'x str, y: &'x str) -> &'x str
+        output:   'x, the only input lifetime
+    34:4  parse
+        written:  fn parse(p: Parser, f: impl Fn(&str) -> &str) -> Parser<'_>
+        explicit: fn parse<'a>(p: Parser<'a>, f: impl Fn(&str) -> &str) -> Parser<'a>
+        output:   'a, the only input lifetime
+    38:4  none
+        written:  fn none() -> &str
+        output:   cannot be elided: there is no input lifetime to borrow from
+    42:4  show
+        written:  fn show<T>(items: &[T]) where for<'b> &'b T: Debug
+        explicit: fn show<'a, T>(items: &'a [T]) where for<'b> &'b T: Debug
+";
+    assert!(stdout.ends_with(expected), "{stdout}");
+}
+
+#[test]
+fn each_hidden_lifetime_of_a_type_is_an_input_lifetime() {
+    let stdout = collect_lifetimes("pair.rs", "text");
+    let expected = "
+elided lifetimes:
+    3:4  both
+        written:  fn both(p: Pair)
+        explicit: fn both<'a, 'b>(p: Pair<'a, 'b>)
+    5:4  first
+        written:  fn first(p: Pair) -> &str
+        output:   cannot be elided: it could borrow from any of the 2 lifetimes of `p`
+    9:4  split
+        written:  fn split(s: &str) -> Pair
+        explicit: fn split<'a>(s: &'a str) -> Pair<'a, 'a>
+        output:   'a, the only input lifetime
+    13:4  keep
+        written:  fn keep(p: Pair<u8>, s: &str)
+        explicit: fn keep<'a, 'b, 'c>(p: Pair<'a, 'b, u8>, s: &'c str)
+";
+    assert!(stdout.ends_with(expected), "{stdout}");
+}
+
+#[test]
+fn json_report() {
+    let report: Value = serde_json::from_str(&collect_lifetimes("elision.rs", "json")).unwrap();
+    let hrtb = &report["sites"][9];
+    assert_eq!(hrtb["lifetime"], "'b");
+    assert_eq!(hrtb["declaration"], true);
+    assert_eq!(hrtb["context"], "higher-ranked");
+    let longest = &report["signatures"][3];
+    assert_eq!(longest["function"], "longest");
+    assert_eq!(longest["explicit"], Value::Null);
+}
@@ -0,0 +1,46 @@
+// The places of `where_lifetime_parameter_exists.md`, and signatures elision
+// completes, fails on, or has nothing to do with.
+
+use std::fmt::Debug;
+
+struct Parser<'a> {
+    input: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> &str {
+        self.input
+    }
+
+    fn rest(&mut self, n: usize) -> &'a str {
+        &self.input[n..]
+    }
+}
+
+fn first_word(s: &str) -> &str {
+    s.split(' ').next().unwrap_or(s)
+}
+
+fn longest(x: &str, y: &str) -> &str {
+    x
+}
+
+fn pick<'x>(x: &'x str, y: &'x str) -> &str {
+    'outer: loop {
+        break 'outer x;
+    }
+}
+
+fn parse(p: Parser, f: impl Fn(&str) -> &str) -> Parser<'_> {
+    p
+}
+
+fn none() -> &str {
+    ""
+}
+
+fn show<T>(items: &[T]) where for<'b> &'b T: Debug {
+    let _boxed: Box<dyn Debug + 'static> = Box::new(1);
+}
+
+static NAME: &'static str = "x";
@@ -0,0 +1,13 @@
+struct Pair<'x, 'y, T = ()>(&'x str, &'y str, T);
+
+fn both(p: Pair) {}
+
+fn first(p: Pair) -> &str {
+    p.0
+}
+
+fn split(s: &str) -> Pair {
+    Pair(s, s, ())
+}
+
+fn keep(p: Pair<u8>, s: &str) {}
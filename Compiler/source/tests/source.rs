@@ -0,0 +1,92 @@
+use std::path::Path;
+use std::process::{Command, Output};
+
+use serde_json::Value;
+
+fn collect_items(root: &Path, format: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_collect-items"))
+        .args(["--format", format])
+        .arg(root)
+        .output()
+        .unwrap()
+}
+
+fn fixture() -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/source/lib.rs")
+}
+
+#[test]
+fn follows_mod_declarations_into_files() {
+    let output = collect_items(&fixture(), "text");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let expected = "\
+# lib.rs (crate)
+3:5  mod crate::shapes
+4:5  mod crate::util
+6:5  mod crate::extra
+10:15  pub(crate) fn crate::longest
+    lifetimes: 'a, 'b: 'a
+18:8  pub fn crate::demo
+
+# shapes.rs (crate::shapes)
+1:9  pub mod crate::shapes::circle
+3:11  pub trait crate::shapes::Shape
+    4:8  fn area
+    6:8  fn scaled
+        generics: T
+        where: T: Into<f64>
+14:12  pub struct crate::shapes::Wrapper
+    lifetimes: 'a
+    generics: T: ?Sized, const N: usize = 1
+    where: T: Shape
+
+# shapes/circle.rs (crate::shapes::circle)
+1:12  pub struct crate::shapes::circle::Circle
+5:1  impl super::Shape for Circle
+    6:8  fn area
+
+# util/mod.rs (crate::util)
+1:9  pub mod crate::util::inner
+2:12  pub fn crate::util::inner::noop
+
+# renamed.rs (crate::extra)
+1:8  pub fn crate::extra::noop
+";
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+}
+
+#[test]
+fn json_report() {
+    let output = collect_items(&fixture(), "json");
+    let report: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let circle = &report["files"][2];
+    assert_eq!(circle["path"], "shapes/circle.rs");
+    let imp = &circle["items"][1];
+    assert_eq!(imp["kind"], "impl");
+    assert_eq!(imp["name"], Value::Null);
+    assert_eq!(imp["trait_ref"], "super::Shape");
+    assert_eq!(imp["self_ty"], "Circle");
+    assert_eq!(imp["items"][0]["path"], "area");
+    assert_eq!(report["errors"], serde_json::json!([]));
+}
+
+#[test]
+fn missing_modules_are_reported() {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("collect-items-missing");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("lib.rs"), "mod gone;\n\npub struct Kept;\n").unwrap();
+
+    let output = collect_items(&dir.join("lib.rs"), "text");
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "# lib.rs (crate)\n\
+         1:5  mod crate::gone\n\
+         3:12  pub struct crate::Kept\n\
+         error: module `crate::gone` not found at gone.rs or gone/mod.rs\n"
+    );
+}
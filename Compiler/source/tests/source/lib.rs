@@ -0,0 +1,23 @@
+// A crate whose modules are spread over files the way rustc looks for them.
+
+mod shapes;
+mod util;
+#[path = "renamed.rs"]
+mod extra;
+
+use shapes::Shape;
+
+pub(crate) fn longest<'a, 'b: 'a>(x: &'a str, y: &'b str) -> &'a str {
+    if x.len() > y.len() {
+        x
+    } else {
+        y
+    }
+}
+
+pub fn demo() {
+    let circle = shapes::circle::Circle { radius: 1.0 };
+    println!("{} {}", circle.area(), longest("a", "bc"));
+    util::inner::noop();
+    extra::noop();
+}
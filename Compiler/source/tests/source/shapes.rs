@@ -0,0 +1,19 @@
+pub mod circle;
+
+pub trait Shape {
+    fn area(&self) -> f64;
+
+    fn scaled<T>(&self, factor: T) -> f64
+    where
+        T: Into<f64>,
+    {
+        self.area() * factor.into()
+    }
+}
+
+pub struct Wrapper<'a, T: ?Sized, const N: usize = 1>
+where
+    T: Shape,
+{
+    pub shapes: [&'a T; N],
+}
@@ -0,0 +1,9 @@
+pub struct Circle {
+    pub radius: f64,
+}
+
+impl super::Shape for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.radius * self.radius
+    }
+}
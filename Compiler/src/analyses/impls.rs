@@ -0,0 +1,219 @@
+//! `impls`: the [`ImplReport`] of a trait, explaining which of its impls
+//! applies to a type when one is given.
+
+use collect_lang_items::impls::{Candidate, Explanation, ImplEntry, ImplReport};
+use rustc_driver::Compilation;
+use rustc_hir::def::DefKind;
+use rustc_hir::LangItem;
+use rustc_infer::infer::TyCtxtInferExt;
+use rustc_infer::traits::{Obligation, ObligationCause};
+use rustc_interface::interface;
+use rustc_middle::ty::print::{with_forced_trimmed_paths, PrintTraitRefExt};
+use rustc_middle::ty::{self, Ty, TyCtxt, TypeVisitableExt, TypingMode};
+use rustc_span::def_id::{DefId, LOCAL_CRATE};
+use rustc_span::DUMMY_SP;
+use rustc_trait_selection::infer::InferCtxtExt;
+use rustc_trait_selection::traits::ObligationCtxt;
+
+use super::Options;
+
+pub struct Impls {
+    pub options: Options,
+    /// The path of the trait, as rustc prints it, e.g. `DefaultPrintable` or
+    /// `std::fmt::Display`.
+    pub trait_path: String,
+    /// A type the crate mentions, e.g. `&str`, for which to explain which
+    /// impl applies.
+    pub ty: Option<String>,
+}
+
+impl rustc_driver::Callbacks for Impls {
+    fn config(&mut self, config: &mut interface::Config) {
+        self.options.config(config);
+    }
+
+    fn after_analysis<'tcx>(
+        &mut self,
+        _compiler: &interface::Compiler,
+        tcx: TyCtxt<'tcx>,
+    ) -> Compilation {
+        let report = collect_impls(tcx, &self.trait_path, self.ty.as_deref());
+        self.options.print(tcx, &report);
+        Compilation::Stop
+    }
+}
+
+/// Every impl of the trait, from this crate and its dependencies, and when
+/// `ty` is given, which of them the trait solver finds to apply to it.
+fn collect_impls(tcx: TyCtxt<'_>, trait_path: &str, ty: Option<&str>) -> ImplReport {
+    let Some(trait_def_id) = tcx
+        .all_traits_including_private()
+        .find(|&def_id| tcx.def_path_str(def_id) == trait_path)
+    else {
+        tcx.dcx().fatal(format!(
+            "no trait `{trait_path}` in the crate or its dependencies"
+        ));
+    };
+    let source_map = tcx.sess.source_map();
+    let impl_def_ids: Vec<DefId> = tcx.all_impls(trait_def_id).collect();
+
+    let impls = impl_def_ids
+        .iter()
+        .map(|&impl_def_id| {
+            let trait_ref = tcx.impl_trait_ref(impl_def_id).skip_binder();
+            let self_ty = tcx.type_of(impl_def_id).skip_binder();
+            let params: Vec<String> = tcx
+                .generics_of(impl_def_id)
+                .own_params
+                .iter()
+                .map(|param| param.name.to_string())
+                // Elided lifetimes, as in `impl Printable for &str`.
+                .filter(|name| name != "'_")
+                .collect();
+            let generics = if params.is_empty() {
+                String::new()
+            } else {
+                format!("<{}>", params.join(", "))
+            };
+            ImplEntry {
+                header: format!(
+                    "impl{generics} {} for {self_ty}",
+                    trait_ref.print_only_trait_path()
+                ),
+                where_clauses: tcx
+                    .predicates_of(impl_def_id)
+                    .predicates
+                    .iter()
+                    .filter(|(clause, _)| !is_implicit_bound(tcx, *clause))
+                    .map(|(clause, _)| clause.to_string())
+                    .collect(),
+                self_ty: self_ty.to_string(),
+                blanket: matches!(self_ty.kind(), ty::Param(_)),
+                defining_crate: tcx.crate_name(impl_def_id.krate).to_string(),
+                span: source_map.span_to_diagnostic_string(tcx.def_span(impl_def_id)),
+            }
+        })
+        .collect();
+
+    let explanation = ty.map(|ty| {
+        let Some(self_ty) = find_type(tcx, ty) else {
+            tcx.dcx()
+                .fatal(format!("the crate mentions no type `{ty}`"));
+        };
+        explain(tcx, trait_def_id, &impl_def_ids, self_ty)
+    });
+    ImplReport {
+        krate: tcx.crate_name(LOCAL_CRATE).to_string(),
+        trait_path: trait_path.to_string(),
+        impls,
+        explanation,
+    }
+}
+
+/// `T: MetaSized`, which rustc adds for `T: ?Sized` and never prints either.
+fn is_implicit_bound<'tcx>(tcx: TyCtxt<'tcx>, clause: ty::Clause<'tcx>) -> bool {
+    clause.as_trait_clause().is_some_and(|trait_clause| {
+        let def_id = trait_clause.def_id();
+        tcx.is_lang_item(def_id, LangItem::MetaSized)
+            || tcx.is_lang_item(def_id, LangItem::PointeeSized)
+    })
+}
+
+/// Finds a type by how rustc prints it among the types of the crate: those of
+/// every expression and pattern, and the crate's own non-generic types. Types
+/// cannot be named otherwise without resolving a path the crate never wrote.
+fn find_type<'tcx>(tcx: TyCtxt<'tcx>, name: &str) -> Option<Ty<'tcx>> {
+    let strip = |s: &str| s.split_whitespace().collect::<String>();
+    let name = strip(name);
+
+    let adts = tcx.hir_free_items().filter_map(|id| {
+        let def_id = id.owner_id.to_def_id();
+        let is_adt = matches!(
+            tcx.def_kind(def_id),
+            DefKind::Struct | DefKind::Enum | DefKind::Union
+        );
+        (is_adt && tcx.generics_of(def_id).is_empty()).then(|| tcx.type_of(def_id).skip_binder())
+    });
+    let in_bodies = tcx
+        .hir_body_owners()
+        .filter(|&def_id| !tcx.is_typeck_child(def_id.to_def_id()))
+        .flat_map(|def_id| {
+            let results = tcx.typeck(def_id);
+            results
+                .node_types()
+                .items_in_stable_order()
+                .into_iter()
+                .map(|(_, &ty)| ty)
+                .collect::<Vec<_>>()
+        });
+    adts.chain(in_bodies)
+        .map(|ty| tcx.erase_and_anonymize_regions(ty))
+        .filter(|ty| !ty.has_param() && !ty.has_infer() && !ty.references_error())
+        .find(|ty| {
+            strip(&ty.to_string()) == name
+                || strip(&with_forced_trimmed_paths!(ty.to_string())) == name
+        })
+}
+
+/// Tries each impl on its own: its self type must unify with `self_ty`, then
+/// its where clauses must hold for the resulting arguments.
+fn explain<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    trait_def_id: DefId,
+    impl_def_ids: &[DefId],
+    self_ty: Ty<'tcx>,
+) -> Explanation {
+    let param_env = ty::ParamEnv::empty();
+    let cause = ObligationCause::dummy();
+    let candidates = impl_def_ids
+        .iter()
+        .map(|&impl_def_id| {
+            let infcx = tcx.infer_ctxt().build(TypingMode::non_body_analysis());
+            let ocx = ObligationCtxt::new_with_diagnostics(&infcx);
+            let args = infcx.fresh_args_for_item(DUMMY_SP, impl_def_id);
+            let impl_self_ty = tcx
+                .type_of(impl_def_id)
+                .instantiate(tcx, args)
+                .skip_normalization();
+            if ocx.eq(&cause, param_env, impl_self_ty, self_ty).is_err() {
+                return Candidate {
+                    applies: false,
+                    self_ty_matches: false,
+                    reason: Some(format!(
+                        "`{self_ty}` is not `{}`",
+                        tcx.type_of(impl_def_id).skip_binder()
+                    )),
+                };
+            }
+            let predicates = tcx.predicates_of(impl_def_id).instantiate(tcx, args);
+            ocx.register_obligations(predicates.predicates.into_iter().map(|clause| {
+                Obligation::new(tcx, cause.clone(), param_env, clause.skip_normalization())
+            }));
+            let errors = ocx.evaluate_obligations_error_on_ambiguity();
+            let reason = errors.first().map(|error| {
+                let predicate = infcx.resolve_vars_if_possible(error.obligation.predicate);
+                format!("`{predicate}` does not hold")
+            });
+            Candidate {
+                applies: reason.is_none(),
+                self_ty_matches: true,
+                reason,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Only self-only traits can be asked about without more arguments.
+    let implemented = if tcx.generics_of(trait_def_id).count() == 1 {
+        let infcx = tcx.infer_ctxt().build(TypingMode::non_body_analysis());
+        infcx
+            .type_implements_trait(trait_def_id, [self_ty], param_env)
+            .must_apply_modulo_regions()
+    } else {
+        candidates.iter().any(|candidate| candidate.applies)
+    };
+    Explanation {
+        ty: self_ty.to_string(),
+        candidates,
+        implemented,
+    }
+}
@@ -0,0 +1,92 @@
+//! `items`: the [`ItemInventory`] of the crate.
+
+use collect_lang_items::items::{ItemEntry, ItemInventory};
+use rustc_driver::Compilation;
+use rustc_hir::ItemKind;
+use rustc_interface::interface;
+use rustc_middle::ty::print::PrintTraitRefExt;
+use rustc_middle::ty::{TyCtxt, Visibility};
+use rustc_span::def_id::{LocalDefId, LOCAL_CRATE};
+
+use super::Options;
+
+pub struct Items {
+    pub options: Options,
+}
+
+impl rustc_driver::Callbacks for Items {
+    fn config(&mut self, config: &mut interface::Config) {
+        self.options.config(config);
+    }
+
+    fn after_analysis<'tcx>(
+        &mut self,
+        _compiler: &interface::Compiler,
+        tcx: TyCtxt<'tcx>,
+    ) -> Compilation {
+        self.options.print(tcx, &collect_items(tcx));
+        Compilation::Stop
+    }
+}
+
+/// Every free item of the crate, in source order. Uses, extern crates,
+/// foreign modules, macros and global asm are left out.
+fn collect_items(tcx: TyCtxt<'_>) -> ItemInventory {
+    let source_map = tcx.sess.source_map();
+    let items = tcx
+        .hir_free_items()
+        .filter_map(|id| {
+            let item = tcx.hir_item(id);
+            let def_id = item.owner_id.to_def_id();
+            let kind = match item.kind {
+                ItemKind::Fn { .. } => "fn",
+                ItemKind::Struct(..) => "struct",
+                ItemKind::Enum(..) => "enum",
+                ItemKind::Union(..) => "union",
+                ItemKind::Trait { .. } => "trait",
+                ItemKind::TraitAlias(..) => "trait alias",
+                ItemKind::Impl(_) => "impl",
+                ItemKind::Const(..) => "const",
+                ItemKind::Static(..) => "static",
+                ItemKind::TyAlias(..) => "type",
+                ItemKind::Mod(..) => "mod",
+                ItemKind::ExternCrate(..)
+                | ItemKind::Use(..)
+                | ItemKind::Macro(..)
+                | ItemKind::ForeignMod { .. }
+                | ItemKind::GlobalAsm { .. } => return None,
+            };
+            let is_impl = matches!(item.kind, ItemKind::Impl(_));
+            Some(ItemEntry {
+                kind: kind.to_string(),
+                name: item.kind.ident().map(|ident| ident.to_string()),
+                def_path: tcx.def_path_str(def_id),
+                visibility: (!is_impl).then(|| visibility(tcx, item.owner_id.def_id)),
+                span: source_map.span_to_diagnostic_string(tcx.def_span(def_id)),
+                trait_ref: is_impl
+                    .then(|| tcx.impl_opt_trait_ref(def_id))
+                    .flatten()
+                    .map(|trait_ref| trait_ref.skip_binder().print_only_trait_path().to_string()),
+                self_ty: is_impl.then(|| tcx.type_of(def_id).skip_binder().to_string()),
+            })
+        })
+        .collect();
+    ItemInventory {
+        krate: tcx.crate_name(LOCAL_CRATE).to_string(),
+        items,
+    }
+}
+
+/// Like `Visibility::to_string`, except that private items of the crate
+/// root are `pub(self)` rather than `pub(crate)`.
+fn visibility(tcx: TyCtxt<'_>, def_id: LocalDefId) -> String {
+    let visibility = tcx.visibility(def_id).map_id(|id| id.expect_local());
+    match visibility {
+        Visibility::Restricted(module)
+            if module == tcx.parent_module_from_def_id(def_id).to_local_def_id() =>
+        {
+            "pub(self)".to_string()
+        }
+        _ => visibility.to_string(def_id, tcx),
+    }
+}
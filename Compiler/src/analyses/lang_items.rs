@@ -0,0 +1,144 @@
+//! `lang-items`: the lang items the crate can see, and which crate defines
+//! each. Also what the driver writes for `cargo collect-lang-items`.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{self, Path, PathBuf};
+
+use collect_lang_items::report::{LangItemEntry, LangItemReport};
+use collect_lang_items::OUT_DIR_ENV;
+use collect_report::Report;
+use rustc_driver::Compilation;
+use rustc_hir::attrs::CrateType;
+use rustc_interface::interface;
+use rustc_middle::ty::TyCtxt;
+use rustc_span::def_id::LOCAL_CRATE;
+use rustc_span::Symbol;
+
+use super::Options;
+
+pub struct LangItems {
+    pub options: Options,
+    /// Where the report is written rather than printed, when the driver is
+    /// cargo's `RUSTC_WORKSPACE_WRAPPER`. Compilation then goes on, so that
+    /// cargo gets the metadata the crates depending on this one need.
+    pub out_dir: Option<PathBuf>,
+}
+
+impl rustc_driver::Callbacks for LangItems {
+    fn config(&mut self, config: &mut interface::Config) {
+        self.options.config(config);
+        // Each run of `cargo collect-lang-items` writes to a directory of its
+        // own. Tracking it in the dep-info makes cargo check the crate again
+        // in the next run rather than find it fresh and leave it out.
+        if let Some(dir) = &self.out_dir {
+            let dir = dir.to_string_lossy().into_owned();
+            config.track_state = Some(Box::new(move |sess| {
+                sess.env_depinfo
+                    .borrow_mut()
+                    .insert((Symbol::intern(OUT_DIR_ENV), Some(Symbol::intern(&dir))));
+            }));
+        }
+    }
+
+    fn after_analysis<'tcx>(
+        &mut self,
+        _compiler: &interface::Compiler,
+        tcx: TyCtxt<'tcx>,
+    ) -> Compilation {
+        let Some(dir) = &self.out_dir else {
+            self.options.print(tcx, &collect(tcx));
+            return Compilation::Stop;
+        };
+        // Only the packages selected on cargo's command line are reported,
+        // not the crates of the workspace they depend on.
+        if env::var_os("CARGO_PRIMARY_PACKAGE").is_none() {
+            return Compilation::Continue;
+        }
+        if let Err(err) = write_report(dir, &cargo_report(tcx)) {
+            tcx.dcx()
+                .fatal(format!("failed to write the report: {err}"));
+        }
+        Compilation::Continue
+    }
+}
+
+fn collect(tcx: TyCtxt<'_>) -> LangItemReport {
+    let lang_items = tcx.lang_items();
+    let source_map = tcx.sess.source_map();
+
+    let defined = lang_items.iter().map(|(item, def_id)| LangItemEntry {
+        name: item.name().to_string(),
+        target: item.target().to_string(),
+        defining_crate: Some(tcx.crate_name(def_id.krate).to_string()),
+        def_id: Some(format!("{def_id:?}")),
+        def_path: Some(tcx.def_path_str(def_id)),
+        span: Some(source_map.span_to_diagnostic_string(tcx.def_span(def_id))),
+        missing: false,
+    });
+    let missing = lang_items.missing.iter().map(|item| LangItemEntry {
+        name: item.name().to_string(),
+        target: item.target().to_string(),
+        defining_crate: None,
+        def_id: None,
+        def_path: None,
+        span: None,
+        missing: true,
+    });
+
+    let mut lang_items: Vec<_> = defined.chain(missing).collect();
+    lang_items.sort_by(|a, b| a.name.cmp(&b.name));
+    let crate_types = tcx
+        .crate_types()
+        .iter()
+        .map(|ty| {
+            CrateType::all()
+                .iter()
+                .find(|(_, other)| other == ty)
+                .map_or_else(|| format!("{ty:?}"), |(name, _)| name.to_string())
+        })
+        .collect();
+    LangItemReport {
+        krate: tcx.crate_name(LOCAL_CRATE).to_string(),
+        crate_types,
+        package: None,
+        target: None,
+        test: false,
+        lang_items,
+    }
+}
+
+/// The report of the crate, with the package and target cargo builds it as.
+fn cargo_report(tcx: TyCtxt<'_>) -> LangItemReport {
+    // Cargo gives the root relative to the workspace, where it runs rustc.
+    let root = tcx
+        .sess
+        .io
+        .input
+        .opt_path()
+        .and_then(|root| path::absolute(root).ok());
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").map(PathBuf::from);
+    let target = root.map(|root| match manifest_dir {
+        Some(dir) => root
+            .strip_prefix(dir)
+            .map_or(root.clone(), Path::to_path_buf),
+        None => root,
+    });
+    let package = env::var("CARGO_PKG_NAME")
+        .ok()
+        .zip(env::var("CARGO_PKG_VERSION").ok())
+        .map(|(name, version)| format!("{name}@{version}"));
+    LangItemReport {
+        package,
+        target: target.map(|target| target.display().to_string()),
+        test: tcx.sess.opts.test,
+        ..collect(tcx)
+    }
+}
+
+fn write_report(dir: &Path, report: &LangItemReport) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let file = fs::File::create(dir.join(report.file_name()))?;
+    report.write_json(io::BufWriter::new(file))
+}
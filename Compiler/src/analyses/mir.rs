@@ -0,0 +1,253 @@
+//! `mir`: the [`MirReport`] of a function, as text, JSON or its region
+//! constraint graph in Graphviz.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io;
+
+use collect_lang_items::mir::{Borrow, Constraint, MirReport, Point, RegionEntry};
+use rustc_borrowck::consumers::{
+    calculate_borrows_out_of_scope_at_location, get_bodies_with_borrowck_facts, BorrowData,
+    BorrowIndex, ConsumerOptions,
+};
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_driver::Compilation;
+use rustc_hir::def::DefKind;
+use rustc_interface::interface;
+use rustc_middle::mir::pretty::MirWriter;
+use rustc_middle::mir::{self, BorrowKind};
+use rustc_middle::ty::{self, RegionVid, Ty, TyCtxt};
+use rustc_span::def_id::{LocalDefId, LOCAL_CRATE};
+
+use super::{Format, Options};
+
+pub struct Mir {
+    pub options: Options,
+    /// The path of the function, as rustc prints it, e.g. `longest` or
+    /// `Parser::next_token`.
+    pub function: String,
+}
+
+impl rustc_driver::Callbacks for Mir {
+    fn config(&mut self, config: &mut interface::Config) {
+        self.options.config(config);
+        // So that the MIR names the region variable of every reference.
+        config.opts.unstable_opts.identify_regions = true;
+    }
+
+    /// The report is printed before analysis, so the driver's lints do not
+    /// run.
+    fn after_expansion<'tcx>(
+        &mut self,
+        _compiler: &interface::Compiler,
+        tcx: TyCtxt<'tcx>,
+    ) -> Compilation {
+        let report = collect_mir(tcx, &self.function);
+        match self.options.format {
+            Format::Dot => {
+                if let Err(err) = report.write_dot(io::stdout().lock()) {
+                    tcx.dcx()
+                        .fatal(format!("failed to write the report: {err}"));
+                }
+            }
+            Format::Text | Format::Json => self.options.print(tcx, &report),
+        }
+        Compilation::Stop
+    }
+}
+
+/// The MIR of the function, with the regions the borrow checker inferred
+/// for it, the outlives constraints between them and where each borrow is
+/// live. Runs before analysis, which steals the MIR the borrow checker needs.
+fn collect_mir(tcx: TyCtxt<'_>, function: &str) -> MirReport {
+    let Some(def_id) = tcx.hir_body_owners().find(|&def_id| {
+        !tcx.is_typeck_child(def_id.to_def_id()) && tcx.def_path_str(def_id) == function
+    }) else {
+        tcx.dcx()
+            .fatal(format!("no function `{function}` in the crate"));
+    };
+    let mut bodies =
+        get_bodies_with_borrowck_facts(tcx, def_id, ConsumerOptions::PoloniusInputFacts);
+    let (Some(facts), None) = (bodies.remove(&def_id), tcx.dcx().has_errors()) else {
+        tcx.dcx().fatal(format!("`{function}` does not type check"));
+    };
+    let body = &facts.body;
+    let (Some(location_table), Some(input_facts)) = (&facts.location_table, &facts.input_facts)
+    else {
+        unreachable!("requested with `ConsumerOptions::PoloniusInputFacts`");
+    };
+
+    let mut mir = Vec::new();
+    MirWriter::new(tcx)
+        .write_mir_fn(body, &mut mir)
+        .expect("writing to a `Vec` cannot fail");
+
+    let universal: Vec<RegionVid> = input_facts
+        .universal_region
+        .iter()
+        .map(|&region| region.into())
+        .collect();
+    let mut names = signature_region_names(tcx, def_id, body);
+    // The regions of the arguments are equal to the universal regions of the
+    // lifetimes they are written with.
+    let regioncx = &facts.region_inference_context;
+    for (vid, name) in names.clone() {
+        if let Some(&region) = universal
+            .iter()
+            .find(|&&region| region != vid && regioncx.eval_equal(region, vid))
+        {
+            names.entry(region).or_insert(name);
+        }
+    }
+    let mut regions: Vec<RegionVid> = universal.clone();
+    for local in body.local_decls.iter() {
+        regions.extend(
+            local
+                .ty
+                .walk()
+                .filter_map(|arg| match arg.as_region()?.kind() {
+                    ty::ReVar(vid) => Some(vid),
+                    _ => None,
+                }),
+        );
+    }
+    for &(sup, sub, _) in &input_facts.subset_base {
+        regions.extend([RegionVid::from(sup), RegionVid::from(sub)]);
+    }
+    regions.sort();
+    regions.dedup();
+
+    // Constraints that hold everywhere are repeated for every point.
+    let num_points = location_table.all_points().count();
+    let mut facts_of: BTreeMap<(RegionVid, RegionVid), (usize, BTreeSet<Point>)> = BTreeMap::new();
+    for &(sup, sub, point) in &input_facts.subset_base {
+        let (count, points) = facts_of.entry((sup.into(), sub.into())).or_default();
+        *count += 1;
+        points.insert(to_point(location_table.to_location(point)));
+    }
+    let constraints = facts_of
+        .into_iter()
+        .filter(|((sup, sub), _)| sup != sub)
+        .map(|((sup, sub), (count, points))| Constraint {
+            sup: format!("{sup:?}"),
+            sub: format!("{sub:?}"),
+            at: if count >= num_points {
+                Vec::new()
+            } else {
+                points.into_iter().collect()
+            },
+        })
+        .collect();
+
+    let out_of_scope =
+        calculate_borrows_out_of_scope_at_location(body, regioncx, &facts.borrow_set);
+    let borrows = facts
+        .borrow_set
+        .location_map()
+        .values()
+        .enumerate()
+        .map(|(i, borrow)| Borrow {
+            borrow: format!("bw{i}"),
+            kind: match borrow.kind() {
+                BorrowKind::Shared => "&",
+                BorrowKind::Mut { .. } => "&mut",
+                BorrowKind::Fake(_) => "fake",
+            }
+            .to_string(),
+            place: format!("{:?}", borrow.borrowed_place()),
+            region: format!("{:?}", borrow.region()),
+            reserved_at: to_point(borrow.reserve_location()),
+            live_at: live_points(body, &out_of_scope, BorrowIndex::from_usize(i), borrow),
+        })
+        .collect();
+
+    MirReport {
+        krate: tcx.crate_name(LOCAL_CRATE).to_string(),
+        function: function.to_string(),
+        mir: String::from_utf8_lossy(&mir).into_owned(),
+        regions: regions
+            .into_iter()
+            .map(|vid| RegionEntry {
+                region: format!("{vid:?}"),
+                universal: universal.contains(&vid),
+                name: names.get(&vid).cloned(),
+            })
+            .collect(),
+        constraints,
+        borrows,
+    }
+}
+
+/// Names the regions of the arguments and return type after the lifetimes
+/// of the signature in the same positions.
+fn signature_region_names<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: LocalDefId,
+    body: &mir::Body<'tcx>,
+) -> BTreeMap<RegionVid, String> {
+    let mut names = BTreeMap::from([(RegionVid::from_usize(0), "'static".to_string())]);
+    if !matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn) {
+        return names;
+    }
+    let sig = tcx.liberate_late_bound_regions(
+        def_id.to_def_id(),
+        tcx.fn_sig(def_id)
+            .instantiate_identity()
+            .skip_normalization(),
+    );
+    // The return place, then the arguments.
+    let sig_tys = std::iter::once(sig.output()).chain(sig.inputs().iter().copied());
+    for (local, sig_ty) in body.local_decls.iter().zip(sig_tys) {
+        let regions = |ty: Ty<'tcx>| -> Vec<ty::Region<'tcx>> {
+            ty.walk().filter_map(|arg| arg.as_region()).collect()
+        };
+        let (body_regions, sig_regions) = (regions(local.ty), regions(sig_ty));
+        if body_regions.len() != sig_regions.len() {
+            continue;
+        }
+        for (body_region, sig_region) in body_regions.into_iter().zip(sig_regions) {
+            if let (ty::ReVar(vid), Some(name)) = (body_region.kind(), sig_region.get_name(tcx)) {
+                names.entry(vid).or_insert_with(|| name.to_string());
+            }
+        }
+    }
+    names
+}
+
+fn to_point(location: mir::Location) -> Point {
+    Point {
+        block: location.block.as_usize(),
+        statement: location.statement_index,
+    }
+}
+
+/// Walks the CFG from where the borrow is reserved until its region no
+/// longer contains the borrow, as the `Borrows` dataflow analysis does.
+fn live_points(
+    body: &mir::Body<'_>,
+    out_of_scope: &FxIndexMap<mir::Location, Vec<BorrowIndex>>,
+    index: BorrowIndex,
+    borrow: &BorrowData<'_>,
+) -> Vec<Point> {
+    let mut live = BTreeSet::new();
+    let mut stack = vec![borrow.reserve_location()];
+    while let Some(location) = stack.pop() {
+        let killed = out_of_scope
+            .get(&location)
+            .is_some_and(|borrows| borrows.contains(&index));
+        if killed || !live.insert(to_point(location)) {
+            continue;
+        }
+        let block = &body.basic_blocks[location.block];
+        if location.statement_index < block.statements.len() {
+            stack.push(location.successor_within_block());
+        } else {
+            stack.extend(
+                block
+                    .terminator()
+                    .successors()
+                    .map(|successor| successor.start_location()),
+            );
+        }
+    }
+    live.into_iter().collect()
+}
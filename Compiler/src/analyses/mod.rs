@@ -0,0 +1,62 @@
+//! The analyses of the driver, one module each with the [`Callbacks`] that
+//! run it. `main` picks one by its subcommand.
+//!
+//! [`Callbacks`]: rustc_driver::Callbacks
+
+pub mod impls;
+pub mod items;
+pub mod lang_items;
+pub mod mir;
+pub mod mono;
+
+use std::io;
+
+use collect_report::Report;
+use rustc_interface::interface;
+use rustc_lint::Level;
+use rustc_middle::ty::TyCtxt;
+
+use crate::lints;
+
+#[derive(Clone, Copy)]
+pub enum Format {
+    Text,
+    Json,
+    /// Only for the region constraint graph of [`mir::Mir`].
+    Dot,
+}
+
+/// The options every subcommand takes.
+pub struct Options {
+    pub format: Format,
+    /// The levels of the lint configuration, as `-A`, `-W` and `-D` flags
+    /// would give them.
+    pub lint_levels: Vec<(String, Level)>,
+}
+
+impl Options {
+    /// Registers the driver's lints, at the levels of the lint
+    /// configuration.
+    fn config(&self, config: &mut interface::Config) {
+        config.register_lints = Some(Box::new(|_, store| lints::register(store)));
+        // Before the flags of the command line, so that those win.
+        config
+            .opts
+            .lint_opts
+            .splice(0..0, self.lint_levels.iter().cloned());
+    }
+
+    /// Prints `report` as text or JSON.
+    fn print(&self, tcx: TyCtxt<'_>, report: &impl Report) {
+        let stdout = io::stdout().lock();
+        let written = match self.format {
+            Format::Text => report.write_text(stdout),
+            Format::Json => report.write_json(stdout),
+            Format::Dot => unreachable!("only for `mir`"),
+        };
+        if let Err(err) = written {
+            tcx.dcx()
+                .fatal(format!("failed to write the report: {err}"));
+        }
+    }
+}
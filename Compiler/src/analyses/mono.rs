@@ -0,0 +1,203 @@
+//! `mono`: the [`MonoReport`] of the crate, what monomorphization
+//! instantiated and the vtables of its `dyn` coercions.
+
+use std::collections::BTreeMap;
+
+use collect_lang_items::mono::{MonoFunction, MonoInstance, MonoReport, VTable};
+use rustc_driver::Compilation;
+use rustc_interface::interface;
+use rustc_middle::mir::{CastKind, Rvalue, StatementKind};
+use rustc_middle::mono::MonoItem;
+use rustc_middle::ty::adjustment::PointerCoercion;
+use rustc_middle::ty::{self, Ty, TyCtxt, VtblEntry};
+use rustc_span::def_id::LOCAL_CRATE;
+
+use super::Options;
+
+pub struct Mono {
+    pub options: Options,
+}
+
+impl rustc_driver::Callbacks for Mono {
+    fn config(&mut self, config: &mut interface::Config) {
+        self.options.config(config);
+    }
+
+    fn after_analysis<'tcx>(
+        &mut self,
+        _compiler: &interface::Compiler,
+        tcx: TyCtxt<'tcx>,
+    ) -> Compilation {
+        self.options.print(tcx, &collect_mono_items(tcx));
+        Compilation::Stop
+    }
+}
+
+/// Every mono item of every codegen unit, grouped by the function it
+/// instantiates, and the vtables needed by the unsizing coercions in the
+/// instances of this crate's functions.
+fn collect_mono_items(tcx: TyCtxt<'_>) -> MonoReport {
+    let mut functions: BTreeMap<(bool, String), MonoFunction> = BTreeMap::new();
+    let mut instances = Vec::new();
+    for cgu in tcx.collect_and_partition_mono_items(()).codegen_units {
+        for (item, data) in cgu.items_in_deterministic_order(tcx) {
+            let MonoItem::Fn(instance) = item else {
+                continue;
+            };
+            let def_id = instance.def_id();
+            let def_path = tcx.def_path_str(def_id);
+            let function = functions
+                .entry((!def_id.is_local(), def_path.clone()))
+                .or_insert_with(|| MonoFunction {
+                    def_path,
+                    local: def_id.is_local(),
+                    generic: false,
+                    instances: Vec::new(),
+                });
+            function.generic |= instance.args.non_erasable_generics().next().is_some();
+            function.instances.push(MonoInstance {
+                instance: instance.to_string(),
+                size_estimate: data.size_estimate,
+                codegen_unit: cgu.name().to_string(),
+                inlined: data.inlined,
+            });
+            if def_id.is_local() {
+                instances.push(instance);
+            }
+        }
+    }
+
+    let mut vtables: Vec<VTable> = Vec::new();
+    instances.sort_by_key(|instance| instance.to_string());
+    instances.dedup();
+    for instance in instances {
+        for (ty, dyn_ty) in unsizing_coercions(tcx, instance) {
+            let (ty_name, dyn_name) = (ty.to_string(), dyn_ty.to_string());
+            let coerced_in = instance.to_string();
+            if let Some(vtable) = vtables
+                .iter_mut()
+                .find(|vtable| vtable.ty == ty_name && vtable.dyn_ty == dyn_name)
+            {
+                if !vtable.coerced_in.contains(&coerced_in) {
+                    vtable.coerced_in.push(coerced_in);
+                }
+                continue;
+            }
+            vtables.push(VTable {
+                entries: vtable_entries(tcx, ty, dyn_ty),
+                ty: ty_name,
+                dyn_ty: dyn_name,
+                coerced_in: vec![coerced_in],
+            });
+        }
+    }
+    vtables.sort_by(|a, b| (&a.ty, &a.dyn_ty).cmp(&(&b.ty, &b.dyn_ty)));
+
+    MonoReport {
+        krate: tcx.crate_name(LOCAL_CRATE).to_string(),
+        functions: functions.into_values().collect(),
+        vtables,
+    }
+}
+
+/// The `(concrete type, dyn type)` pairs the unsize casts in the MIR of
+/// `instance` coerce between, as the monomorphization collector finds them.
+fn unsizing_coercions<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    instance: ty::Instance<'tcx>,
+) -> Vec<(Ty<'tcx>, Ty<'tcx>)> {
+    let typing_env = ty::TypingEnv::fully_monomorphized();
+    let body = tcx.instance_mir(instance.def);
+    let mut coercions = Vec::new();
+    for block in body.basic_blocks.iter() {
+        for statement in &block.statements {
+            let StatementKind::Assign(assign) = &statement.kind else {
+                continue;
+            };
+            let Rvalue::Cast(
+                CastKind::PointerCoercion(PointerCoercion::Unsize, _),
+                operand,
+                target,
+            ) = &assign.1
+            else {
+                continue;
+            };
+            let source = instance.instantiate_mir_and_normalize_erasing_regions(
+                tcx,
+                typing_env,
+                ty::EarlyBinder::bind(operand.ty(&body.local_decls, tcx)),
+            );
+            let target = instance.instantiate_mir_and_normalize_erasing_regions(
+                tcx,
+                typing_env,
+                ty::EarlyBinder::bind(*target),
+            );
+            let Some((source, target)) = unsized_tails(tcx, source, target) else {
+                continue;
+            };
+            if !matches!(source.kind(), ty::Dynamic(..)) && matches!(target.kind(), ty::Dynamic(..))
+            {
+                coercions.push((source, target));
+            }
+        }
+    }
+    coercions
+}
+
+/// The pointees of an unsizing coercion, through references, raw pointers,
+/// boxes and the first field of a smart pointer whose type changes.
+fn unsized_tails<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    source: Ty<'tcx>,
+    target: Ty<'tcx>,
+) -> Option<(Ty<'tcx>, Ty<'tcx>)> {
+    let typing_env = ty::TypingEnv::fully_monomorphized();
+    if let (Some(source), Some(target)) = (source.boxed_ty(), target.boxed_ty()) {
+        return Some(tcx.struct_lockstep_tails_for_codegen(source, target, typing_env));
+    }
+    match (source.kind(), target.kind()) {
+        // `NonNull<T>` holds a `*const T is !null`.
+        (&ty::Pat(source, _), &ty::Pat(target, _)) => unsized_tails(tcx, source, target),
+        (
+            &ty::Ref(_, source, _) | &ty::RawPtr(source, _),
+            &ty::Ref(_, target, _) | &ty::RawPtr(target, _),
+        ) => Some(tcx.struct_lockstep_tails_for_codegen(source, target, typing_env)),
+        (&ty::Adt(adt, source_args), &ty::Adt(_, target_args)) if adt.is_struct() => adt
+            .non_enum_variant()
+            .fields
+            .iter()
+            .map(|field| {
+                (
+                    tcx.normalize_erasing_regions(typing_env, field.ty(tcx, source_args)),
+                    tcx.normalize_erasing_regions(typing_env, field.ty(tcx, target_args)),
+                )
+            })
+            .find(|(source, target)| source != target)
+            .and_then(|(source, target)| unsized_tails(tcx, source, target)),
+        _ => None,
+    }
+}
+
+fn vtable_entries<'tcx>(tcx: TyCtxt<'tcx>, ty: Ty<'tcx>, dyn_ty: Ty<'tcx>) -> Vec<String> {
+    let ty::Dynamic(predicates, ..) = dyn_ty.kind() else {
+        return Vec::new();
+    };
+    // `dyn Send` and other trait objects of auto traits only have the header.
+    let Some(principal) = predicates.principal() else {
+        return ["drop_in_place", "size", "align"]
+            .map(str::to_string)
+            .to_vec();
+    };
+    let trait_ref = tcx.instantiate_bound_regions_with_erased(principal.with_self_ty(tcx, ty));
+    tcx.vtable_entries(trait_ref)
+        .iter()
+        .map(|entry| match entry {
+            VtblEntry::MetadataDropInPlace => "drop_in_place".to_string(),
+            VtblEntry::MetadataSize => "size".to_string(),
+            VtblEntry::MetadataAlign => "align".to_string(),
+            VtblEntry::Vacant => "vacant".to_string(),
+            VtblEntry::Method(instance) => instance.to_string(),
+            VtblEntry::TraitVPtr(trait_ref) => format!("vtable of {trait_ref}"),
+        })
+        .collect()
+}
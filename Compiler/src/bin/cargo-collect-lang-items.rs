@@ -0,0 +1,221 @@
+//! `cargo collect-lang-items`: runs `cargo check` with the
+//! `collect-lang-items` driver as `RUSTC_WORKSPACE_WRAPPER`, so that only the
+//! crates of the workspace are analysed while their dependencies go straight
+//! to rustc, then prints the reports of every target of the packages cargo
+//! selected together.
+//!
+//! ```text
+//! cargo collect-lang-items [--format text|json] [--manifest-path PATH] [CHECK ARGS]...
+//! ```
+
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, ExitCode};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use collect_lang_items::report::LangItemReport;
+use collect_lang_items::OUT_DIR_ENV;
+use collect_report::Report;
+use serde::Serialize;
+
+/// The reports of every target of the selected packages, sorted by package
+/// and target.
+#[derive(Serialize)]
+struct WorkspaceReport<'a> {
+    crates: &'a [LangItemReport],
+}
+
+impl Report for WorkspaceReport<'_> {
+    /// The report of each crate under a `# crate (crate types) package
+    /// target` line, with `test` after the crate types of a test harness.
+    fn write_text(&self, mut out: impl Write) -> io::Result<()> {
+        self.crates.iter().try_for_each(|report| {
+            let test = if report.test { ", test" } else { "" };
+            writeln!(
+                out,
+                "# {} ({}{test}) {} {}",
+                report.krate,
+                report.crate_types.join(", "),
+                report.package.as_deref().unwrap_or_default(),
+                report.target.as_deref().unwrap_or_default()
+            )?;
+            report.write_text(&mut out)
+        })
+    }
+}
+
+struct Args {
+    json: bool,
+    manifest_path: Option<String>,
+    /// Passed on to `cargo check`.
+    check_args: Vec<String>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = env::args().skip(1).peekable();
+    // `cargo collect-lang-items` runs `cargo-collect-lang-items collect-lang-items`.
+    if args.peek().is_some_and(|arg| arg == "collect-lang-items") {
+        args.next();
+    }
+
+    let mut parsed = Args {
+        json: false,
+        manifest_path: None,
+        check_args: Vec::new(),
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => match args.next().as_deref() {
+                Some("json") => parsed.json = true,
+                Some("text") => parsed.json = false,
+                _ => return Err("`--format` expects `text` or `json`".to_string()),
+            },
+            "--manifest-path" => {
+                parsed.manifest_path = Some(args.next().ok_or("`--manifest-path` needs a path")?);
+            }
+            _ => parsed.check_args.push(arg),
+        }
+    }
+    Ok(parsed)
+}
+
+fn cargo() -> Command {
+    Command::new(env::var_os("CARGO").unwrap_or_else(|| OsString::from("cargo")))
+}
+
+/// What `cargo metadata` says of the workspace.
+struct Workspace {
+    target_directory: PathBuf,
+    /// The packages of the workspace, as `name@version`.
+    members: Vec<String>,
+}
+
+fn workspace(args: &Args) -> Result<Workspace, String> {
+    let mut metadata = cargo();
+    metadata.args(["metadata", "--format-version", "1", "--no-deps"]);
+    if let Some(path) = &args.manifest_path {
+        metadata.args(["--manifest-path", path]);
+    }
+    let output = metadata
+        .output()
+        .map_err(|err| format!("failed to run `cargo metadata`: {err}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+    let metadata: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|err| format!("invalid `cargo metadata` output: {err}"))?;
+    let target_directory = metadata["target_directory"]
+        .as_str()
+        .map(PathBuf::from)
+        .ok_or("`cargo metadata` did not report a target directory")?;
+    // Without `--no-deps`, `packages` would also list the dependencies.
+    let members = metadata["packages"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|package| {
+            Some(format!(
+                "{}@{}",
+                package["name"].as_str()?,
+                package["version"].as_str()?
+            ))
+        })
+        .collect();
+    Ok(Workspace {
+        target_directory,
+        members,
+    })
+}
+
+/// The reports in `dir` of the packages in `members`, sorted.
+fn read_reports(dir: &Path, members: &[String]) -> Result<Vec<LangItemReport>, String> {
+    let mut reports = Vec::new();
+    // Nothing is written when no package is selected.
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(reports);
+    };
+    for entry in entries {
+        let path = entry.map_err(|err| err.to_string())?.path();
+        let file = fs::read(&path).map_err(|err| format!("{}: {err}", path.display()))?;
+        let report: LangItemReport =
+            serde_json::from_slice(&file).map_err(|err| format!("{}: {err}", path.display()))?;
+        if report
+            .package
+            .as_ref()
+            .is_some_and(|package| members.contains(package))
+        {
+            reports.push(report);
+        }
+    }
+    reports.sort_by(|a, b| (&a.package, &a.target, a.test).cmp(&(&b.package, &b.target, b.test)));
+    Ok(reports)
+}
+
+fn run() -> Result<bool, String> {
+    let args = parse_args()?;
+    let driver = env::current_exe()
+        .map_err(|err| format!("failed to find the driver: {err}"))?
+        .with_file_name(format!("collect-lang-items{}", env::consts::EXE_SUFFIX));
+    let workspace = workspace(&args)?;
+    // A directory per run, so that the reports of earlier runs, of packages
+    // since removed or not selected this time, are not read again. The
+    // driver has cargo check each crate again when it changes.
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_nanos());
+    let out_dir = workspace
+        .target_directory
+        .join("collect-lang-items")
+        .join(format!("{}-{nanos}", process::id()));
+
+    let mut check = cargo();
+    check
+        .arg("check")
+        .args(&args.check_args)
+        .env("RUSTC_WORKSPACE_WRAPPER", &driver)
+        // The driver can only read metadata written by its own rustc.
+        .env(
+            "RUSTC",
+            PathBuf::from(env!("COLLECT_LANG_ITEMS_SYSROOT")).join("bin/rustc"),
+        )
+        .env(OUT_DIR_ENV, &out_dir);
+    if let Some(path) = &args.manifest_path {
+        check.args(["--manifest-path", path]);
+    }
+    let status = check
+        .status()
+        .map_err(|err| format!("failed to run `cargo check`: {err}"));
+    let reports = match status {
+        Ok(status) if status.success() => read_reports(&out_dir, &workspace.members).map(Some),
+        Ok(_) => Ok(None),
+        Err(err) => Err(err),
+    };
+    let _ = fs::remove_dir_all(&out_dir);
+    let Some(reports) = reports? else {
+        return Ok(false);
+    };
+
+    let report = WorkspaceReport { crates: &reports };
+    let stdout = io::stdout().lock();
+    let written = if args.json {
+        report.write_json(stdout)
+    } else {
+        report.write_text(stdout)
+    };
+    written.map_err(|err| err.to_string())?;
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use std::io::{self, Write};
+
+use collect_report::Report;
+use serde::{Deserialize, Serialize};
+
+/// Every impl of a trait the crate can see, and optionally which of them
+/// applies to a type.
+#[derive(Serialize, Deserialize)]
+pub struct ImplReport {
+    #[serde(rename = "crate")]
+    pub krate: String,
+    #[serde(rename = "trait")]
+    pub trait_path: String,
+    pub impls: Vec<ImplEntry>,
+    pub explanation: Option<Explanation>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ImplEntry {
+    /// `impl<T> Trait for T`, without the where clauses.
+    pub header: String,
+    /// Every where clause, including the bounds written on the parameters
+    /// and the implicit `Sized` ones, but not the `MetaSized` of `?Sized`.
+    pub where_clauses: Vec<String>,
+    pub self_ty: String,
+    /// Whether the self type is a bare type parameter, as in
+    /// `impl<T: Printable> DefaultPrintable for T`.
+    pub blanket: bool,
+    pub defining_crate: String,
+    pub span: String,
+}
+
+/// Which impls apply to a type, as decided by the trait solver.
+#[derive(Serialize, Deserialize)]
+pub struct Explanation {
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// One per entry of [`ImplReport::impls`], in the same order.
+    pub candidates: Vec<Candidate>,
+    /// Whether the type implements the trait at all, which it can also do
+    /// through an impl the compiler provides, e.g. `Sized` or auto traits.
+    pub implemented: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Candidate {
+    pub applies: bool,
+    /// Whether the self type of the impl unifies with the type, so that
+    /// only the where clauses can rule it out.
+    pub self_ty_matches: bool,
+    /// Why the impl does not apply: its self type does not match, or one of
+    /// its where clauses does not hold.
+    pub reason: Option<String>,
+}
+
+impl Report for ImplReport {
+    /// The impls, then the verdict for the type if one was given:
+    ///
+    /// ```text
+    /// impl<T> DefaultPrintable for T
+    ///     where T: Printable
+    ///     at src/main.rs:42:1: 42:51
+    ///
+    /// `&str` implements `DefaultPrintable`:
+    ///     applies: impl<T> DefaultPrintable for T
+    /// ```
+    ///
+    /// Impls for other types are only counted, as there are hundreds of them
+    /// for traits like `Clone`.
+    fn write_text(&self, mut out: impl Write) -> io::Result<()> {
+        for (i, imp) in self.impls.iter().enumerate() {
+            if i > 0 {
+                writeln!(out)?;
+            }
+            writeln!(out, "{}", imp.header)?;
+            for clause in &imp.where_clauses {
+                writeln!(out, "    where {clause}")?;
+            }
+            writeln!(out, "    at {}", imp.span)?;
+        }
+        if self.impls.is_empty() {
+            writeln!(out, "no impls of `{}`", self.trait_path)?;
+        }
+
+        let Some(explanation) = &self.explanation else {
+            return Ok(());
+        };
+        writeln!(out)?;
+        let verdict = if explanation.implemented {
+            "implements"
+        } else {
+            "does not implement"
+        };
+        writeln!(out, "`{}` {verdict} `{}`:", explanation.ty, self.trait_path)?;
+        let mut other_types = 0;
+        for (imp, candidate) in self.impls.iter().zip(&explanation.candidates) {
+            match &candidate.reason {
+                None => writeln!(out, "    applies: {}", imp.header)?,
+                Some(reason) if candidate.self_ty_matches => {
+                    writeln!(out, "    rejected: {}: {reason}", imp.header)?
+                }
+                Some(_) => other_types += 1,
+            }
+        }
+        if other_types > 0 {
+            writeln!(out, "    {other_types} more impls are for other types")?;
+        }
+        if explanation.implemented && !explanation.candidates.iter().any(|c| c.applies) {
+            writeln!(out, "    through an impl provided by the compiler")?;
+        }
+        Ok(())
+    }
+}
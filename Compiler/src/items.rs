@@ -0,0 +1,82 @@
+use std::io::{self, Write};
+
+use collect_report::Report;
+use serde::{Deserialize, Serialize};
+
+/// The items of a crate as the HIR sees them, in source order.
+#[derive(Serialize, Deserialize)]
+pub struct ItemInventory {
+    #[serde(rename = "crate")]
+    pub krate: String,
+    pub items: Vec<ItemEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ItemEntry {
+    /// `fn`, `struct`, `enum`, `union`, `trait`, `impl`, ...
+    pub kind: String,
+    /// `None` for impls, which have no name.
+    pub name: Option<String>,
+    pub def_path: String,
+    /// As written by rustc, e.g. `pub(crate)` or `pub(self)`. `None` for
+    /// impls, which have none of their own.
+    pub visibility: Option<String>,
+    pub span: String,
+    /// The implemented trait, for trait impls.
+    pub trait_ref: Option<String>,
+    /// The implementing type, for impls.
+    pub self_ty: Option<String>,
+}
+
+impl ItemEntry {
+    /// How the item reads in a table: its path, or the header of an impl.
+    pub fn title(&self) -> String {
+        match (&self.trait_ref, &self.self_ty) {
+            (Some(trait_ref), Some(self_ty)) => format!("impl {trait_ref} for {self_ty}"),
+            (None, Some(self_ty)) => format!("impl {self_ty}"),
+            _ => self.def_path.clone(),
+        }
+    }
+}
+
+impl Report for ItemInventory {
+    /// One item per row, under a `KIND VISIBILITY ITEM SPAN` header.
+    fn write_text(&self, mut out: impl Write) -> io::Result<()> {
+        let header = ["KIND", "VISIBILITY", "ITEM", "SPAN"];
+        let rows: Vec<[String; 4]> = self
+            .items
+            .iter()
+            .map(|item| {
+                [
+                    item.kind.clone(),
+                    item.visibility.clone().unwrap_or_else(|| "-".to_string()),
+                    item.title(),
+                    item.span.clone(),
+                ]
+            })
+            .collect();
+
+        let mut widths = header.map(str::len);
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        let header = header.map(str::to_string);
+        for row in std::iter::once(&header).chain(&rows) {
+            // The last column is not padded, so lines have no trailing spaces.
+            writeln!(
+                out,
+                "{:w0$}  {:w1$}  {:w2$}  {}",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+            )?;
+        }
+        Ok(())
+    }
+}
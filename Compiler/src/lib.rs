@@ -0,0 +1,14 @@
+//! The reports of the `collect-lang-items` driver, shared with the
+//! `cargo collect-lang-items` subcommand that aggregates the reports of a
+//! whole workspace.
+
+pub mod impls;
+pub mod items;
+pub mod lint_config;
+pub mod mir;
+pub mod mono;
+pub mod report;
+
+/// Where the driver writes one report per target of the packages cargo
+/// selected, when run by cargo.
+pub const OUT_DIR_ENV: &str = "COLLECT_LANG_ITEMS_OUT_DIR";
@@ -0,0 +1,52 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// The levels of the driver's lints, read from a TOML file:
+///
+/// ```toml
+/// [lints]
+/// impls_for_str_ref = "deny"
+/// ```
+///
+/// Lints not named keep their default level. Like `-A`, `-W` and `-D`, the
+/// levels can still be changed by `#[allow]` and friends in the crate.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintConfig {
+    #[serde(default)]
+    pub lints: BTreeMap<String, LintLevel>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl LintConfig {
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let contents =
+            fs::read_to_string(path).map_err(|err| format!("{}: {err}", path.display()))?;
+        toml::from_str(&contents).map_err(|err| format!("{}: {err}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_are_lowercase() {
+        let config: LintConfig =
+            toml::from_str("[lints]\nimpls_for_str_ref = \"deny\"\nother = \"allow\"\n").unwrap();
+        assert_eq!(config.lints["impls_for_str_ref"], LintLevel::Deny);
+        assert_eq!(config.lints["other"], LintLevel::Allow);
+
+        assert!(toml::from_str::<LintConfig>("[lints]\nimpls_for_str_ref = \"forbid\"\n").is_err());
+    }
+}
@@ -0,0 +1,125 @@
+use rustc_errors::{Applicability, DiagDecorator};
+use rustc_hir::def::Res;
+use rustc_hir::{Item, ItemKind, LangItem, MutTy, Mutability, PrimTy, QPath, TyKind};
+use rustc_lint::{LateContext, LateLintPass, LintContext};
+use rustc_middle::ty::{self, TyCtxt};
+use rustc_session::{declare_lint, declare_lint_pass};
+use rustc_span::def_id::DefId;
+
+declare_lint! {
+    /// Flags `impl Trait for &str`, as in `rust_advanced_concepts/1.rs`:
+    ///
+    /// ```rust,ignore
+    /// impl Printable for &str {
+    ///     fn print_info(&self) {
+    ///         println!("String: {}", *self);
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// The `&self` methods of the impl take a `&&str`, and `str` itself, or a
+    /// `Box<str>`, does not implement the trait. Implemented for `str`, the
+    /// trait is found for `&str` receivers all the same, by auto-deref.
+    pub IMPLS_FOR_STR_REF,
+    Warn,
+    "traits implemented for `&str` rather than for `str`"
+}
+
+declare_lint_pass!(ImplsForStrRef => [IMPLS_FOR_STR_REF]);
+
+impl<'tcx> LateLintPass<'tcx> for ImplsForStrRef {
+    fn check_item(&mut self, cx: &LateContext<'tcx>, item: &'tcx Item<'tcx>) {
+        let ItemKind::Impl(imp) = item.kind else {
+            return;
+        };
+        let Some(of_trait) = imp.of_trait else {
+            return;
+        };
+        let TyKind::Ref(
+            _,
+            MutTy {
+                ty: referent,
+                mutbl: Mutability::Not,
+            },
+        ) = imp.self_ty.kind
+        else {
+            return;
+        };
+        let TyKind::Path(QPath::Resolved(None, path)) = referent.kind else {
+            return;
+        };
+        if path.res != Res::PrimTy(PrimTy::Str) || imp.self_ty.span.from_expansion() {
+            return;
+        }
+        let Some(trait_id) = of_trait.trait_ref.trait_def_id() else {
+            return;
+        };
+
+        let tcx = cx.tcx;
+        let trait_name = tcx.item_name(trait_id);
+        let span = imp.self_ty.span;
+        cx.emit_span_lint(
+            IMPLS_FOR_STR_REF,
+            span,
+            DiagDecorator(|diag| {
+                diag.primary_message(format!("`{trait_name}` is implemented for `&str`"));
+                if has_ref_self_method(tcx, trait_id) {
+                    diag.note(
+                        "its `&self` methods take a `&&str`, and `str` does not implement it",
+                    );
+                }
+                if let Some(reason) = needs_sized(tcx, trait_id) {
+                    diag.note(format!("it cannot be implemented for `str`, as {reason}"));
+                } else {
+                    diag.span_suggestion(
+                        span,
+                        "implement it for `str`, which method calls on `&str` find by auto-deref",
+                        "str",
+                        // Generic code bounded by the trait no longer accepts `&str`.
+                        Applicability::MaybeIncorrect,
+                    );
+                }
+            }),
+        );
+    }
+}
+
+/// Whether a method of the trait takes `&self` or `&mut self`.
+fn has_ref_self_method(tcx: TyCtxt<'_>, trait_id: DefId) -> bool {
+    tcx.associated_items(trait_id)
+        .in_definition_order()
+        .any(|item| {
+            item.is_method()
+                && matches!(
+                    tcx.fn_sig(item.def_id).skip_binder().inputs().skip_binder()[0].kind(),
+                    ty::Ref(_, referent, _) if referent.is_param(0)
+                )
+        })
+}
+
+/// Why the trait cannot be implemented for an unsized type, if it cannot.
+fn needs_sized(tcx: TyCtxt<'_>, trait_id: DefId) -> Option<String> {
+    let sized_supertrait = tcx
+        .predicates_of(trait_id)
+        .predicates
+        .iter()
+        .any(|(clause, _)| {
+            clause.as_trait_clause().is_some_and(|clause| {
+                tcx.is_lang_item(clause.def_id(), LangItem::Sized)
+                    && clause.self_ty().skip_binder().is_param(0)
+            })
+        });
+    if sized_supertrait {
+        return Some("it requires `Sized`".to_string());
+    }
+    tcx.associated_items(trait_id)
+        .in_definition_order()
+        .find(|item| {
+            item.is_method()
+                && matches!(
+                    tcx.fn_sig(item.def_id).skip_binder().inputs().skip_binder()[0].kind(),
+                    ty::Param(param) if param.index == 0
+                )
+        })
+        .map(|item| format!("`{}` takes `self` by value", item.name()))
+}
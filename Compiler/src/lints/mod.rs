@@ -0,0 +1,25 @@
+//! The late lint passes the driver adds to rustc's, one module per lint.
+//!
+//! A new lint declares its `Lint` and pass in its own module, then its pass
+//! is added to [`lints`] and to [`register`]. Its level can then be set in the
+//! [`LintConfig`](collect_lang_items::lint_config::LintConfig) under its lower
+//! case name, like the level of any other lint with `-A`, `-W` or `-D`.
+
+mod impls_for_str_ref;
+
+use rustc_lint::{LintStore, LintVec};
+
+use self::impls_for_str_ref::ImplsForStrRef;
+
+/// Every lint of the driver.
+///
+/// Taken from the passes rather than kept in a `static`, as rustc tells lints
+/// apart by their address, which a copy in another `static` does not keep.
+pub fn lints() -> LintVec {
+    ImplsForStrRef::lint_vec()
+}
+
+pub fn register(store: &mut LintStore) {
+    store.register_lints(&lints());
+    store.register_late_pass(|_| Box::new(ImplsForStrRef));
+}
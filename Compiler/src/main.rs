@@ -1,43 +1,247 @@
+//! `collect-lang-items`: a rustc driver printing what rustc knows about a
+//! crate once it has analysed it. A subcommand picks the analysis, and the
+//! arguments after its options are those of rustc:
+//!
+//! ```text
+//! collect-lang-items lang-items --edition 2021 --crate-type lib src/lib.rs
+//! collect-lang-items impls --trait Display --type '&str' --format json src/main.rs
+//! ```
+//!
+//! - `lang-items`: a line per lang item the crate can see, or a
+//!   [`LangItemReport`](collect_lang_items::report::LangItemReport).
+//! - `items`: the [`ItemInventory`](collect_lang_items::items::ItemInventory)
+//!   of the crate, as a table.
+//! - `impls --trait PATH [--type TYPE]`: the
+//!   [`ImplReport`](collect_lang_items::impls::ImplReport) of the trait,
+//!   explaining which impl applies to the type when one is given.
+//! - `mono`: the [`MonoReport`](collect_lang_items::mono::MonoReport) of the
+//!   crate: what monomorphization instantiated, and the vtables of its `dyn`
+//!   coercions.
+//! - `mir --fn PATH`: the [`MirReport`](collect_lang_items::mir::MirReport)
+//!   of the function, or with `--format dot` its region constraint graph in
+//!   Graphviz.
+//!
+//! Each prints text, or JSON with `--format json`. In every analysis but
+//! `mir`, the crate is also checked by the lints of the [`lints`] module,
+//! whose levels can be set in the TOML file given to `--lints`.
+//!
+//! It can also stand in for rustc as cargo's `RUSTC_WORKSPACE_WRAPPER`, which
+//! is what `cargo collect-lang-items` does: cargo then passes the path of
+//! rustc as the first argument, and the driver writes the lang item report of
+//! each workspace crate to `COLLECT_LANG_ITEMS_OUT_DIR` instead of printing
+//! it.
+
 #![feature(rustc_private)]
 
-extern crate rustc_ast;
+extern crate rustc_borrowck;
+extern crate rustc_data_structures;
 extern crate rustc_driver;
+extern crate rustc_errors;
 extern crate rustc_hir;
+extern crate rustc_index;
+extern crate rustc_infer;
 extern crate rustc_interface;
+extern crate rustc_lint;
+extern crate rustc_middle;
 extern crate rustc_session;
+extern crate rustc_span;
+extern crate rustc_trait_selection;
+
+mod analyses;
+mod lints;
+
+use std::path::Path;
+use std::process::{Command, ExitCode};
+
+use collect_lang_items::lint_config::{LintConfig, LintLevel};
+use collect_lang_items::OUT_DIR_ENV;
+use rustc_driver::Callbacks;
+use rustc_lint::Level;
+
+use analyses::impls::Impls;
+use analyses::items::Items;
+use analyses::lang_items::LangItems;
+use analyses::mir::Mir;
+use analyses::mono::Mono;
+use analyses::{Format, Options};
 
-use rustc_ast::ast;
-use rustc_driver::Compilation;
-use rustc_hir::LangItem;
-use rustc_interface::interface;
-use rustc_session::config::Options;
+const USAGE: &str = "\
+usage: collect-lang-items SUBCOMMAND [OPTIONS] [--] RUSTC_ARGS...
 
-fn main() {
-    let mut rustc_args = std::env::args().collect::<Vec<_>>();
-    rustc_args.insert(1, "collect_lang_item".to_string());
+subcommands:
+    lang-items                         the lang items the crate can see
+    items                              the items of the crate
+    impls --trait PATH [--type TYPE]   the impls of a trait, and which applies to a type
+    mono                               what monomorphization instantiated
+    mir --fn PATH                      the MIR and regions of a function
+
+options:
+    --format text|json                 what to print, or `dot` for `mir`
+    --lints FILE                       the levels of the driver's lints, in TOML";
+
+/// The analysis the subcommand `args[1]` picks, with its options, and the
+/// arguments left for rustc.
+fn parse_args(args: &[String]) -> Result<(Box<dyn Callbacks + Send>, Vec<String>), String> {
+    let subcommand = &args[1];
+    let mut format = Format::Text;
+    let mut lints = None;
+    let mut trait_path = None;
+    let mut ty = None;
+    let mut function = None;
+    let mut rest = args[2..].iter();
+    let mut rustc_args = vec![args[0].clone()];
+    while let Some(arg) = rest.next() {
+        let mut value = || {
+            rest.next()
+                .cloned()
+                .ok_or_else(|| format!("`{arg}` needs a value"))
+        };
+        match arg.as_str() {
+            "--format" => {
+                format = match value()?.as_str() {
+                    "text" => Format::Text,
+                    "json" => Format::Json,
+                    "dot" if subcommand == "mir" => Format::Dot,
+                    other => {
+                        return Err(format!(
+                        "unknown format `{other}`, expected `text` or `json`, or `dot` for `mir`"
+                    ))
+                    }
+                }
+            }
+            "--lints" => lints = Some(value()?),
+            "--trait" if subcommand == "impls" => trait_path = Some(value()?),
+            "--type" if subcommand == "impls" => ty = Some(value()?),
+            "--fn" if subcommand == "mir" => function = Some(value()?),
+            "--" => {
+                rustc_args.extend(rest.cloned());
+                break;
+            }
+            // The first argument that is not an option of the subcommand
+            // starts those of rustc.
+            _ => {
+                rustc_args.push(arg.clone());
+                rustc_args.extend(rest.cloned());
+                break;
+            }
+        }
+    }
 
     let options = Options {
-        crate_types: vec![rustc_session::config::CrateType::Rlib],
-        output_dir: None,
-        ..Options::default()
+        format,
+        lint_levels: lint_levels(lints.as_deref().map(Path::new))?,
+    };
+    let callbacks: Box<dyn Callbacks + Send> = match subcommand.as_str() {
+        "lang-items" => Box::new(LangItems {
+            options,
+            out_dir: None,
+        }),
+        "items" => Box::new(Items { options }),
+        "impls" => Box::new(Impls {
+            options,
+            trait_path: trait_path.ok_or("`impls` needs the path of a trait in `--trait`")?,
+            ty,
+        }),
+        "mono" => Box::new(Mono { options }),
+        "mir" => Box::new(Mir {
+            options,
+            function: function.ok_or("`mir` needs the path of a function in `--fn`")?,
+        }),
+        other => {
+            return Err(format!(
+                "unknown subcommand `{other}`, expected `lang-items`, `items`, `impls`, `mono` or `mir`"
+            ))
+        }
     };
+    Ok((callbacks, rustc_args))
+}
+
+/// Whether cargo runs rustc only to learn about it, e.g. `rustc -vV`, or to
+/// build something other than a crate of the workspace.
+fn is_passthrough(args: &[String]) -> bool {
+    let crate_name = args
+        .iter()
+        .position(|arg| arg == "--crate-name")
+        .and_then(|i| args.get(i + 1));
+    args.iter()
+        .any(|arg| arg == "-vV" || arg == "-V" || arg.starts_with("--print"))
+        || crate_name.is_some_and(|name| name == "build_script_build")
+}
 
-    let mut callbacks = rustc_driver::Callbacks::new();
-    callbacks.after_analysis = Some(Box::new(|state| {
-        let krate = state.hir_crate.unwrap();
-        let lang_items = krate.session.lang_items();
-        
-        if let Some(lang_item) = lang_items.require(LangItem::Option) {
-            println!("Found LangItem::Option at {:?}", lang_item);
-        } else {
-            println!("LangItem::Option not found");
+/// The levels of the lint configuration at `path`, if any.
+fn lint_levels(path: Option<&Path>) -> Result<Vec<(String, Level)>, String> {
+    let Some(path) = path else {
+        return Ok(Vec::new());
+    };
+    let config = LintConfig::read(path)?;
+    let known: Vec<String> = lints::lints()
+        .iter()
+        .map(|lint| lint.name_lower())
+        .collect();
+    config
+        .lints
+        .into_iter()
+        .map(|(name, level)| {
+            if !known.contains(&name) {
+                return Err(format!(
+                    "unknown lint `{name}` in {}, expected one of {}",
+                    path.display(),
+                    known.join(", ")
+                ));
+            }
+            let level = match level {
+                LintLevel::Allow => Level::Allow,
+                LintLevel::Warn => Level::Warn,
+                LintLevel::Deny => Level::Deny,
+            };
+            Ok((name, level))
+        })
+        .collect()
+}
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().collect();
+    rustc_driver::install_ice_hook(rustc_driver::DEFAULT_BUG_REPORT_URL, |_| ());
+
+    // As `RUSTC_WORKSPACE_WRAPPER`, the first argument is rustc itself.
+    let is_wrapper = args.get(1).is_some_and(|arg| {
+        Path::new(arg)
+            .file_stem()
+            .is_some_and(|stem| stem == "rustc")
+    });
+    let (mut callbacks, rustc_args): (Box<dyn Callbacks + Send>, _) = if is_wrapper {
+        let rustc = args.remove(1);
+        if is_passthrough(&args[1..]) {
+            return match Command::new(rustc).args(&args[1..]).status() {
+                Ok(status) if status.success() => ExitCode::SUCCESS,
+                Ok(_) => ExitCode::FAILURE,
+                Err(err) => {
+                    eprintln!("error: failed to run rustc: {err}");
+                    ExitCode::FAILURE
+                }
+            };
         }
-    }));
-
-    rustc_driver::run_compiler_with_args(
-        &rustc_args,
-        &options,
-        &mut callbacks,
-        None,
-    ).unwrap();
+        let lang_items = LangItems {
+            options: Options {
+                format: Format::Text,
+                lint_levels: Vec::new(),
+            },
+            out_dir: std::env::var_os(OUT_DIR_ENV).map(Into::into),
+        };
+        (Box::new(lang_items), args)
+    } else if args.len() < 2 {
+        eprintln!("{USAGE}");
+        return ExitCode::FAILURE;
+    } else {
+        match parse_args(&args) {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                eprintln!("error: {err}");
+                return ExitCode::FAILURE;
+            }
+        }
+    };
+    rustc_driver::catch_with_exit_code(|| {
+        rustc_driver::run_compiler(&rustc_args, callbacks.as_mut())
+    })
 }
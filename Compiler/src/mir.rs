@@ -0,0 +1,212 @@
+use std::fmt;
+use std::io::{self, Write};
+
+use collect_report::Report;
+use serde::{Deserialize, Serialize};
+
+/// The MIR of a function as the borrow checker sees it, with the regions it
+/// inferred: which must outlive which, and where each borrow is live.
+#[derive(Serialize, Deserialize)]
+pub struct MirReport {
+    #[serde(rename = "crate")]
+    pub krate: String,
+    pub function: String,
+    /// As `-Zunpretty=mir` prints it, with region variables like `'?3`.
+    pub mir: String,
+    pub regions: Vec<RegionEntry>,
+    pub constraints: Vec<Constraint>,
+    pub borrows: Vec<Borrow>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct RegionEntry {
+    /// The region variable, e.g. `'?3`.
+    pub region: String,
+    /// Whether the region comes from outside the body, like `'static` or a
+    /// lifetime parameter, rather than being inferred in it.
+    pub universal: bool,
+    /// The lifetime the region stands for, when it is named in the
+    /// signature, e.g. `'a`.
+    pub name: Option<String>,
+}
+
+/// `sup: sub`, the constraint graph having an edge from `sup` to `sub`.
+#[derive(Serialize, Deserialize)]
+pub struct Constraint {
+    pub sup: String,
+    pub sub: String,
+    /// Where the constraint arises, empty when it holds everywhere, as for
+    /// those of the signature.
+    pub at: Vec<Point>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Borrow {
+    /// `bw0`, `bw1`, ..., in the order of the borrow set.
+    pub borrow: String,
+    /// `&`, `&mut`, or `fake` for the borrows of match guards.
+    pub kind: String,
+    pub place: String,
+    pub region: String,
+    pub reserved_at: Point,
+    /// Every statement, in order, at which the region of the borrow still
+    /// contains the borrow.
+    pub live_at: Vec<Point>,
+}
+
+/// A MIR location, the statement `statement` of the basic block `block`, the
+/// terminator being the statement after the last one.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Point {
+    pub block: usize,
+    pub statement: usize,
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "bb{}[{}]", self.block, self.statement)
+    }
+}
+
+/// `bb0[1..=3], bb2[0]`: the points of each block, runs of consecutive
+/// statements collapsed.
+fn point_ranges(points: &[Point]) -> String {
+    let mut ranges: Vec<(Point, Point)> = Vec::new();
+    for &point in points {
+        match ranges.last_mut() {
+            Some((_, end)) if end.block == point.block && end.statement + 1 == point.statement => {
+                *end = point;
+            }
+            _ => ranges.push((point, point)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("bb{}[{}..={}]", start.block, start.statement, end.statement)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl Report for MirReport {
+    /// The MIR, then the regions, the constraints between them and the
+    /// borrows.
+    fn write_text(&self, mut out: impl Write) -> io::Result<()> {
+        write!(out, "{}", self.mir)?;
+        writeln!(out)?;
+        writeln!(out, "regions:")?;
+        for region in &self.regions {
+            let mut line = format!("    {}", region.region);
+            if region.universal {
+                line.push_str(" universal");
+            }
+            if let Some(name) = &region.name {
+                line.push_str(&format!(" {name}"));
+            }
+            writeln!(out, "{line}")?;
+        }
+        writeln!(out, "constraints:")?;
+        for constraint in &self.constraints {
+            if constraint.at.is_empty() {
+                writeln!(out, "    {}: {} everywhere", constraint.sup, constraint.sub)?;
+            } else {
+                writeln!(
+                    out,
+                    "    {}: {} at {}",
+                    constraint.sup,
+                    constraint.sub,
+                    point_ranges(&constraint.at)
+                )?;
+            }
+        }
+        writeln!(out, "borrows:")?;
+        for borrow in &self.borrows {
+            writeln!(
+                out,
+                "    {} = {} {} in {}, reserved at {}, live at {}",
+                borrow.borrow,
+                borrow.kind,
+                borrow.place,
+                borrow.region,
+                borrow.reserved_at,
+                point_ranges(&borrow.live_at)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl MirReport {
+    /// The region constraint graph: an edge from `sup` to `sub` per
+    /// constraint, and a dashed one from each borrow to its region.
+    pub fn write_dot(&self, mut out: impl Write) -> io::Result<()> {
+        writeln!(out, "digraph {:?} {{", self.function)?;
+        for region in &self.regions {
+            let label = match &region.name {
+                Some(name) => format!("{} ({name})", region.region),
+                None => region.region.clone(),
+            };
+            let shape = if region.universal { "box" } else { "ellipse" };
+            writeln!(
+                out,
+                "    {:?} [label={label:?}, shape={shape}];",
+                region.region
+            )?;
+        }
+        for constraint in &self.constraints {
+            let label = if constraint.at.is_empty() {
+                "everywhere".to_string()
+            } else {
+                point_ranges(&constraint.at)
+            };
+            writeln!(
+                out,
+                "    {:?} -> {:?} [label={label:?}];",
+                constraint.sup, constraint.sub
+            )?;
+        }
+        for borrow in &self.borrows {
+            let label = format!("{} = {} {}", borrow.borrow, borrow.kind, borrow.place);
+            writeln!(
+                out,
+                "    {:?} [label={label:?}, shape=note];",
+                borrow.borrow
+            )?;
+            writeln!(
+                out,
+                "    {:?} -> {:?} [style=dashed, label={:?}];",
+                borrow.borrow,
+                borrow.region,
+                point_ranges(&borrow.live_at)
+            )?;
+        }
+        writeln!(out, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(block: usize, statement: usize) -> Point {
+        Point { block, statement }
+    }
+
+    #[test]
+    fn consecutive_points_collapse_into_ranges() {
+        let points = [
+            point(0, 1),
+            point(0, 2),
+            point(0, 3),
+            point(1, 0),
+            point(2, 4),
+            point(2, 5),
+        ];
+        assert_eq!(point_ranges(&points), "bb0[1..=3], bb1[0], bb2[4..=5]");
+    }
+}
@@ -0,0 +1,107 @@
+use std::io::{self, Write};
+
+use collect_report::Report;
+use serde::{Deserialize, Serialize};
+
+/// What the crate hands to codegen: every instantiation of every function,
+/// grouped by the function it instantiates, and the vtables its unsizing
+/// coercions to `dyn Trait` need.
+#[derive(Serialize, Deserialize)]
+pub struct MonoReport {
+    #[serde(rename = "crate")]
+    pub krate: String,
+    /// Sorted by def path, the functions of the crate first.
+    pub functions: Vec<MonoFunction>,
+    pub vtables: Vec<VTable>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MonoFunction {
+    pub def_path: String,
+    /// Defined in this crate rather than instantiated from a dependency.
+    pub local: bool,
+    /// Whether the function has type or const parameters, so that each
+    /// instance is a copy for other arguments.
+    pub generic: bool,
+    pub instances: Vec<MonoInstance>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MonoInstance {
+    /// As rustc prints it, e.g. `process_shape_generic::<Circle>`.
+    pub instance: String,
+    /// rustc's estimate of the size of the instance, in MIR statements.
+    pub size_estimate: usize,
+    pub codegen_unit: String,
+    /// Copied into every codegen unit using it rather than shared.
+    pub inlined: bool,
+}
+
+/// The vtable of a concrete type behind a `dyn Trait`.
+#[derive(Serialize, Deserialize)]
+pub struct VTable {
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub dyn_ty: String,
+    /// `drop_in_place`, `size` and `align`, then one entry per method.
+    pub entries: Vec<String>,
+    /// The instances coercing the type to `dyn_ty`.
+    pub coerced_in: Vec<String>,
+}
+
+impl Report for MonoReport {
+    /// One block per function, then one per vtable:
+    ///
+    /// ```text
+    /// process_shape_generic (generic)
+    ///     process_shape_generic::<Circle>  size 9  in main.a1b2c3-cgu.0
+    ///
+    /// vtable of Circle as dyn Shape
+    ///     entries: drop_in_place, size, align, <Circle as Shape>::area
+    ///     coerced in: main
+    /// ```
+    ///
+    /// Functions of dependencies are counted rather than listed.
+    fn write_text(&self, mut out: impl Write) -> io::Result<()> {
+        let mut first = true;
+        let mut foreign = 0;
+        for function in &self.functions {
+            if !function.local {
+                foreign += function.instances.len();
+                continue;
+            }
+            if !first {
+                writeln!(out)?;
+            }
+            first = false;
+            let generic = if function.generic { " (generic)" } else { "" };
+            writeln!(out, "{}{generic}", function.def_path)?;
+            for instance in &function.instances {
+                let inlined = if instance.inlined { ", inlined" } else { "" };
+                writeln!(
+                    out,
+                    "    {}  size {}  in {}{inlined}",
+                    instance.instance, instance.size_estimate, instance.codegen_unit
+                )?;
+            }
+        }
+        if foreign > 0 {
+            if !first {
+                writeln!(out)?;
+            }
+            first = false;
+            writeln!(out, "{foreign} instances of functions from dependencies")?;
+        }
+
+        for vtable in &self.vtables {
+            if !first {
+                writeln!(out)?;
+            }
+            first = false;
+            writeln!(out, "vtable of {} as {}", vtable.ty, vtable.dyn_ty)?;
+            writeln!(out, "    entries: {}", vtable.entries.join(", "))?;
+            writeln!(out, "    coerced in: {}", vtable.coerced_in.join(", "))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use std::io::{self, Write};
+
+use collect_report::Report;
+use serde::{Deserialize, Serialize};
+
+/// Every lang item a crate can see, whether it defines it, gets it from a
+/// dependency, or needs it and has none.
+#[derive(Serialize, Deserialize)]
+pub struct LangItemReport {
+    #[serde(rename = "crate")]
+    pub krate: String,
+    /// `lib`, `bin`, `proc-macro`, ...
+    pub crate_types: Vec<String>,
+    /// The cargo package of the crate, as `name@version`, when the driver is
+    /// run by cargo.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// The cargo target of the crate, as the path of its root in the package,
+    /// when the driver is run by cargo. A library, a binary and an example
+    /// may share their crate name, but not their root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Whether the crate is built as a test harness, which `--all-targets`
+    /// does for a target besides building it as itself.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub test: bool,
+    pub lang_items: Vec<LangItemEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct LangItemEntry {
+    /// The name in `#[lang = "..."]`.
+    pub name: String,
+    /// The kind of item the lang item must be attached to, e.g. `trait`.
+    pub target: String,
+    /// The crate defining the item, `None` when missing.
+    pub defining_crate: Option<String>,
+    pub def_id: Option<String>,
+    pub def_path: Option<String>,
+    pub span: Option<String>,
+    /// Required by the crate but defined nowhere, like `eh_personality` in a
+    /// `#![no_core]` crate.
+    pub missing: bool,
+}
+
+impl LangItemReport {
+    /// The name of the file the report of this crate is written to, unique
+    /// to its package, target and whether it is a test harness.
+    pub fn file_name(&self) -> String {
+        let target = self.target.as_deref().unwrap_or(&self.krate);
+        format!(
+            "{}-{}{}.json",
+            self.package.as_deref().unwrap_or(&self.krate),
+            target.replace(['/', '\\'], "-"),
+            if self.test { "-test" } else { "" }
+        )
+    }
+}
+
+impl Report for LangItemReport {
+    /// One lang item per line, as `name<TAB>DefId<TAB>def path<TAB>span`, or
+    /// `name<TAB>missing`.
+    fn write_text(&self, mut out: impl Write) -> io::Result<()> {
+        for item in &self.lang_items {
+            match (&item.def_id, &item.def_path, &item.span) {
+                (Some(def_id), Some(def_path), Some(span)) => {
+                    writeln!(out, "{}\t{def_id}\t{def_path}\t{span}", item.name)?
+                }
+                _ => writeln!(out, "{}\tmissing", item.name)?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+//! Runs the `collect-lang-items` driver on the fixtures of the tests.
+
+// Each test file uses some of these.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// The path of `fixture`, relative to `tests/`.
+pub fn fixture(fixture: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join(fixture)
+}
+
+/// Runs the driver on `fixture` with `args`, a subcommand and its options.
+/// The fixture is compiled as a library, unless it has a `main`.
+pub fn driver(args: &[&str], fixture: &str) -> Output {
+    let path = self::fixture(fixture);
+    let mut command = Command::new(env!("CARGO_BIN_EXE_collect-lang-items"));
+    command.args(args).args(["--edition", "2021"]);
+    if !fs::read_to_string(&path).unwrap().contains("fn main(") {
+        command.args(["--crate-type", "lib"]);
+    }
+    command.arg(&path).output().unwrap()
+}
+
+/// What the driver prints on `fixture` with `args`, which must succeed.
+pub fn run_driver(args: &[&str], fixture: &str) -> String {
+    let output = driver(args, fixture);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
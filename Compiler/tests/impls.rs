@@ -0,0 +1,99 @@
+use serde_json::Value;
+
+mod common;
+
+use common::run_driver;
+
+fn impl_report(trait_path: &str, ty: &str, format: &str) -> String {
+    run_driver(
+        &[
+            "impls", "--trait", trait_path, "--type", ty, "--format", format,
+        ],
+        "impls/blanket.rs",
+    )
+}
+
+#[test]
+fn blanket_impl_applies_through_its_bound() {
+    let stdout = impl_report("DefaultPrintable", "&str", "text");
+    let expected = "\
+impl<T> DefaultPrintable for T
+    where T: Printable
+    at $DIR/blanket.rs:30:1: 30:51
+
+`&str` implements `DefaultPrintable`:
+    applies: impl<T> DefaultPrintable for T
+";
+    let dir = common::fixture("impls");
+    assert_eq!(stdout.replace(dir.to_str().unwrap(), "$DIR"), expected);
+}
+
+#[test]
+fn explains_why_no_impl_applies() {
+    let stdout = impl_report("DefaultPrintable", "String", "text");
+    assert!(
+        stdout.ends_with(
+            "`std::string::String` does not implement `DefaultPrintable`:\n    \
+             rejected: impl<T> DefaultPrintable for T: \
+             `std::string::String: Printable` does not hold\n"
+        ),
+        "{stdout}"
+    );
+
+    let stdout = impl_report("Printable", "String", "text");
+    assert!(
+        stdout.ends_with("    2 more impls are for other types\n"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn json_report() {
+    let report: Value = serde_json::from_str(&impl_report("Printable", "Person", "json")).unwrap();
+    assert_eq!(report["trait"], "Printable");
+    let headers: Vec<_> = report["impls"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|imp| {
+            (
+                imp["header"].as_str().unwrap(),
+                imp["blanket"].as_bool().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        headers,
+        [
+            ("impl Printable for Person", false),
+            ("impl Printable for &str", false),
+        ]
+    );
+
+    let explanation = &report["explanation"];
+    assert_eq!(explanation["type"], "Person");
+    assert_eq!(explanation["implemented"], true);
+    assert_eq!(explanation["candidates"][0]["applies"], true);
+    assert_eq!(explanation["candidates"][1]["self_ty_matches"], false);
+    assert_eq!(
+        explanation["candidates"][1]["reason"],
+        "`Person` is not `&str`"
+    );
+}
+
+#[test]
+fn impls_of_dependencies_apply_too() {
+    let report: Value =
+        serde_json::from_str(&impl_report("std::marker::Copy", "&str", "json")).unwrap();
+    assert_eq!(report["explanation"]["implemented"], true);
+    let blanket = report["impls"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .position(|imp| imp["header"] == "impl<T> std::marker::Copy for &T")
+        .unwrap();
+    assert_eq!(
+        report["explanation"]["candidates"][blanket]["applies"],
+        true
+    );
+}
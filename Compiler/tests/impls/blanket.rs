@@ -0,0 +1,39 @@
+// The blanket impl of `rust_advanced_concepts/1.rs`: `DefaultPrintable`
+// comes for free with `Printable`.
+
+trait Printable {
+    fn print_info(&self);
+}
+
+struct Person {
+    name: String,
+}
+
+impl Printable for Person {
+    fn print_info(&self) {
+        println!("Name: {}", self.name);
+    }
+}
+
+impl Printable for &str {
+    fn print_info(&self) {
+        println!("String: {}", *self);
+    }
+}
+
+trait DefaultPrintable {
+    fn default_print(&self) {
+        println!("Information not available.");
+    }
+}
+
+impl<T: ?Sized + Printable> DefaultPrintable for T {}
+
+fn main() {
+    let person = Person {
+        name: String::from("Alice"),
+    };
+    person.print_info();
+    person.default_print();
+    "Some data".default_print();
+}
@@ -0,0 +1,68 @@
+use serde_json::Value;
+
+mod common;
+
+use common::run_driver;
+
+fn item_inventory(format: &str) -> String {
+    run_driver(&["items", "--format", format], "items/inventory.rs")
+}
+
+#[test]
+fn table_lists_items_in_source_order() {
+    let stdout = item_inventory("text");
+    let rows: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|line| line.split("  ").filter(|cell| !cell.is_empty()).collect())
+        .collect();
+    let columns: Vec<_> = rows
+        .iter()
+        .map(|row| (row[0].trim(), row[1].trim(), row[2].trim()))
+        .collect();
+    assert_eq!(
+        columns,
+        [
+            ("KIND", "VISIBILITY", "ITEM"),
+            ("struct", "pub", "Meters"),
+            ("enum", "pub", "Unit"),
+            ("trait", "pub", "Describe"),
+            ("impl", "-", "impl Meters"),
+            ("impl", "-", "impl std::fmt::Display for Meters"),
+            ("impl", "-", "impl Describe for std::vec::Vec<T>"),
+            ("mod", "pub(self)", "private"),
+            ("fn", "pub(crate)", "private::helper"),
+            ("fn", "pub", "convert"),
+        ]
+    );
+    assert!(
+        rows[1][3].ends_with("inventory.rs:5:1: 5:18"),
+        "{:?}",
+        rows[1]
+    );
+}
+
+#[test]
+fn json_inventory() {
+    let inventory: Value = serde_json::from_str(&item_inventory("json")).unwrap();
+    assert_eq!(inventory["crate"], "inventory");
+    let items = inventory["items"].as_array().unwrap();
+
+    let display = &items[4];
+    assert_eq!(display["kind"], "impl");
+    assert_eq!(display["name"], Value::Null);
+    assert_eq!(display["visibility"], Value::Null);
+    assert_eq!(display["trait_ref"], "std::fmt::Display");
+    assert_eq!(display["self_ty"], "Meters");
+
+    let inherent = &items[3];
+    assert_eq!(inherent["trait_ref"], Value::Null);
+    assert_eq!(inherent["self_ty"], "Meters");
+
+    let helper = &items[7];
+    assert_eq!(helper["name"], "helper");
+    assert_eq!(helper["def_path"], "private::helper");
+    assert!(helper["span"]
+        .as_str()
+        .unwrap()
+        .ends_with("inventory.rs:35:5: 35:42"));
+}
@@ -0,0 +1,43 @@
+// One item of each kind the inventory lists.
+
+use std::fmt;
+
+pub struct Meters(pub f64);
+
+pub enum Unit {
+    Meter,
+    Foot,
+}
+
+pub trait Describe {
+    fn describe(&self) -> String;
+}
+
+impl Meters {
+    pub fn new(value: f64) -> Self {
+        Meters(value)
+    }
+}
+
+impl fmt::Display for Meters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} m", self.0)
+    }
+}
+
+impl<T: fmt::Debug> Describe for Vec<T> {
+    fn describe(&self) -> String {
+        format!("{self:?}")
+    }
+}
+
+mod private {
+    pub(super) fn helper() -> super::Unit {
+        super::Unit::Foot
+    }
+}
+
+pub fn convert(_unit: Unit) -> Meters {
+    let _ = (Unit::Meter, private::helper());
+    Meters::new(0.0)
+}
@@ -0,0 +1,5 @@
+// An ordinary library: every lang item comes from `core`, `alloc` or `std`.
+
+pub fn first(items: &[u8]) -> Option<u8> {
+    items.first().copied()
+}
@@ -0,0 +1,200 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde_json::Value;
+
+mod common;
+
+use common::run_driver;
+
+fn collect_lang_items(file: &str, format: &str) -> String {
+    run_driver(
+        &["lang-items", "--format", format],
+        &format!("lang-items/{file}"),
+    )
+}
+
+#[test]
+fn no_core_crate_defines_its_own_lang_items() {
+    let stdout = collect_lang_items("no_core.rs", "text");
+    let items: Vec<Vec<&str>> = stdout
+        .lines()
+        .map(|line| line.split('\t').collect())
+        .collect();
+    let names: Vec<&str> = items.iter().map(|item| item[0]).collect();
+    assert_eq!(
+        names,
+        [
+            "copy",
+            "eh_personality",
+            "meta_sized",
+            "pointee_sized",
+            "sized"
+        ]
+    );
+
+    assert_eq!(items[1], ["eh_personality", "missing"]);
+    let sized = &items[4];
+    assert!(sized[1].starts_with("DefId(0:"), "{sized:?}");
+    assert_eq!(sized[2], "Sized");
+    assert!(sized[3].ends_with("no_core.rs:15:1: 15:27"), "{sized:?}");
+}
+
+#[test]
+fn json_report() {
+    let report: Value = serde_json::from_str(&collect_lang_items("no_core.rs", "json")).unwrap();
+    assert_eq!(report["crate"], "no_core");
+    let sized = &report["lang_items"][4];
+    assert_eq!(sized["name"], "sized");
+    assert_eq!(sized["target"], "trait");
+    assert_eq!(sized["defining_crate"], "no_core");
+    assert_eq!(sized["def_path"], "Sized");
+    assert_eq!(sized["missing"], false);
+    let eh_personality = &report["lang_items"][1];
+    assert_eq!(eh_personality["missing"], true);
+    assert_eq!(eh_personality["span"], Value::Null);
+
+    let report: Value = serde_json::from_str(&collect_lang_items("uses_core.rs", "json")).unwrap();
+    let items = report["lang_items"].as_array().unwrap();
+    let option = items.iter().find(|item| item["name"] == "Option").unwrap();
+    assert_eq!(option["defining_crate"], "core");
+    assert_eq!(option["target"], "enum");
+    assert!(items.iter().all(|item| item["missing"] == false));
+}
+
+fn write(path: &Path, contents: &str) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+/// A workspace of a binary `app` depending on a library `util`, which
+/// depends on a crate outside of it, in a directory named `name`.
+fn workspace(name: &str) -> PathBuf {
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join(name);
+    let _ = std::fs::remove_dir_all(&root);
+    let workspace = root.join("ws");
+    write(
+        &workspace.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"app\", \"util\"]\nresolver = \"2\"\n",
+    );
+    write(
+        &workspace.join("app/Cargo.toml"),
+        "[package]\nname = \"app\"\nedition = \"2021\"\n\n\
+         [dependencies]\nutil = { path = \"../util\" }\n",
+    );
+    write(
+        &workspace.join("app/src/main.rs"),
+        "fn main() {\n    util::hello();\n}\n",
+    );
+    write(
+        &workspace.join("util/Cargo.toml"),
+        "[package]\nname = \"util\"\nedition = \"2021\"\n\n\
+         [dependencies]\noutside = { path = \"../../outside\" }\n",
+    );
+    write(
+        &workspace.join("util/src/lib.rs"),
+        "pub fn hello() {\n    outside::hello();\n}\n",
+    );
+    // Not a member, so built by plain rustc.
+    write(
+        &root.join("outside/Cargo.toml"),
+        "[package]\nname = \"outside\"\nedition = \"2021\"\n\n[workspace]\n",
+    );
+    write(&root.join("outside/src/lib.rs"), "pub fn hello() {}\n");
+    workspace
+}
+
+/// The JSON report of `cargo collect-lang-items` in `workspace`, with
+/// `args` for `cargo check`.
+fn cargo_collect_lang_items(workspace: &Path, args: &[&str]) -> Value {
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-collect-lang-items"))
+        .args(["collect-lang-items", "--format", "json", "--quiet"])
+        .args(args)
+        .current_dir(workspace)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+/// The package, target and crate types of each crate of `report`.
+fn targets(report: &Value) -> Vec<(&str, &str, String)> {
+    report["crates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|krate| {
+            let mut crate_types = krate["crate_types"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|ty| ty.as_str().unwrap())
+                .collect::<Vec<_>>()
+                .join(", ");
+            if krate["test"] == true {
+                crate_types.push_str(", test");
+            }
+            (
+                krate["package"].as_str().unwrap(),
+                krate["target"].as_str().unwrap(),
+                crate_types,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn cargo_subcommand_reports_workspace_crates_only() {
+    let workspace = workspace("collect-lang-items-workspace");
+    let report = cargo_collect_lang_items(&workspace, &[]);
+    assert_eq!(
+        targets(&report),
+        [
+            ("app@0.0.0", "src/main.rs", "bin".to_string()),
+            ("util@0.0.0", "src/lib.rs", "lib".to_string()),
+        ]
+    );
+    let sized = report["crates"][1]["lang_items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["name"] == "sized")
+        .unwrap();
+    assert_eq!(sized["defining_crate"], "core");
+}
+
+#[test]
+fn cargo_subcommand_reports_the_selected_packages_of_this_run_only() {
+    let workspace = workspace("collect-lang-items-selected");
+    write(&workspace.join("app/src/lib.rs"), "pub fn app() {}\n");
+
+    let report = cargo_collect_lang_items(&workspace, &["-p", "util"]);
+    assert_eq!(
+        targets(&report),
+        [("util@0.0.0", "src/lib.rs", "lib".to_string())]
+    );
+    // `util` is fresh, and checked again only as a dependency of `app`.
+    let report = cargo_collect_lang_items(&workspace, &["-p", "app", "--all-targets"]);
+    assert_eq!(
+        targets(&report),
+        [
+            ("app@0.0.0", "src/lib.rs", "lib".to_string()),
+            ("app@0.0.0", "src/lib.rs", "bin, test".to_string()),
+            ("app@0.0.0", "src/main.rs", "bin".to_string()),
+            ("app@0.0.0", "src/main.rs", "bin, test".to_string()),
+        ]
+    );
+    // Nothing changed, so cargo finds `app` fresh, yet it is reported.
+    let report = cargo_collect_lang_items(&workspace, &["-p", "app"]);
+    assert_eq!(
+        targets(&report),
+        [
+            ("app@0.0.0", "src/lib.rs", "lib".to_string()),
+            ("app@0.0.0", "src/main.rs", "bin".to_string()),
+        ]
+    );
+}
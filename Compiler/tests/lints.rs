@@ -0,0 +1,102 @@
+use std::path::Path;
+use std::process::Output;
+
+mod common;
+
+use common::{driver, fixture};
+
+fn check(config: Option<&str>) -> Output {
+    match config {
+        Some(config) => {
+            let config = fixture("lints").join(config);
+            driver(
+                &["lang-items", "--lints", config.to_str().unwrap()],
+                "lints/str_impls.rs",
+            )
+        }
+        None => driver(&["lang-items"], "lints/str_impls.rs"),
+    }
+}
+
+fn stderr(output: &Output) -> String {
+    let dir = fixture("lints");
+    String::from_utf8_lossy(&output.stderr).replace(dir.to_str().unwrap(), "$DIR")
+}
+
+#[test]
+fn suggests_implementing_for_str() {
+    let output = check(None);
+    assert!(output.status.success(), "{}", stderr(&output));
+    let stderr = stderr(&output);
+    let expected = "\
+warning: `Printable` is implemented for `&str`
+ --> $DIR/str_impls.rs:8:20
+  |
+8 | impl Printable for &str {
+  |                    ^^^^
+  |
+  = note: its `&self` methods take a `&&str`, and `str` does not implement it
+  = note: `#[warn(impls_for_str_ref)]` on by default
+help: implement it for `str`, which method calls on `&str` find by auto-deref
+  |
+8 - impl Printable for &str {
+8 + impl Printable for str {
+  |
+";
+    assert!(stderr.starts_with(expected), "{stderr}");
+}
+
+#[test]
+fn no_suggestion_when_the_trait_needs_sized() {
+    let stderr = stderr(&check(None));
+    let expected = "\
+warning: `Consume` is implemented for `&str`
+  --> $DIR/str_impls.rs:18:18
+   |
+18 | impl Consume for &'static str {
+   |                  ^^^^^^^^^^^^
+   |
+   = note: it cannot be implemented for `str`, as `consume` takes `self` by value
+
+warning: 2 warnings emitted
+
+";
+    assert!(stderr.ends_with(expected), "{stderr}");
+    let consume = &stderr[stderr.find("`Consume`").unwrap()..];
+    assert!(!consume.contains("`&&str`"), "{stderr}");
+}
+
+#[test]
+fn levels_come_from_the_config() {
+    let output = check(Some("deny.toml"));
+    assert!(!output.status.success());
+    let denied = stderr(&output);
+    assert!(
+        denied.contains("error: `Printable` is implemented for `&str`"),
+        "{denied}"
+    );
+    assert!(
+        denied.contains("error: aborting due to 2 previous errors"),
+        "{denied}"
+    );
+
+    let output = check(Some("allow.toml"));
+    assert!(output.status.success());
+    assert_eq!(stderr(&output), "");
+}
+
+#[test]
+fn unknown_lints_are_rejected() {
+    let config = Path::new(env!("CARGO_TARGET_TMPDIR")).join("unknown-lint.toml");
+    std::fs::write(&config, "[lints]\nimpls_for_string_ref = \"deny\"\n").unwrap();
+    let output = check(Some(config.to_str().unwrap()));
+    assert!(!output.status.success());
+    assert_eq!(
+        stderr(&output),
+        format!(
+            "error: unknown lint `impls_for_string_ref` in {}, \
+             expected one of impls_for_str_ref\n",
+            config.display()
+        )
+    );
+}
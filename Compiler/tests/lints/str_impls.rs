@@ -0,0 +1,31 @@
+// `impl Printable for &str` of `rust_advanced_concepts/1.rs`, and impls for
+// `&str` that could not be for `str`.
+
+pub trait Printable {
+    fn print_info(&self);
+}
+
+impl Printable for &str {
+    fn print_info(&self) {
+        println!("String: {}", *self);
+    }
+}
+
+pub trait Consume {
+    fn consume(self);
+}
+
+impl Consume for &'static str {
+    fn consume(self) {}
+}
+
+pub trait Describe {
+    fn describe(&self) -> String;
+}
+
+#[allow(impls_for_str_ref)]
+impl Describe for &str {
+    fn describe(&self) -> String {
+        format!("{self:?}")
+    }
+}
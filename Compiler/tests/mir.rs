@@ -0,0 +1,84 @@
+use serde_json::Value;
+
+mod common;
+
+use common::{driver, run_driver};
+
+fn mir_report(function: &str, format: &str) -> String {
+    run_driver(
+        &["mir", "--fn", function, "--format", format],
+        "mir/borrows.rs",
+    )
+}
+
+#[test]
+fn lifetime_parameter_is_a_universal_region() {
+    let report: Value = serde_json::from_str(&mir_report("longest", "json")).unwrap();
+    assert!(report["mir"]
+        .as_str()
+        .unwrap()
+        .starts_with("fn longest(_1: &'?6 str, _2: &'?7 str) -> &'?5 str {"));
+
+    let regions = report["regions"].as_array().unwrap();
+    let a = regions
+        .iter()
+        .find(|region| region["universal"] == true && region["name"] == "'a")
+        .unwrap();
+    assert_eq!(a["region"], "'?1");
+    // Both arguments and the return type are `'a`.
+    for region in ["'?5", "'?6", "'?7"] {
+        let entry = regions
+            .iter()
+            .find(|entry| entry["region"] == region)
+            .unwrap();
+        assert_eq!(entry["name"], "'a");
+        assert_eq!(entry["universal"], false);
+    }
+
+    let constraints = report["constraints"].as_array().unwrap();
+    let everywhere = |sup: &str, sub: &str| {
+        constraints.iter().any(|constraint| {
+            constraint["sup"] == sup
+                && constraint["sub"] == sub
+                && constraint["at"].as_array().unwrap().is_empty()
+        })
+    };
+    assert!(everywhere("'?1", "'?6") && everywhere("'?6", "'?1"));
+}
+
+#[test]
+fn shared_borrow_ends_before_the_mutable_one() {
+    let report: Value = serde_json::from_str(&mir_report("push_first", "json")).unwrap();
+    let borrows = report["borrows"].as_array().unwrap();
+    let shared = borrows
+        .iter()
+        .find(|borrow| borrow["kind"] == "&" && borrow["place"] == "(*_1)")
+        .unwrap();
+    let mutable = borrows
+        .iter()
+        .find(|borrow| borrow["kind"] == "&mut")
+        .unwrap();
+    let live_at = shared["live_at"].as_array().unwrap();
+    assert!(live_at.contains(&shared["reserved_at"]));
+    assert!(!live_at.contains(&mutable["reserved_at"]), "{shared:#}");
+}
+
+#[test]
+fn text_and_dot() {
+    let text = mir_report("longest", "text");
+    assert!(text.contains("\nregions:\n    '?0 universal 'static\n    '?1 universal 'a\n"));
+    assert!(text.contains("\nconstraints:\n    '?1: '?5 everywhere\n"));
+
+    let dot = mir_report("longest", "dot");
+    assert!(dot.starts_with("digraph \"longest\" {\n"));
+    assert!(dot.contains("    \"'?1\" [label=\"'?1 ('a)\", shape=box];\n"));
+    assert!(dot.contains("    \"'?6\" -> \"'?5\" [label=\"bb3[2]\"];\n"));
+    assert!(dot.ends_with("}\n"));
+}
+
+#[test]
+fn unknown_function() {
+    let output = driver(&["mir", "--fn", "shortest"], "mir/borrows.rs");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no function `shortest` in the crate"));
+}
@@ -0,0 +1,19 @@
+// The two lifetime questions the prose of the repository keeps coming back
+// to: what `'a` ties together, and how long a borrow lasts.
+
+pub fn longest<'a>(x: &'a str, y: &'a str) -> &'a str {
+    if x.len() > y.len() {
+        x
+    } else {
+        y
+    }
+}
+
+// Accepted since NLL: `first` is not used after the copy, so the shared
+// borrow is over before `push` borrows `*v` mutably.
+pub fn push_first(v: &mut Vec<i32>) -> i32 {
+    let first = &v[0];
+    let x = *first;
+    v.push(x);
+    x
+}
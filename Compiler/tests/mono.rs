@@ -0,0 +1,101 @@
+use serde_json::Value;
+
+mod common;
+
+use common::run_driver;
+
+fn mono_report(file: &str, format: &str) -> String {
+    run_driver(&["mono", "--format", format], &format!("mono/{file}"))
+}
+
+#[test]
+fn generics_are_instantiated_per_type_and_trait_objects_once() {
+    let report: Value = serde_json::from_str(&mono_report("shapes.rs", "json")).unwrap();
+    let functions = report["functions"].as_array().unwrap();
+    let instances = |def_path: &str| -> Vec<String> {
+        let function = functions
+            .iter()
+            .find(|function| function["def_path"] == def_path)
+            .unwrap();
+        assert_eq!(function["local"], true);
+        function["instances"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|instance| {
+                assert!(instance["size_estimate"].as_u64().unwrap() > 0);
+                assert!(instance["codegen_unit"]
+                    .as_str()
+                    .unwrap()
+                    .starts_with("shapes."));
+                instance["instance"].as_str().unwrap().to_string()
+            })
+            .collect()
+    };
+
+    assert_eq!(
+        instances("process_shape_generic"),
+        [
+            "process_shape_generic::<Circle>",
+            "process_shape_generic::<Rectangle>"
+        ]
+    );
+    assert_eq!(
+        instances("process_shape_opaque"),
+        [
+            "process_shape_opaque::<Circle>",
+            "process_shape_opaque::<Rectangle>"
+        ]
+    );
+    assert_eq!(instances("process_shape_trait"), ["process_shape_trait"]);
+    let generic = |def_path: &str| {
+        functions
+            .iter()
+            .find(|function| function["def_path"] == def_path)
+            .unwrap()["generic"]
+            .clone()
+    };
+    assert_eq!(generic("process_shape_generic"), true);
+    assert_eq!(generic("process_shape_trait"), false);
+}
+
+#[test]
+fn vtables_of_dyn_coercions() {
+    let stdout = mono_report("shapes.rs", "text");
+    let vtables = &stdout[stdout.find("vtable of").unwrap()..];
+    assert_eq!(
+        vtables,
+        "\
+vtable of Circle as dyn Shape
+    entries: drop_in_place, size, align, <Circle as Shape>::area
+    coerced in: main
+
+vtable of Rectangle as dyn Shape
+    entries: drop_in_place, size, align, <Rectangle as Shape>::area
+    coerced in: main
+"
+    );
+}
+
+#[test]
+fn vtables_behind_smart_pointers() {
+    let report: Value = serde_json::from_str(&mono_report("smart_pointers.rs", "json")).unwrap();
+    let vtables: Vec<_> = report["vtables"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|vtable| {
+            (
+                vtable["type"].as_str().unwrap(),
+                vtable["dyn_ty"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        vtables,
+        [
+            ("&str", "dyn std::fmt::Debug + std::marker::Send"),
+            ("u8", "dyn std::fmt::Debug"),
+        ]
+    );
+}
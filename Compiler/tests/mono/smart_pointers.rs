@@ -0,0 +1,9 @@
+// Unsizing coercions through smart pointers rather than references.
+
+use std::fmt::Debug;
+use std::rc::Rc;
+fn main() {
+    let a: Box<dyn Debug> = Box::new(1u8);
+    let b: Rc<dyn Debug + Send> = Rc::new("x");
+    println!("{a:?} {b:?}");
+}
@@ -0,0 +1,10 @@
+mod string {
+    pub fn new() -> &'static str {
+        "a module, not a misspelled type"
+    }
+}
+
+fn main() {
+    let s = string::new();
+    println!("{s}");
+}
@@ -0,0 +1,3 @@
+fn main() {}
+
+struct //~ ERROR unexpected end of input
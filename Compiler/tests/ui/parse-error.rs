@@ -0,0 +1,3 @@
+fn main() {
+    let x = ; //~ ERROR expected an expression
+}
@@ -0,0 +1,7 @@
+// The suggestion differs only in the case of `s`, which looks alike in both
+// cases, see `Compiler/icd_2/`.
+
+fn main() {
+    let s = string::new(); //~ ERROR failed to resolve: use of undeclared type `string`
+    println!("{s}");
+}
@@ -0,0 +1,7 @@
+fn main() {
+    let x = 42; //~ WARN unused variable: `x`
+    //~^ HELP prefix it with an underscore
+    let _y = 1;
+    let z = 2;
+    println!("{z}");
+}
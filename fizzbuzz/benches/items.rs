@@ -0,0 +1,63 @@
+//! Writes the classic FizzBuzz up to 10^8, or the number given, once as the
+//! strings of its rules and once as `FizzBuzzItem`s, to a writer that only
+//! counts the bytes.
+//!
+//! ```text
+//! cargo bench --bench items [-- MAX]
+//! ```
+
+use std::io::{self, BufWriter, Write};
+use std::time::{Duration, Instant};
+
+use fizzbuzz::FizzBuzz;
+
+#[derive(Default)]
+struct Counter {
+    bytes: u64,
+}
+
+impl Write for Counter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn bench(name: &str, max: u64, write: impl FnOnce(&mut Counter) -> io::Result<()>) -> Duration {
+    let mut counter = Counter::default();
+    let start = Instant::now();
+    write(&mut counter).unwrap();
+    let elapsed = start.elapsed();
+    println!(
+        "{name:<8} {max} items, {} bytes in {:.3} s: {:.1} M items/s",
+        counter.bytes,
+        elapsed.as_secs_f64(),
+        max as f64 / elapsed.as_secs_f64() / 1e6
+    );
+    elapsed
+}
+
+fn main() {
+    // `cargo bench` passes `--bench` on to the benchmarks.
+    let max = std::env::args()
+        .skip(1)
+        .find(|arg| !arg.starts_with('-'))
+        .map_or(100_000_000, |max| max.parse().expect("the maximum"));
+
+    let strings = bench("String", max, |out| {
+        let mut out = BufWriter::with_capacity(1 << 16, out);
+        for item in FizzBuzz::new(max) {
+            writeln!(out, "{item}")?;
+        }
+        out.flush()
+    });
+    let items = bench("items", max, |out| FizzBuzz::new(max).items().write_to(out));
+    println!(
+        "items are {:.2}x as fast",
+        strings.as_secs_f64() / items.as_secs_f64()
+    );
+}
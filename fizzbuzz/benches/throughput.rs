@@ -0,0 +1,64 @@
+//! Writes the classic FizzBuzz up to 10^9, or the number given, with
+//! `Parallel` on more and more threads, and sequentially, to a writer that
+//! only counts the bytes, in GB/s.
+//!
+//! ```text
+//! cargo bench --bench throughput [-- MAX]
+//! ```
+
+use std::io::{self, Write};
+use std::thread;
+use std::time::Instant;
+
+use fizzbuzz::{FizzBuzz, Parallel};
+
+#[derive(Default)]
+struct Counter {
+    bytes: u64,
+}
+
+impl Write for Counter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.bytes += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn bench(name: &str, write: impl FnOnce(&mut Counter) -> io::Result<()>) {
+    let mut counter = Counter::default();
+    let start = Instant::now();
+    write(&mut counter).unwrap();
+    let elapsed = start.elapsed().as_secs_f64();
+    println!(
+        "{name:<12} {} bytes in {elapsed:.3} s: {:.2} GB/s",
+        counter.bytes,
+        counter.bytes as f64 / elapsed / 1e9
+    );
+}
+
+fn main() {
+    // `cargo bench` passes `--bench` on to the benchmarks.
+    let max = std::env::args()
+        .skip(1)
+        .find(|arg| !arg.starts_with('-'))
+        .map_or(1_000_000_000, |max| max.parse().expect("the maximum"));
+    let cpus = thread::available_parallelism().map_or(1, Into::into);
+
+    bench("sequential", |out| FizzBuzz::new(max).items().write_to(out));
+    let mut threads = 1;
+    while threads <= cpus {
+        bench(&format!("{threads} threads"), |out| {
+            Parallel::new(max).threads(threads).write_to(out)
+        });
+        threads *= 2;
+    }
+    if threads / 2 != cpus {
+        bench(&format!("{cpus} threads"), |out| {
+            Parallel::new(max).write_to(out)
+        });
+    }
+}
@@ -0,0 +1,60 @@
+use std::fmt;
+
+use crate::num::{small, Divisible, Step, Zero};
+use crate::rules::Words;
+
+/// An item of the classic FizzBuzz, that only becomes text when displayed, so
+/// that writing many of them allocates nothing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum FizzBuzzItem<T> {
+    Fizz,
+    Buzz,
+    FizzBuzz,
+    Number(T),
+}
+
+impl<T: fmt::Display> fmt::Display for FizzBuzzItem<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FizzBuzzItem::Fizz => f.write_str("Fizz"),
+            FizzBuzzItem::Buzz => f.write_str("Buzz"),
+            FizzBuzzItem::FizzBuzz => f.write_str("FizzBuzz"),
+            FizzBuzzItem::Number(n) => n.fmt(f),
+        }
+    }
+}
+
+/// The classic rules, naming numbers by [`FizzBuzzItem`]s rather than by
+/// strings like [`Rules::classic`](crate::Rules::classic).
+pub struct Classic<T> {
+    three: T,
+    five: T,
+}
+
+impl<T: Zero + Step> Classic<T> {
+    pub fn new() -> Classic<T> {
+        Classic {
+            three: small(3),
+            five: small(5),
+        }
+    }
+}
+
+impl<T: Zero + Step> Default for Classic<T> {
+    fn default() -> Classic<T> {
+        Classic::new()
+    }
+}
+
+impl<T: Divisible> Words<T> for Classic<T> {
+    type Item = FizzBuzzItem<T>;
+
+    fn name(&self, n: T) -> FizzBuzzItem<T> {
+        match (n.divisible_by(&self.three), n.divisible_by(&self.five)) {
+            (true, true) => FizzBuzzItem::FizzBuzz,
+            (true, false) => FizzBuzzItem::Fizz,
+            (false, true) => FizzBuzzItem::Buzz,
+            (false, false) => FizzBuzzItem::Number(n),
+        }
+    }
+}
@@ -0,0 +1,219 @@
+//! FizzBuzz as an iterator generic over the integers, their ranges, and its
+//! rules, written in parallel by [`Parallel`], or worked out at compile time
+//! in a [`Table`].
+//!
+//! ```
+//! use fizzbuzz::{FizzBuzz, Rules};
+//!
+//! let classic: Vec<String> = FizzBuzz::new(15u8).collect();
+//! assert_eq!(classic[14], "FizzBuzz");
+//!
+//! let rules = Rules::classic().divisor(7, "Bazz").predicate(|n: &i64| *n < 0, "Negative");
+//! let mut fizz_buzz_bazz = FizzBuzz::with_rules(105i64, rules);
+//! assert_eq!(fizz_buzz_bazz.nth(104).unwrap(), "FizzBuzzBazz");
+//!
+//! let odd_backwards: Vec<String> = FizzBuzz::range(1u32..10).step(2).rev().collect();
+//! assert_eq!(odd_backwards, ["Fizz", "7", "Buzz", "Fizz", "1"]);
+//!
+//! let mut out = Vec::new();
+//! FizzBuzz::new(5u8).items().write_to(&mut out).unwrap();
+//! assert_eq!(out, b"1\n2\nFizz\n4\nBuzz\n");
+//! ```
+
+use std::fmt::Display;
+use std::io::{self, BufWriter, Write};
+use std::iter::FusedIterator;
+use std::ops::{Bound, RangeBounds};
+
+mod item;
+pub mod num;
+mod parallel;
+mod rules;
+mod table;
+
+pub use item::{Classic, FizzBuzzItem};
+pub use num::{Bounded, CheckedRem, Divisible, Integer, One, Step, Zero};
+pub use parallel::Parallel;
+pub use rules::{Rules, Words};
+pub use table::{table, Table};
+
+/// The numbers of a range, or every so many of them, those matching rules
+/// replaced by their words: strings with [`Rules`], [`FizzBuzzItem`]s with
+/// [`Classic`].
+///
+/// It knows how many numbers are left and how far apart they are, so it goes
+/// from both ends, and jumps with [`nth`](Iterator::nth) and
+/// [`nth_back`](DoubleEndedIterator::nth_back) rather than counting.
+pub struct FizzBuzz<T, W = Rules<T>> {
+    /// The next number from the front, if any is left.
+    front: T,
+    /// How many numbers are left.
+    len: usize,
+    /// How far apart they are.
+    step: usize,
+    words: W,
+}
+
+impl<T: Integer> FizzBuzz<T> {
+    /// The classic FizzBuzz, from 1 up to `max`.
+    pub fn new(max: T) -> FizzBuzz<T> {
+        FizzBuzz::with_rules(max, Rules::classic())
+    }
+
+    pub fn with_rules(max: T, rules: Rules<T>) -> FizzBuzz<T> {
+        FizzBuzz::range_with_rules(T::one()..=max, rules)
+    }
+
+    /// The classic FizzBuzz over `range`. Without a start, it starts at 1,
+    /// where FizzBuzz does, and without an end it goes up to the largest `T`.
+    ///
+    /// # Panics
+    ///
+    /// If the range has more numbers than a `usize` can count, as the length
+    /// of an [`ExactSizeIterator`] is one.
+    pub fn range(range: impl RangeBounds<T>) -> FizzBuzz<T> {
+        FizzBuzz::range_with_rules(range, Rules::classic())
+    }
+
+    /// # Panics
+    ///
+    /// If the range has more numbers than a `usize` can count.
+    pub fn range_with_rules(range: impl RangeBounds<T>, rules: Rules<T>) -> FizzBuzz<T> {
+        let start = match range.start_bound() {
+            Bound::Included(start) => Some(start.clone()),
+            Bound::Excluded(start) => T::forward_checked(start.clone(), 1),
+            Bound::Unbounded => Some(T::one()),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => Some(end.clone()),
+            Bound::Excluded(end) => T::backward_checked(end.clone(), 1),
+            Bound::Unbounded => Some(T::max_value()),
+        };
+        let (front, len) = match start.zip(end) {
+            Some((start, end)) if start <= end => {
+                let len = T::steps_between(&start, &end)
+                    .and_then(|steps| steps.checked_add(1))
+                    .expect("more numbers than a `usize` can count");
+                (start, len)
+            }
+            _ => (T::zero(), 0),
+        };
+        FizzBuzz {
+            front,
+            len,
+            step: 1,
+            words: rules,
+        }
+    }
+
+    /// The same numbers with the classic rules instead of its own, as
+    /// [`FizzBuzzItem`]s that are not allocated.
+    pub fn items(self) -> FizzBuzz<T, Classic<T>> {
+        FizzBuzz {
+            front: self.front,
+            len: self.len,
+            step: self.step,
+            words: Classic::new(),
+        }
+    }
+}
+
+impl<T: Integer, W: Words<T>> FizzBuzz<T, W> {
+    /// Only every `step`-th of the numbers left, from the next one on, like
+    /// [`Iterator::step_by`] but still a `FizzBuzz`.
+    ///
+    /// # Panics
+    ///
+    /// If `step` is 0.
+    pub fn step(mut self, step: usize) -> FizzBuzz<T, W> {
+        assert!(step != 0, "the step of a `FizzBuzz` must not be 0");
+        if self.len > 0 {
+            self.len = (self.len - 1) / step + 1;
+        }
+        // Only overflows when there is one number left, that `step` is not
+        // needed for.
+        self.step = self.step.saturating_mul(step);
+        self
+    }
+
+    /// The number `i` steps after the front, `i` being less than `len`.
+    fn number(&self, i: usize) -> T {
+        T::forward_checked(self.front.clone(), i * self.step).expect("a number of the range")
+    }
+
+    /// Writes the items left, a line each, through a buffer rather than
+    /// with a write to `out` for each.
+    pub fn write_to(self, out: impl Write) -> io::Result<()>
+    where
+        W::Item: Display,
+    {
+        let mut out = BufWriter::with_capacity(1 << 16, out);
+        for item in self {
+            writeln!(out, "{item}")?;
+        }
+        out.flush()
+    }
+}
+
+impl<T: Integer, W: Words<T>> Iterator for FizzBuzz<T, W> {
+    type Item = W::Item;
+
+    fn next(&mut self) -> Option<W::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        let next = if self.len > 0 {
+            self.number(1)
+        } else {
+            self.front.clone()
+        };
+        let current = std::mem::replace(&mut self.front, next);
+        Some(self.words.name(current))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<W::Item> {
+        if n >= self.len {
+            self.len = 0;
+            return None;
+        }
+        self.front = self.number(n);
+        self.len -= n;
+        self.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+
+    fn count(self) -> usize {
+        self.len
+    }
+
+    fn last(mut self) -> Option<W::Item> {
+        self.next_back()
+    }
+}
+
+impl<T: Integer, W: Words<T>> DoubleEndedIterator for FizzBuzz<T, W> {
+    fn next_back(&mut self) -> Option<W::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        Some(self.words.name(self.number(self.len)))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<W::Item> {
+        if n >= self.len {
+            self.len = 0;
+            return None;
+        }
+        self.len -= n;
+        self.next_back()
+    }
+}
+
+impl<T: Integer, W: Words<T>> ExactSizeIterator for FizzBuzz<T, W> {}
+
+impl<T: Integer, W: Words<T>> FusedIterator for FizzBuzz<T, W> {}
@@ -0,0 +1,39 @@
+use std::io;
+
+use fizzbuzz::{FizzBuzz, Parallel};
+
+fn main() {
+    let mut parallel = false;
+    let mut max = 100;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--parallel" | "-p" => parallel = true,
+            "-h" | "--help" => {
+                println!("usage: fizzbuzz [--parallel] [MAX]");
+                return;
+            }
+            _ => match arg.parse() {
+                Ok(arg) => max = arg,
+                Err(err) => {
+                    eprintln!("error: `{arg}` is not a maximum: {err}");
+                    std::process::exit(2);
+                }
+            },
+        }
+    }
+
+    let out = io::stdout().lock();
+    let written = if parallel {
+        Parallel::new(max).write_to(out)
+    } else {
+        FizzBuzz::<u64>::new(max).items().write_to(out)
+    };
+    // A pipe closed by the reader, of `fizzbuzz | head` say, is not an error.
+    if let Err(err) = written.or_else(|err| match err.kind() {
+        io::ErrorKind::BrokenPipe => Ok(()),
+        _ => Err(err),
+    }) {
+        eprintln!("error: {err}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,177 @@
+//! The arithmetic FizzBuzz needs, in the style of the `num-traits` crate:
+//! [`Zero`] and [`One`] are the identities that `prob_code_1.rs` and
+//! `prob_code_2.rs` try to get out of `Default`, and [`Divisible`] is built
+//! on them and [`CheckedRem`]. [`Bounded`] and [`Step`], a stable stand-in for the unstable
+//! `std::iter::Step`, let the iterator jump through a range without counting.
+
+use std::fmt::Display;
+
+pub trait Zero: Sized {
+    fn zero() -> Self;
+    fn is_zero(&self) -> bool;
+}
+
+pub trait One: Sized {
+    fn one() -> Self;
+}
+
+pub trait CheckedRem: Sized {
+    /// `self % v`, `None` if `v` is zero or if it overflows, as `MIN % -1`
+    /// does.
+    fn checked_rem(&self, v: &Self) -> Option<Self>;
+}
+
+pub trait Bounded {
+    fn min_value() -> Self;
+    fn max_value() -> Self;
+}
+
+pub trait Step: Clone + PartialOrd + Sized {
+    /// How many steps of one go from `start` to `end`, `None` if `end` is
+    /// before `start`, or if there are more than `usize` can count.
+    fn steps_between(start: &Self, end: &Self) -> Option<usize>;
+
+    /// `start` plus `n`, `None` if it overflows.
+    fn forward_checked(start: Self, n: usize) -> Option<Self>;
+
+    /// `start` minus `n`, `None` if it overflows.
+    fn backward_checked(start: Self, n: usize) -> Option<Self>;
+}
+
+macro_rules! impl_identities {
+    ($($t:ty)*) => {
+        $(
+            impl Zero for $t {
+                fn zero() -> $t {
+                    0
+                }
+
+                fn is_zero(&self) -> bool {
+                    *self == 0
+                }
+            }
+
+            impl One for $t {
+                fn one() -> $t {
+                    1
+                }
+            }
+        )*
+    };
+}
+
+impl_identities!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+macro_rules! impl_checked_rem {
+    ($($t:ty)*) => {
+        $(
+            impl CheckedRem for $t {
+                fn checked_rem(&self, v: &$t) -> Option<$t> {
+                    <$t>::checked_rem(*self, *v)
+                }
+            }
+        )*
+    };
+}
+
+impl_checked_rem!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+macro_rules! impl_bounded {
+    ($($t:ty)*) => {
+        $(
+            impl Bounded for $t {
+                fn min_value() -> $t {
+                    <$t>::MIN
+                }
+
+                fn max_value() -> $t {
+                    <$t>::MAX
+                }
+            }
+        )*
+    };
+}
+
+impl_bounded!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize);
+
+macro_rules! impl_step {
+    (unsigned: $($t:ty)*) => {
+        $(
+            impl Step for $t {
+                fn steps_between(start: &$t, end: &$t) -> Option<usize> {
+                    if start > end {
+                        return None;
+                    }
+                    usize::try_from(end - start).ok()
+                }
+
+                fn forward_checked(start: $t, n: usize) -> Option<$t> {
+                    start.checked_add(<$t>::try_from(n).ok()?)
+                }
+
+                fn backward_checked(start: $t, n: usize) -> Option<$t> {
+                    start.checked_sub(<$t>::try_from(n).ok()?)
+                }
+            }
+        )*
+    };
+    // The distance between two signed integers only fits in the unsigned
+    // integer of the same size.
+    (signed: $($t:ty => $unsigned:ty)*) => {
+        $(
+            impl Step for $t {
+                fn steps_between(start: &$t, end: &$t) -> Option<usize> {
+                    if start > end {
+                        return None;
+                    }
+                    usize::try_from(end.abs_diff(*start)).ok()
+                }
+
+                fn forward_checked(start: $t, n: usize) -> Option<$t> {
+                    start.checked_add_unsigned(<$unsigned>::try_from(n).ok()?)
+                }
+
+                fn backward_checked(start: $t, n: usize) -> Option<$t> {
+                    start.checked_sub_unsigned(<$unsigned>::try_from(n).ok()?)
+                }
+            }
+        )*
+    };
+}
+
+impl_step!(unsigned: u8 u16 u32 u64 u128 usize);
+impl_step!(signed: i8 => u8 i16 => u16 i32 => u32 i64 => u64 i128 => u128 isize => usize);
+
+pub trait Divisible {
+    /// Whether `divisor` divides `self`. Nothing is divisible by zero, rather
+    /// than the remainder panicking.
+    fn divisible_by(&self, divisor: &Self) -> bool;
+}
+
+impl<T> Divisible for T
+where
+    T: Zero + CheckedRem,
+{
+    fn divisible_by(&self, divisor: &T) -> bool {
+        // Past zero, the remainder only overflows for `MIN % -1`, and
+        // everything is divisible by -1.
+        !divisor.is_zero()
+            && self
+                .checked_rem(divisor)
+                .is_none_or(|remainder| remainder.is_zero())
+    }
+}
+
+/// Everything [`FizzBuzz`](crate::FizzBuzz) asks of the numbers it counts.
+pub trait Integer: Divisible + Zero + One + Bounded + Step + Display {}
+
+impl<T> Integer for T where T: Divisible + Zero + One + Bounded + Step + Display {}
+
+/// `n` as a `T`, for the divisors of the rules.
+///
+/// # Panics
+///
+/// If `n` does not fit in a `T`.
+pub fn small<T: Zero + Step>(n: u8) -> T {
+    T::forward_checked(T::zero(), n.into()).expect("too large for the integer")
+}
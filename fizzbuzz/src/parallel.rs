@@ -0,0 +1,195 @@
+//! The classic FizzBuzz of the `u64`s as fast as it can be written: the range
+//! is cut into chunks that threads render into buffers of bytes, which are
+//! written in order.
+//!
+//! Rendering a chunk divides nothing: the line of a number is the line of its
+//! place in the period of 15, and the digits of the numbers are counted up in
+//! place, the way they are written.
+
+use std::io::{self, Write};
+use std::ops::{Bound, RangeBounds};
+use std::sync::mpsc;
+use std::thread;
+
+/// The lines of the numbers by their remainder modulo 15, `None` for those
+/// written as themselves.
+const PERIOD: [Option<&[u8]>; 15] = [
+    Some(b"FizzBuzz\n"),
+    None,
+    None,
+    Some(b"Fizz\n"),
+    None,
+    Some(b"Buzz\n"),
+    Some(b"Fizz\n"),
+    None,
+    None,
+    Some(b"Fizz\n"),
+    Some(b"Buzz\n"),
+    None,
+    Some(b"Fizz\n"),
+    None,
+    None,
+];
+
+/// The classic FizzBuzz of a range of `u64`s, written by [`write_to`]
+/// with chunks rendered on several threads.
+///
+/// [`write_to`]: Parallel::write_to
+pub struct Parallel {
+    /// The first and the last number, if the range is not empty.
+    numbers: Option<(u64, u64)>,
+    threads: usize,
+    /// How many numbers a chunk has.
+    chunk: u64,
+}
+
+impl Parallel {
+    /// From 1 up to `max`.
+    pub fn new(max: u64) -> Parallel {
+        Parallel::range(1..=max)
+    }
+
+    /// Over `range`, that starts at 1 without a start and goes up to
+    /// `u64::MAX` without an end, as with [`FizzBuzz::range`].
+    ///
+    /// [`FizzBuzz::range`]: crate::FizzBuzz::range
+    pub fn range(range: impl RangeBounds<u64>) -> Parallel {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => Some(start),
+            Bound::Excluded(&start) => start.checked_add(1),
+            Bound::Unbounded => Some(1),
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => Some(end),
+            Bound::Excluded(&end) => end.checked_sub(1),
+            Bound::Unbounded => Some(u64::MAX),
+        };
+        Parallel {
+            numbers: start.zip(end).filter(|(start, end)| start <= end),
+            threads: thread::available_parallelism().map_or(1, Into::into),
+            chunk: 1 << 16,
+        }
+    }
+
+    /// Renders on `threads` threads, rather than on as many as there are
+    /// CPUs.
+    ///
+    /// # Panics
+    ///
+    /// If `threads` is 0.
+    pub fn threads(mut self, threads: usize) -> Parallel {
+        assert!(threads != 0, "a `Parallel` needs a thread");
+        self.threads = threads;
+        self
+    }
+
+    /// Renders `chunk` numbers at a time, rather than 65536.
+    ///
+    /// # Panics
+    ///
+    /// If `chunk` is 0.
+    pub fn chunk(mut self, chunk: u64) -> Parallel {
+        assert!(chunk != 0, "the chunks of a `Parallel` must not be empty");
+        self.chunk = chunk;
+        self
+    }
+
+    /// Writes the range, a line each, to `out`.
+    ///
+    /// The `k`-th chunk is rendered by the thread `k % threads`, which sends
+    /// it to be written on a channel of its own, so the chunks are taken in
+    /// order and a thread gets at most two chunks ahead of the writing.
+    pub fn write_to(self, mut out: impl Write) -> io::Result<()> {
+        let Some((start, end)) = self.numbers else {
+            return Ok(());
+        };
+        let (threads, chunk) = (self.threads as u64, self.chunk);
+        // The numbers of the `k`-th chunk, if there is one.
+        let bounds = move |k: u64| {
+            let first = start.checked_add(k.checked_mul(chunk)?)?;
+            (first <= end).then(|| (first, first.saturating_add(chunk - 1).min(end)))
+        };
+        thread::scope(|scope| {
+            let receivers: Vec<_> = (0..threads)
+                .map(|thread| {
+                    let (sender, receiver) = mpsc::sync_channel(2);
+                    scope.spawn(move || {
+                        let chunks = (0..).map_while(|i: u64| {
+                            bounds(i.checked_mul(threads)?.checked_add(thread)?)
+                        });
+                        for (first, last) in chunks {
+                            let mut buffer = Vec::new();
+                            render(first, last, &mut buffer);
+                            // The writing stopped, on an error.
+                            if sender.send(buffer).is_err() {
+                                break;
+                            }
+                        }
+                    });
+                    receiver
+                })
+                .collect();
+            // A thread is done when its next chunk would be past the end,
+            // and so would all the chunks after it.
+            for receiver in receivers.iter().cycle() {
+                let Ok(buffer) = receiver.recv() else {
+                    break;
+                };
+                out.write_all(&buffer)?;
+            }
+            out.flush()
+        })
+    }
+}
+
+/// Appends the lines of the numbers from `first` to `last` to `buffer`.
+fn render(first: u64, last: u64, buffer: &mut Vec<u8>) {
+    let mut digits = first.to_string().into_bytes();
+    let mut place = (first % 15) as usize;
+    // A guess at the length of the lines, to grow the buffer less often.
+    buffer.reserve(
+        ((last - first) as usize)
+            .saturating_add(1)
+            .saturating_mul(8),
+    );
+    for _ in first..=last {
+        match PERIOD[place] {
+            Some(line) => buffer.extend_from_slice(line),
+            None => {
+                buffer.extend_from_slice(&digits);
+                buffer.push(b'\n');
+            }
+        }
+        increment(&mut digits);
+        place = if place == 14 { 0 } else { place + 1 };
+    }
+}
+
+/// Adds one to the decimal number of `digits`, which grows a digit from
+/// 99...9.
+fn increment(digits: &mut Vec<u8>) {
+    for digit in digits.iter_mut().rev() {
+        if *digit == b'9' {
+            *digit = b'0';
+        } else {
+            *digit += 1;
+            return;
+        }
+    }
+    digits.insert(0, b'1');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_in_decimal() {
+        let mut digits = b"1299".to_vec();
+        increment(&mut digits);
+        assert_eq!(digits, b"1300");
+        let mut digits = b"999".to_vec();
+        increment(&mut digits);
+        assert_eq!(digits, b"1000");
+    }
+}
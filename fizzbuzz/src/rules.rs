@@ -0,0 +1,99 @@
+use std::fmt::Display;
+
+use crate::num::{small, Divisible, Step, Zero};
+
+/// How a [`FizzBuzz`](crate::FizzBuzz) names its numbers.
+pub trait Words<T> {
+    type Item;
+
+    fn name(&self, n: T) -> Self::Item;
+}
+
+/// The ordered rules of a FizzBuzz: a number is replaced by the words of all
+/// the rules it matches, in order, so that with the classic rules 15 is
+/// `FizzBuzz`.
+pub struct Rules<T> {
+    rules: Vec<Rule<T>>,
+}
+
+struct Rule<T> {
+    test: Test<T>,
+    word: String,
+}
+
+enum Test<T> {
+    Divisor(T),
+    Predicate(Box<dyn Fn(&T) -> bool + Send + Sync>),
+}
+
+impl<T> Rules<T> {
+    /// No rule at all: every number is itself.
+    pub fn new() -> Rules<T> {
+        Rules { rules: Vec::new() }
+    }
+
+    /// `Fizz` for the multiples of 3, `Buzz` for those of 5.
+    pub fn classic() -> Rules<T>
+    where
+        T: Zero + Step,
+    {
+        Rules::new()
+            .divisor(small(3), "Fizz")
+            .divisor(small(5), "Buzz")
+    }
+
+    /// Adds `word` for the multiples of `divisor`.
+    pub fn divisor(mut self, divisor: T, word: impl Into<String>) -> Rules<T> {
+        self.rules.push(Rule {
+            test: Test::Divisor(divisor),
+            word: word.into(),
+        });
+        self
+    }
+
+    /// Adds `word` for the numbers `predicate` holds for.
+    pub fn predicate(
+        mut self,
+        predicate: impl Fn(&T) -> bool + Send + Sync + 'static,
+        word: impl Into<String>,
+    ) -> Rules<T> {
+        self.rules.push(Rule {
+            test: Test::Predicate(Box::new(predicate)),
+            word: word.into(),
+        });
+        self
+    }
+
+    /// The words of the rules `n` matches, or `None` if it matches none.
+    pub fn words(&self, n: &T) -> Option<String>
+    where
+        T: Divisible,
+    {
+        let mut words: Option<String> = None;
+        for rule in &self.rules {
+            let matches = match &rule.test {
+                Test::Divisor(divisor) => n.divisible_by(divisor),
+                Test::Predicate(predicate) => predicate(n),
+            };
+            if matches {
+                words.get_or_insert_with(String::new).push_str(&rule.word);
+            }
+        }
+        words
+    }
+}
+
+impl<T> Default for Rules<T> {
+    fn default() -> Rules<T> {
+        Rules::new()
+    }
+}
+
+impl<T: Divisible + Display> Words<T> for Rules<T> {
+    type Item = String;
+
+    /// The words of the rules `n` matches, or `n` itself.
+    fn name(&self, n: T) -> String {
+        self.words(&n).unwrap_or_else(|| n.to_string())
+    }
+}
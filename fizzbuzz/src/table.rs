@@ -0,0 +1,60 @@
+//! FizzBuzz worked out by the compiler: tables of the [`FizzBuzzItem`]s of
+//! 1 to `N`, for divisors known at compile time.
+//!
+//! A `const fn` cannot call the methods of the traits of [`crate::num`] yet,
+//! so the tables are of `u64`s only.
+
+use crate::FizzBuzzItem;
+
+/// The items of 1 to `N`, with `FIZZ` and `BUZZ` as the divisors of `Fizz`
+/// and `Buzz`.
+///
+/// ```
+/// use fizzbuzz::{FizzBuzzItem, Table};
+///
+/// static CLASSIC: [FizzBuzzItem<u64>; 100] = Table::<100>::ITEMS;
+/// assert_eq!(CLASSIC[14], FizzBuzzItem::FizzBuzz);
+/// assert_eq!(Table::<14, 2, 7>::ITEMS[13], FizzBuzzItem::FizzBuzz);
+/// ```
+pub struct Table<const N: usize, const FIZZ: u64 = 3, const BUZZ: u64 = 5>;
+
+impl<const N: usize, const FIZZ: u64, const BUZZ: u64> Table<N, FIZZ, BUZZ> {
+    pub const ITEMS: [FizzBuzzItem<u64>; N] = table(FIZZ, BUZZ);
+}
+
+/// The items of 1 to `N`, `Fizz` for the multiples of `fizz` and `Buzz` for
+/// those of `buzz`. As with [`Divisible`](crate::Divisible), nothing is a
+/// multiple of 0.
+pub const fn table<const N: usize>(fizz: u64, buzz: u64) -> [FizzBuzzItem<u64>; N] {
+    let mut items = [FizzBuzzItem::Number(0); N];
+    let mut i = 0;
+    while i < N {
+        let n = i as u64 + 1;
+        items[i] = match (multiple(n, fizz), multiple(n, buzz)) {
+            (true, true) => FizzBuzzItem::FizzBuzz,
+            (true, false) => FizzBuzzItem::Fizz,
+            (false, true) => FizzBuzzItem::Buzz,
+            (false, false) => FizzBuzzItem::Number(n),
+        };
+        i += 1;
+    }
+    items
+}
+
+const fn multiple(n: u64, divisor: u64) -> bool {
+    divisor != 0 && n.is_multiple_of(divisor)
+}
+
+// Checked when the crate is compiled.
+const _: () = assert!(matches!(
+    Table::<15>::ITEMS,
+    [
+        FizzBuzzItem::Number(1),
+        FizzBuzzItem::Number(2),
+        FizzBuzzItem::Fizz,
+        FizzBuzzItem::Number(4),
+        FizzBuzzItem::Buzz,
+        ..,
+        FizzBuzzItem::FizzBuzz
+    ]
+));
@@ -0,0 +1,32 @@
+use fizzbuzz::{FizzBuzz, FizzBuzzItem};
+
+#[test]
+fn items_display_as_the_strings() {
+    let items: Vec<String> = FizzBuzz::range(-30i32..=30)
+        .step(7)
+        .items()
+        .map(|item| item.to_string())
+        .collect();
+    let strings: Vec<String> = FizzBuzz::range(-30i32..=30).step(7).collect();
+    assert_eq!(items, strings);
+    assert_eq!(
+        FizzBuzz::range(13u8..=15).items().rev().collect::<Vec<_>>(),
+        [
+            FizzBuzzItem::FizzBuzz,
+            FizzBuzzItem::Number(14),
+            FizzBuzzItem::Number(13)
+        ]
+    );
+    assert_eq!(format!("{:>4}|", FizzBuzzItem::Number(7)), "   7|");
+}
+
+#[test]
+fn write_to() {
+    let mut items = Vec::new();
+    FizzBuzz::new(1000u16).items().write_to(&mut items).unwrap();
+    let mut strings = Vec::new();
+    FizzBuzz::new(1000u16).write_to(&mut strings).unwrap();
+    let lines: String = FizzBuzz::new(1000u16).map(|s| s + "\n").collect();
+    assert_eq!(items, lines.as_bytes());
+    assert_eq!(strings, lines.as_bytes());
+}
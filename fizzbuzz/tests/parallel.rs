@@ -0,0 +1,56 @@
+use fizzbuzz::{FizzBuzz, Parallel};
+
+fn sequential(fizz_buzz: FizzBuzz<u64>) -> String {
+    let mut out = Vec::new();
+    fizz_buzz.items().write_to(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+fn parallel(parallel: Parallel) -> String {
+    let mut out = Vec::new();
+    parallel.write_to(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn same_lines_in_the_same_order() {
+    assert_eq!(
+        parallel(Parallel::new(10_000)),
+        sequential(FizzBuzz::new(10_000))
+    );
+    for threads in [1, 2, 3, 8] {
+        for chunk in [1, 7, 15, 64] {
+            assert_eq!(
+                parallel(Parallel::range(0..1005).threads(threads).chunk(chunk)),
+                sequential(FizzBuzz::range(0..1005)),
+                "{threads} threads, chunks of {chunk}"
+            );
+        }
+    }
+    assert_eq!(
+        parallel(Parallel::range(u64::MAX - 40..).threads(4).chunk(16)),
+        sequential(FizzBuzz::range(u64::MAX - 40..))
+    );
+    assert_eq!(parallel(Parallel::range(..1)), "");
+}
+
+#[test]
+fn stops_on_an_error() {
+    struct Full;
+
+    impl std::io::Write for Full {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::WriteZero.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let err = Parallel::range(..)
+        .chunk(1 << 10)
+        .write_to(Full)
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::WriteZero);
+}
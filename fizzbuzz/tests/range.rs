@@ -0,0 +1,103 @@
+use fizzbuzz::FizzBuzz;
+
+/// The classic FizzBuzz of each of `numbers`, written out.
+fn classic(numbers: impl Iterator<Item = i64>) -> Vec<String> {
+    numbers
+        .map(|i| match (i % 3, i % 5) {
+            (0, 0) => "FizzBuzz".to_string(),
+            (0, _) => "Fizz".to_string(),
+            (_, 0) => "Buzz".to_string(),
+            _ => i.to_string(),
+        })
+        .collect()
+}
+
+fn collect(fizz_buzz: FizzBuzz<i64>) -> Vec<String> {
+    fizz_buzz.collect()
+}
+
+#[test]
+fn every_kind_of_range() {
+    assert_eq!(collect(FizzBuzz::range(10..20)), classic(10..20));
+    assert_eq!(collect(FizzBuzz::range(10..=20)), classic(10..=20));
+    assert_eq!(collect(FizzBuzz::range(..=20)), classic(1..=20));
+    assert_eq!(collect(FizzBuzz::range(-20..-10)), classic(-20..-10));
+    let (start, end) = (20, 10);
+    assert_eq!(collect(FizzBuzz::range(start..end)), classic(start..end));
+    let from = (std::ops::Bound::Excluded(10), std::ops::Bound::Included(20));
+    assert_eq!(collect(FizzBuzz::range(from)), classic(11..=20));
+    assert_eq!(FizzBuzz::range(250u8..).count(), 6);
+}
+
+#[test]
+fn steps() {
+    assert_eq!(
+        collect(FizzBuzz::range(1..=100).step(7)),
+        classic((1..=100).step_by(7))
+    );
+    assert_eq!(
+        collect(FizzBuzz::range(1..=100).step(3).step(2)),
+        classic((1..=100).step_by(6))
+    );
+    let mut fizz_buzz = FizzBuzz::range(1..=100);
+    fizz_buzz.next();
+    assert_eq!(collect(fizz_buzz.step(10)), classic((2..=100).step_by(10)));
+}
+
+#[test]
+fn from_both_ends() {
+    assert_eq!(
+        collect(FizzBuzz::range(1..=30).step(4))
+            .into_iter()
+            .rev()
+            .collect::<Vec<_>>(),
+        FizzBuzz::range(1..=30).step(4).rev().collect::<Vec<_>>()
+    );
+    let mut fizz_buzz = FizzBuzz::range(1..=10i64);
+    assert_eq!(fizz_buzz.next().unwrap(), "1");
+    assert_eq!(fizz_buzz.next_back().unwrap(), "Buzz");
+    assert_eq!(fizz_buzz.nth_back(1).unwrap(), "8");
+    assert_eq!(fizz_buzz.len(), 6);
+    assert_eq!(collect(fizz_buzz), classic(2..=7));
+}
+
+#[test]
+fn exact_size_and_fused() {
+    let mut fizz_buzz = FizzBuzz::range(i8::MIN..=i8::MAX);
+    assert_eq!(fizz_buzz.len(), 256);
+    assert_eq!(fizz_buzz.next().unwrap(), "-128");
+    assert_eq!(fizz_buzz.next_back().unwrap(), "127");
+    assert_eq!(fizz_buzz.size_hint(), (254, Some(254)));
+    assert_eq!(fizz_buzz.nth(300), None);
+    assert_eq!(fizz_buzz.next(), None);
+    assert_eq!(fizz_buzz.next_back(), None);
+    assert_eq!(fizz_buzz.len(), 0);
+}
+
+#[test]
+fn nth_jumps() {
+    let mut fizz_buzz = FizzBuzz::range(1..=1_000_000);
+    assert_eq!(fizz_buzz.nth(14).unwrap(), "FizzBuzz");
+    assert_eq!(fizz_buzz.nth(1).unwrap(), "17");
+    assert_eq!(fizz_buzz.nth_back(0).unwrap(), "Buzz");
+
+    // Counting up to there would take centuries.
+    let mut everything = FizzBuzz::range(1u64..);
+    assert_eq!(everything.len(), usize::MAX);
+    assert_eq!(
+        everything.nth(usize::MAX - 2).unwrap(),
+        "18446744073709551614"
+    );
+    assert_eq!(everything.next().unwrap(), "FizzBuzz");
+    assert_eq!(everything.next(), None);
+    assert_eq!(
+        FizzBuzz::range(1u64..).step(3).last().unwrap(),
+        "18446744073709551613"
+    );
+}
+
+#[test]
+#[should_panic = "more numbers than a `usize` can count"]
+fn too_many_numbers() {
+    FizzBuzz::range(0u128..);
+}
@@ -0,0 +1,80 @@
+use fizzbuzz::{Divisible, FizzBuzz, Integer, Rules};
+
+/// The classic FizzBuzz, written out.
+fn classic(max: u32) -> Vec<String> {
+    (1..=max)
+        .map(|i| match (i % 3, i % 5) {
+            (0, 0) => "FizzBuzz".to_string(),
+            (0, _) => "Fizz".to_string(),
+            (_, 0) => "Buzz".to_string(),
+            _ => i.to_string(),
+        })
+        .collect()
+}
+
+fn collect<T: Integer>(max: T) -> Vec<String> {
+    FizzBuzz::new(max).collect()
+}
+
+#[test]
+fn classic_sequence_for_every_integer() {
+    let expected = classic(100);
+    assert_eq!(collect(100u8), expected);
+    assert_eq!(collect(100i8), expected);
+    assert_eq!(collect(100u32), expected);
+    assert_eq!(collect(100i64), expected);
+    assert_eq!(collect(100u128), expected);
+    assert_eq!(collect(100isize), expected);
+}
+
+#[test]
+fn counts_up_to_the_largest_integer() {
+    let items: Vec<String> = FizzBuzz::new(u8::MAX).collect();
+    assert_eq!(items.len(), 255);
+    assert_eq!(items[254], "FizzBuzz");
+    assert_eq!(FizzBuzz::new(0u8).count(), 0);
+}
+
+#[test]
+fn words_of_every_matching_rule_in_order() {
+    let rules = Rules::classic().divisor(7, "Bazz");
+    let items: Vec<String> = FizzBuzz::with_rules(105u32, rules).collect();
+    assert_eq!(items[6], "Bazz");
+    assert_eq!(items[20], "FizzBazz");
+    assert_eq!(items[34], "BuzzBazz");
+    assert_eq!(items[104], "FizzBuzzBazz");
+
+    let rules = Rules::new().divisor(5, "Buzz").divisor(3, "Fizz");
+    assert_eq!(
+        FizzBuzz::with_rules(15u32, rules).last().unwrap(),
+        "BuzzFizz"
+    );
+}
+
+#[test]
+fn predicate_rules() {
+    let rules = Rules::classic().predicate(|n: &u32| n.to_string().contains('7'), "Lucky");
+    let items: Vec<String> = FizzBuzz::with_rules(30u32, rules).collect();
+    assert_eq!(items[6], "Lucky");
+    assert_eq!(items[16], "Lucky");
+    assert_eq!(items[26], "FizzLucky");
+    assert_eq!(items[29], "FizzBuzz");
+
+    let none: Vec<String> = FizzBuzz::with_rules(3u32, Rules::new()).collect();
+    assert_eq!(none, ["1", "2", "3"]);
+}
+
+#[test]
+fn nothing_is_divisible_by_zero() {
+    assert!(!10u32.divisible_by(&0));
+    assert!(0u32.divisible_by(&3));
+    assert!((-9i32).divisible_by(&3));
+}
+
+#[test]
+fn everything_is_divisible_by_minus_one() {
+    let rules = Rules::new().divisor(-1i8, "Any");
+    let items: Vec<String> = FizzBuzz::range_with_rules(i8::MIN..=i8::MIN, rules).collect();
+    assert_eq!(items, ["Any"]);
+    assert!(i64::MIN.divisible_by(&-1));
+}
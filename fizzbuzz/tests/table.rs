@@ -0,0 +1,49 @@
+use fizzbuzz::{FizzBuzz, FizzBuzzItem, Rules, Table};
+
+static CLASSIC: [FizzBuzzItem<u64>; 1000] = Table::<1000>::ITEMS;
+
+#[test]
+fn same_items_as_at_run_time() {
+    let items: Vec<_> = FizzBuzz::new(1000u64).items().collect();
+    assert_eq!(CLASSIC, items[..]);
+
+    let rules = Rules::new().divisor(2, "Fizz").divisor(7, "Buzz");
+    let strings: Vec<String> = FizzBuzz::with_rules(100u64, rules).collect();
+    let table: Vec<String> = Table::<100, 2, 7>::ITEMS
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(table, strings);
+
+    assert!(Table::<10, 0, 0>::ITEMS
+        .iter()
+        .all(|item| matches!(item, FizzBuzzItem::Number(_))));
+}
+
+/// The tables are read only data of the executable, mapped without the
+/// permission to write them, rather than built when it runs.
+#[test]
+#[cfg(target_os = "linux")]
+fn in_read_only_data() {
+    fn mapping(address: usize) -> String {
+        let maps = std::fs::read_to_string("/proc/self/maps").unwrap();
+        let line = maps.lines().find(|line| {
+            let (start, end) = line.split_once(' ').unwrap().0.split_once('-').unwrap();
+            let start = usize::from_str_radix(start, 16).unwrap();
+            let end = usize::from_str_radix(end, 16).unwrap();
+            (start..end).contains(&address)
+        });
+        line.unwrap().to_string()
+    }
+
+    let executable = std::env::current_exe().unwrap();
+    let executable = executable.to_str().unwrap();
+    // A reference to a constant is promoted to a static.
+    let promoted: &'static [FizzBuzzItem<u64>; 300] = &Table::<300, 4, 6>::ITEMS;
+    for address in [CLASSIC.as_ptr() as usize, promoted.as_ptr() as usize] {
+        let mapping = mapping(address);
+        let mut fields = mapping.split_whitespace();
+        assert_eq!(fields.nth(1), Some("r--p"), "{mapping}");
+        assert_eq!(fields.last(), Some(executable), "{mapping}");
+    }
+}